@@ -0,0 +1,14 @@
+use crate::ast::Ident; 
+use crate::ast::Instance; 
+use crate::entities::Span; 
+
+/// `circuit Name(params) { instances }`, the top circuit is simulated
+#[derive(Clone)]
+pub struct Circuit {
+    pub name: String, 
+    pub name_span: Span, 
+    pub params: Vec<Ident>, 
+    pub instances: Vec<Instance>, 
+    pub docs: Vec<String>, 
+    pub span: Span, // up to the closing '}'
+}
@@ -0,0 +1,26 @@
+use crate::entities::Token; 
+use crate::entities::TokenType; 
+
+/// Text of comments of the statement, one line per line comment and 
+/// per line of the block comment. Markers ('//', '/*', '*/' and the 
+/// leading '*' of lines) and empty lines are dropped. 
+pub fn get_docs(comments: &[Token]) -> Vec<String> {
+    let mut lines: Vec<String> = vec![]; 
+    for comment in comments.iter().filter(|x| matches!(x.token_type, TokenType::Comment)) {
+        let text = comment.value.trim(); 
+        let text_lines: Vec<&str> = match text.strip_prefix("//") {
+            Option::Some(line) => vec![line], 
+            Option::None => text.trim_start_matches("/*").trim_end_matches("*/").lines().collect(), 
+        }; 
+
+        for line in text_lines {
+            let line = line.trim(); 
+            let line = line.strip_prefix('*').unwrap_or(line).trim(); 
+            if !line.is_empty() {
+                lines.push(line.to_string()); 
+            }
+        }
+    }
+
+    lines
+}
@@ -0,0 +1,16 @@
+use crate::ast::Ident; 
+use crate::ast::Instance; 
+use crate::entities::Span; 
+
+/// `element [pins] Name(params) { instances }`, the subcircuit 
+/// which is inlined into every instance of it
+#[derive(Clone)]
+pub struct ElementDef {
+    pub name: String, 
+    pub name_span: Span, 
+    pub nodes: Vec<Ident>, // pins
+    pub params: Vec<Ident>, 
+    pub instances: Vec<Instance>, 
+    pub docs: Vec<String>, 
+    pub span: Span, // up to the closing '}'
+}
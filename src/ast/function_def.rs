@@ -0,0 +1,14 @@
+use crate::ast::Ident; 
+use crate::entities::Expression; 
+use crate::entities::Span; 
+
+/// `function Name(params) => expression;`
+#[derive(Clone)]
+pub struct FunctionDef {
+    pub name: String, 
+    pub name_span: Span, 
+    pub params: Vec<Ident>, 
+    pub expression: Expression, 
+    pub docs: Vec<String>, 
+    pub span: Span, 
+}
@@ -0,0 +1,13 @@
+use crate::entities::Span; 
+
+/// Name with its span, e.g. a pin or a parameter of the declaration
+#[derive(Clone)]
+pub struct Ident {
+    pub name: String, 
+    pub span: Span, 
+}
+
+/// Names of the identifiers, e.g. of pins 
+pub fn get_names(idents: &[Ident]) -> Vec<String> {
+    idents.iter().map(|x| x.name.clone()).collect()
+}
@@ -0,0 +1,10 @@
+use crate::entities::Span; 
+
+/// `import packageName;`
+#[derive(Clone)]
+pub struct Import {
+    pub package_name: String, 
+    pub name_span: Span, 
+    pub docs: Vec<String>, 
+    pub span: Span, 
+}
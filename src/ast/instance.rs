@@ -0,0 +1,16 @@
+use crate::entities::Expression; 
+use crate::entities::Span; 
+use crate::ast::Ident; 
+
+/// `R1 [nodes] = Resistor(params);` inside a circuit or an element
+#[derive(Clone)]
+pub struct Instance {
+    pub entry_name: String, // variable name 
+    pub name_span: Span, 
+    pub element_name: String, // type name
+    pub element_name_span: Span, 
+    pub nodes: Vec<Ident>, // names or numbers of nets
+    pub param_expressions: Vec<Expression>, 
+    pub docs: Vec<String>, 
+    pub span: Span, // up to ';'
+}
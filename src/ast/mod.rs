@@ -0,0 +1,163 @@
+mod ident; 
+mod docs; 
+mod circuit; 
+mod element_def; 
+mod instance; 
+mod function_def; 
+mod import; 
+mod package; 
+mod primitive_def; 
+
+pub use ident::Ident; 
+pub use ident::get_names; 
+pub use circuit::Circuit; 
+pub use element_def::ElementDef; 
+pub use instance::Instance; 
+pub use function_def::FunctionDef; 
+pub use import::Import; 
+pub use package::Package; 
+pub use primitive_def::PrimitiveDef; 
+pub(crate) use docs::get_docs; 
+
+// Expression nodes live with the evaluator 
+pub use crate::entities::Expression; 
+pub use crate::entities::ExpressionNode; 
+pub use crate::entities::OperatorNode; 
+pub use crate::entities::Span; 
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::Command; 
+    use crate::entities::ErrorEntry; 
+    use crate::packages::parse_source; 
+    use super::*; 
+
+    fn parse(code: &str) -> Vec<Command> {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source(code.to_string(), 0, &mut errs); 
+        assert!(errs.is_empty(), "{:?}", errs.iter().map(|x| x.get_message()).collect::<Vec<_>>()); 
+        commands
+    }
+
+    fn text<'a>(code: &'a str, span: &Span) -> &'a str {
+        &code[span.start..span.end]
+    }
+
+    #[test]
+    fn spans_cover_whole_nodes() {
+        let code = "function half(x) => x / 2;\n\ncircuit Main(U)\n{\n    R1[1, 0] = Resistor(half(U));\n}\n"; 
+        let commands = parse(code); 
+
+        let function = match &commands[0] {
+            Command::Function(c) => c, 
+            _ => panic!("the function is expected"), 
+        }; 
+        assert_eq!(text(code, &function.span), "function half(x) => x / 2;"); 
+        assert_eq!(text(code, &function.name_span), "half"); 
+        assert_eq!(text(code, &function.params[0].span), "x"); 
+        assert_eq!(text(code, &function.expression.span), "x / 2"); 
+
+        let circuit = match &commands[1] {
+            Command::Circle(c) => c, 
+            _ => panic!("the circuit is expected"), 
+        }; 
+        assert_eq!(text(code, &circuit.span), &code[code.find("circuit").unwrap()..code.len() - 1]); 
+        assert_eq!(text(code, &circuit.name_span), "Main"); 
+        assert_eq!(text(code, &circuit.params[0].span), "U"); 
+        assert_eq!((circuit.span.position.line, circuit.span.end_position.line), (2, 5)); 
+
+        let instance = &circuit.instances[0]; 
+        assert_eq!(text(code, &instance.span), "R1[1, 0] = Resistor(half(U));"); 
+        assert_eq!(text(code, &instance.name_span), "R1"); 
+        assert_eq!(text(code, &instance.element_name_span), "Resistor"); 
+        assert_eq!(text(code, &instance.nodes[1].span), "0"); 
+        assert_eq!((instance.span.position.line, instance.span.position.line_position), (4, 4)); 
+        assert_eq!((instance.span.end_position.line, instance.span.end_position.line_position), (4, 33)); 
+
+        let call = match instance.param_expressions[0].root() {
+            Option::Some(ExpressionNode::Operator(x)) => x, 
+            _ => panic!("the call is expected"), 
+        }; 
+        assert_eq!(text(code, &call.span), "half(U)"); 
+        assert_eq!(text(code, &call.name_span), "half"); 
+        assert_eq!(text(code, call.nodes[0].span()), "U"); 
+    }
+
+    #[test]
+    fn spans_of_declarations() {
+        let code = "package main;\nimport std;\nprimitive [A, K] Diode(IS, N);\nelement [In, Out] Load(R)\n{\n    R1[In, Out] = Resistor(R);\n}\n"; 
+        let commands = parse(code); 
+
+        match &commands[0] {
+            Command::Package(c) => {
+                assert_eq!(text(code, &c.span), "package main;"); 
+                assert_eq!(text(code, &c.name_span), "main"); 
+            }, 
+            _ => panic!("the package is expected"), 
+        }
+
+        match &commands[1] {
+            Command::Import(c) => {
+                assert_eq!(text(code, &c.span), "import std;"); 
+                assert_eq!(text(code, &c.name_span), "std"); 
+            }, 
+            _ => panic!("the import is expected"), 
+        }
+
+        match &commands[2] {
+            Command::Primitive(c) => {
+                assert_eq!(text(code, &c.span), "primitive [A, K] Diode(IS, N);"); 
+                assert_eq!(text(code, &c.name_span), "Diode"); 
+                assert_eq!(get_names(&c.nodes), ["A", "K"]); 
+                assert_eq!(text(code, &c.params[1].span), "N"); 
+            }, 
+            _ => panic!("the primitive is expected"), 
+        }
+
+        match &commands[3] {
+            Command::Element(c) => {
+                assert_eq!(text(code, &c.span), &code[code.find("element").unwrap()..code.len() - 1]); 
+                assert_eq!(text(code, &c.name_span), "Load"); 
+                assert_eq!(text(code, &c.nodes[1].span), "Out"); 
+                assert_eq!(text(code, &c.instances[0].span), "R1[In, Out] = Resistor(R);"); 
+            }, 
+            _ => panic!("the element is expected"), 
+        }
+    }
+
+    #[test]
+    fn spans_point_to_the_file() {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source("circuit Main() { R1[1, 0] = Resistor(1); }".to_string(), 3, &mut errs); 
+        let circuit = match &commands[0] {
+            Command::Circle(c) => c, 
+            _ => panic!("the circuit is expected"), 
+        }; 
+
+        assert_eq!(circuit.span.file_id, 3); 
+        assert_eq!(circuit.instances[0].span.file_id, 3); 
+        assert_eq!(circuit.instances[0].param_expressions[0].span.file_id, 3); 
+    }
+
+    #[test]
+    fn comments_are_docs_of_nodes() {
+        let code = "// Main circuit\ncircuit Main()\n{\n    /* The load\n     * of 1k */\n    R1[1, 0] = Resistor(1k);\n}\n// The half\nfunction half(x) => x / 2;\n"; 
+        let commands = parse(code); 
+        let circuit = match &commands[0] {
+            Command::Circle(c) => c, 
+            _ => panic!("the circuit is expected"), 
+        }; 
+
+        assert_eq!(circuit.docs, ["Main circuit"]); 
+        assert_eq!(circuit.instances[0].docs, ["The load", "of 1k"]); 
+        assert!(matches!(&commands[1], Command::Function(c) if c.docs == ["The half"])); 
+    }
+
+    #[test]
+    fn block_comments_are_split_at_line_breaks() {
+        let code = "/* First line\n   second line without a star\n * a  * b\n */\ncircuit Main() { R1[1, 0] = Resistor(1); }\n/** One line */\nfunction f() => 1;\n"; 
+        let commands = parse(code); 
+        assert!(matches!(&commands[0], Command::Circle(c) if c.docs == ["First line", "second line without a star", "a  * b"])); 
+        assert!(matches!(&commands[1], Command::Function(c) if c.docs == ["One line"])); 
+    }
+}
@@ -0,0 +1,10 @@
+use crate::entities::Span; 
+
+/// `package packageName;` names the package declared by the file
+#[derive(Clone)]
+pub struct Package {
+    pub name: String, 
+    pub name_span: Span, 
+    pub docs: Vec<String>, 
+    pub span: Span, 
+}
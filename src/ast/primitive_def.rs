@@ -0,0 +1,14 @@
+use crate::ast::Ident; 
+use crate::entities::Span; 
+
+/// Declaration of the element which is simulated by the built-in model, 
+/// e.g. `primitive [P, N] Resistor(R);`
+#[derive(Clone)]
+pub struct PrimitiveDef {
+    pub name: String, 
+    pub name_span: Span, 
+    pub nodes: Vec<Ident>, 
+    pub params: Vec<Ident>, 
+    pub docs: Vec<String>, 
+    pub span: Span, 
+}
@@ -6,21 +6,15 @@ use crate::commands::is_first_token_specific_word;
 use crate::commands::variant_eq; 
 use crate::entities::TokenType; 
 use crate::commands::get_args; 
-use crate::commands::ElementEntryCommand; 
+use crate::ast::Circuit; 
+use crate::ast::Instance; 
+use crate::ast::get_docs; 
 use crate::commands::ElementEntryCommandRecognizer; 
 use crate::commands::get_commands; 
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 use crate::keywords::is_keyword;
 
-#[derive(Clone)]
-pub struct CircleCommand {
-    pub name: String, 
-    pub param_names: Vec<String>, 
-    pub statement: Statement, 
-    pub element_entries: Vec<ElementEntryCommand>, 
-}
-
 pub struct CircleCommandRecognizer{}
 impl CommandRecognizer for CircleCommandRecognizer {
     fn from_statement(&self, statement: &Statement, errs_acc: &mut Vec<ErrorEntry>) -> Command {
@@ -38,11 +32,13 @@ impl CommandRecognizer for CircleCommandRecognizer {
                 &init_token.position, 
                 &vec![format!("{} <CIRCLE_NAME>(<ARGS_LIST>) {{ ... }} ", keyword::PACKAGE)])); 
 
-            return Command::Circle(CircleCommand{
-                name: name, 
-                param_names: vec![], 
-                element_entries: vec![], 
-                statement: statement.clone(), 
+            return Command::Circle(Circuit{
+                name, 
+                name_span: init_token.span.clone(), 
+                params: vec![], 
+                instances: vec![], 
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
             });
         }
 
@@ -56,13 +52,13 @@ impl CommandRecognizer for CircleCommandRecognizer {
                         &vec![name_token.value.clone()])); 
                 }
 
-                name_token.value
+                name_token.value.clone()
             }, 
             _ => {
                 errs_acc.push(ErrorEntry::new(
                     errors::INVALID_NAME, 
                     &name_token.position, 
-                    &vec![name_token.value]));
+                    &vec![name_token.value.clone()]));
 
                 name 
             }
@@ -86,7 +82,7 @@ impl CommandRecognizer for CircleCommandRecognizer {
 
         let start = 3;
         let end = len - 1; 
-        let params = get_args(tokens, start, end, false); 
+        let params = get_args(tokens, start, end, false, errs_acc); 
         if statement.substatements.len() == 0 {
             errs_acc.push(ErrorEntry::new(
                 errors::NO_SUBSTATEMENTS, 
@@ -94,7 +90,7 @@ impl CommandRecognizer for CircleCommandRecognizer {
                 &vec![])); 
         }
 
-        let mut element_entries: Vec<ElementEntryCommand> = vec![]; 
+        let mut instances: Vec<Instance> = vec![]; 
         let recognizers: Vec<&dyn CommandRecognizer> = vec![
             &ElementEntryCommandRecognizer{},
         ];
@@ -103,7 +99,7 @@ impl CommandRecognizer for CircleCommandRecognizer {
         for cmd_entry in &cmd_entries {
             match cmd_entry {
                 Command::ElementEntry(c) => {
-                    element_entries.push(c.clone()); 
+                    instances.push(c.clone()); 
                 },  
                 _ => {
                     // Adding error not nesessary because 
@@ -112,11 +108,13 @@ impl CommandRecognizer for CircleCommandRecognizer {
             }
         }
 
-        Command::Circle(CircleCommand{
-            name: name, 
-            param_names: params, 
-            element_entries: element_entries, 
-            statement: statement.clone(), 
+        Command::Circle(Circuit{
+            name, 
+            name_span: name_token.span, 
+            params, 
+            instances, 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
         })
     }
 }
\ No newline at end of file
@@ -1,16 +1,20 @@
-use crate::commands::CircleCommand;
-use crate::commands::ElementCommand;
-use crate::commands::ElementEntryCommand;
-use crate::commands::ImportCommand;
-use crate::commands::PackageCommand;
-use crate::commands::FunctionCommand; 
+use crate::ast::Circuit;
+use crate::ast::ElementDef;
+use crate::ast::Instance;
+use crate::ast::Import;
+use crate::ast::Package;
+use crate::ast::FunctionDef; 
+use crate::ast::PrimitiveDef; 
 
+/// Top-level statement (or the instance inside the block) recognized 
+/// as the node of the AST 
 pub enum Command {
     None, 
-    Circle(CircleCommand), 
-    Element(ElementCommand),
-    ElementEntry(ElementEntryCommand),
-    Import(ImportCommand),
-    Package(PackageCommand),
-    Function(FunctionCommand),
+    Circle(Circuit), 
+    Element(ElementDef),
+    ElementEntry(Instance),
+    Import(Import),
+    Package(Package),
+    Function(FunctionDef),
+    Primitive(PrimitiveDef),
 }
\ No newline at end of file
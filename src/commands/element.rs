@@ -1,7 +1,9 @@
 use crate::commands::Command;
 use crate::entities::Statement; 
 use crate::interfaces::CommandRecognizer; 
-use crate::commands::ElementEntryCommand; 
+use crate::ast::ElementDef; 
+use crate::ast::Instance; 
+use crate::ast::get_docs; 
 use crate::keywords::keyword; 
 use crate::commands::is_first_token_specific_word; 
 use crate::commands::variant_eq; 
@@ -14,15 +16,6 @@ use crate::entities::ErrorEntry;
 use crate::entities::errors;
 use crate::keywords::is_keyword;
 
-#[derive(Clone)]
-pub struct ElementCommand {
-    pub name: String, 
-    pub param_names: Vec<String>,
-    pub nodes: Vec<String>, 
-    pub element_entries: Vec<ElementEntryCommand>, 
-    pub statement: Statement, 
-}
-
 pub struct ElementCommandRecognizer{}
 impl CommandRecognizer for ElementCommandRecognizer {
     fn from_statement(&self, statement: &Statement, errs_acc: &mut Vec<ErrorEntry>) -> Command {
@@ -40,12 +33,14 @@ impl CommandRecognizer for ElementCommandRecognizer {
                 &init_token.position, 
                 &vec![format!("{} [node1, node2, ...] ElementName(arg1, arg2, ...) {{ ... }} ", keyword::ELEMENT)])); 
 
-            return Command::Element(ElementCommand{
-                name: name, 
+            return Command::Element(ElementDef{
+                name, 
+                name_span: init_token.span.clone(), 
                 nodes: vec![], 
-                param_names: vec![], 
-                element_entries: vec![], 
-                statement: statement.clone(), 
+                params: vec![], 
+                instances: vec![], 
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
             });
         }
 
@@ -71,14 +66,24 @@ impl CommandRecognizer for ElementCommandRecognizer {
             }
         }
 
-        let nodes = get_args(tokens, start, end, false); 
+        let nodes = get_args(tokens, start, end, false, errs_acc); 
 
         // 3 because name, '(' and ')'
-        if end > len - 3 {
+        if end == 0 || end > len - 3 {
             errs_acc.push(ErrorEntry::new(
                 errors::WROND_COMMAND_SEMANTIC, 
                 &init_token.position, 
                 &vec![format!("{} [node1, node2, ...] ElementName(arg1, arg2, ...) {{ ... }} ", keyword::ELEMENT)])); 
+
+            return Command::Element(ElementDef{
+                name, 
+                name_span: init_token.span.clone(), 
+                nodes, 
+                params: vec![], 
+                instances: vec![], 
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
+            });
         }
 
         let name_index = end + 1;
@@ -92,13 +97,13 @@ impl CommandRecognizer for ElementCommandRecognizer {
                         &vec![name_token.value.clone()])); 
                 }
 
-                name_token.value
+                name_token.value.clone()
             }, 
             _ => {
                 errs_acc.push(ErrorEntry::new(
                     errors::INVALID_NAME, 
                     &name_token.position, 
-                    &vec![name_token.value]));
+                    &vec![name_token.value.clone()]));
 
                 name 
             }
@@ -123,7 +128,7 @@ impl CommandRecognizer for ElementCommandRecognizer {
         }
 
         let start = name_index + 2;
-        let params = get_args(tokens, start, end, false); 
+        let params = get_args(tokens, start, end, false, errs_acc); 
 
         if statement.substatements.len() == 0 {
             errs_acc.push(ErrorEntry::new(
@@ -132,7 +137,7 @@ impl CommandRecognizer for ElementCommandRecognizer {
                 &vec![])); 
         }
 
-        let mut element_entries: Vec<ElementEntryCommand> = vec![]; 
+        let mut instances: Vec<Instance> = vec![]; 
         let recognizers: Vec<&dyn CommandRecognizer> = vec![
             &ElementEntryCommandRecognizer{},
         ];
@@ -141,7 +146,7 @@ impl CommandRecognizer for ElementCommandRecognizer {
         for cmd_entry in &cmd_entries {
             match cmd_entry {
                 Command::ElementEntry(c) => {
-                    element_entries.push(c.clone()); 
+                    instances.push(c.clone()); 
                 },  
                 _ => {
                     // Adding error not nesessary because 
@@ -150,12 +155,14 @@ impl CommandRecognizer for ElementCommandRecognizer {
             }
         }
 
-        Command::Element(ElementCommand{
-            name: name, 
-            nodes: nodes, 
-            param_names: params, 
-            element_entries: element_entries, 
-            statement: statement.clone(), 
+        Command::Element(ElementDef{
+            name, 
+            name_span: name_token.span, 
+            nodes, 
+            params, 
+            instances, 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
         })
     }
 }
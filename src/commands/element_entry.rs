@@ -1,4 +1,6 @@
 use crate::commands::Command;
+use crate::ast::Instance; 
+use crate::ast::get_docs; 
 use crate::entities::Statement;
 use crate::entities::Expression;  
 use crate::interfaces::CommandRecognizer; 
@@ -11,42 +13,42 @@ use crate::entities::get_expression;
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 use crate::keywords::is_keyword;
-
-#[derive(Clone)]
-pub struct ElementEntryCommand {
-    pub entry_name: String, // variable name 
-    pub element_name: String, // type name
-    pub nodes: Vec<String>, 
-    pub param_expressions: Vec<Expression>,
-    pub statement: Statement,
-}
+use crate::entities::Token; 
+use crate::entities::Position; 
 
 pub struct ElementEntryCommandRecognizer{}
 impl CommandRecognizer for ElementEntryCommandRecognizer {
     fn from_statement(&self, statement: &Statement, errs_acc: &mut Vec<ErrorEntry>) -> Command {
         let tokens = &statement.tokens;
-        match find_token(tokens, "=", TokenType::Symbol) {
-            Option::Some(_) => { }
-            Option::None => {
-                return Command::None; 
-            } 
-        };
+        // 'R1[1, 2] Resistor(1)' is the entry which misses '='
+        let is_entry_start = match (tokens.first(), tokens.get(1)) {
+            (Option::Some(name), Option::Some(opened)) => 
+                variant_eq(&TokenType::Word, &name.token_type) && !is_keyword(&name.value) && 
+                variant_eq(&TokenType::Symbol, &opened.token_type) && opened.value == "[", 
+            _ => false, 
+        }; 
+
+        if find_token(tokens, "=", TokenType::Symbol).is_none() && !is_entry_start {
+            return Command::None; 
+        }
 
         let len = tokens.len(); 
         let init_token = tokens[0].clone(); 
         if len < 7 { // a [ ] = T ( )
-            // TODO: Add error (invalid command format)
             errs_acc.push(ErrorEntry::new(
                 errors::WROND_COMMAND_SEMANTIC, 
                 &init_token.position, 
                 &vec![format!("elemName [node1, node2, ...] = ElementName(arg1, arg2, ...); ")])); 
 
-            return Command::ElementEntry(ElementEntryCommand{
-                entry_name: format!(""),
-                element_name: format!(""),  
+            return Command::ElementEntry(Instance{
+                entry_name: String::new(),
+                name_span: init_token.span.clone(), 
+                element_name: String::new(),  
+                element_name_span: init_token.span.clone(), 
                 nodes: vec![], 
                 param_expressions: vec![], 
-                statement: statement.clone(), 
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
             });
         }
 
@@ -81,25 +83,44 @@ impl CommandRecognizer for ElementEntryCommandRecognizer {
             }
         }
 
-        let nodes = get_args(tokens, start, end, true); 
+        let nodes = get_args(tokens, start, end, true, errs_acc); 
 
         // 4 because  '=', element_name, '(' and ')'
-        if end > len - 4 {
+        if end == 0 || end > len - 4 {
             errs_acc.push(ErrorEntry::new(
                 errors::WROND_COMMAND_SEMANTIC, 
                 &init_token.position, 
                 &vec![format!("elemName [node1, node2, ...] = ElementName(arg1, arg2, ...); ")])); 
+
+            return Command::ElementEntry(Instance{
+                entry_name,
+                name_span: entry_name_token.span, 
+                element_name: String::new(),  
+                element_name_span: init_token.span.clone(), 
+                nodes, 
+                param_expressions: vec![], 
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
+            });
         }
 
         let eq_index = end + 1;
         let eq_token = tokens[eq_index].clone(); 
-        if !(variant_eq(&TokenType::Symbol, &eq_token.token_type) && eq_token.value == "=") {
-            // TODO: Add error 
+        let has_assignment = variant_eq(&TokenType::Symbol, &eq_token.token_type) && eq_token.value == "="; 
+        if !has_assignment {
+            errs_acc.push(ErrorEntry::new(
+                errors::MISSING_ASSIGNMENT, 
+                &eq_token.position, 
+                &vec![entry_name.clone(), eq_token.value.clone()])); 
         }
 
-        let name_index = end + 2;
+        // Without '=' the token after nodes is taken as the element name
+        let name_index = match has_assignment {
+            true => end + 2, 
+            false => end + 1, 
+        };
         let name_token = tokens[name_index].clone(); 
-        let mut element_name = format!(""); 
+        let mut element_name = String::new(); 
         element_name = match name_token.token_type {
             TokenType::Word => {
                 if is_keyword(&name_token.value) {
@@ -109,13 +130,13 @@ impl CommandRecognizer for ElementEntryCommandRecognizer {
                         &vec![name_token.value.clone()])); 
                 }
 
-                name_token.value
+                name_token.value.clone()
             }, 
             _ => {
                 errs_acc.push(ErrorEntry::new(
                     errors::INVALID_NAME, 
                     &name_token.position, 
-                    &vec![name_token.value]));
+                    &vec![name_token.value.clone()]));
 
                 element_name 
             }
@@ -140,27 +161,67 @@ impl CommandRecognizer for ElementEntryCommandRecognizer {
         }
 
         let start = name_index + 2;
-        let param_expressions_tokens = get_expressions_tokens(tokens, start, end); 
+        let errors_count = errs_acc.len(); 
+        let param_expressions_tokens = get_expressions_tokens(tokens, start, end, errs_acc); 
         let mut param_expressions: Vec<Expression> = vec![]; 
 
-        for tokens in &param_expressions_tokens {
-            let op_expr = get_expression(tokens, errs_acc); 
-            match op_expr {
-                Option::Some(expr) => {
-                    param_expressions.push(expr); 
-                },
-                Option::None => {
-                    // TODO: Add error 
-                }
+        // Expressions with unbalanced brackets would repeat the error 
+        if errs_acc.len() > errors_count {
+            return Command::ElementEntry(Instance{
+                entry_name,
+                name_span: entry_name_token.span, 
+                element_name,  
+                element_name_span: name_token.span, 
+                nodes, 
+                param_expressions, 
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
+            });
+        }
+
+        for (i, tokens) in param_expressions_tokens.iter().enumerate() {
+            // The expression parser reports other failed expressions 
+            if tokens.is_empty() {
+                errs_acc.push(ErrorEntry::new(
+                    errors::EMPTY_PARAMETER, 
+                    &get_separator_position(&statement.tokens, start, end, i), 
+                    &vec![(i + 1).to_string(), element_name.clone()])); 
+
+                continue; 
+            }
+
+            if let Option::Some(expr) = get_expression(tokens, errs_acc) {
+                param_expressions.push(expr); 
             }
         }
 
-        Command::ElementEntry(ElementEntryCommand{
-            entry_name: entry_name,
-            element_name: element_name,  
-            nodes: nodes, 
-            param_expressions: param_expressions, 
-            statement: statement.clone(), 
+        Command::ElementEntry(Instance{
+            entry_name,
+            name_span: entry_name_token.span, 
+            element_name,  
+            element_name_span: name_token.span, 
+            nodes, 
+            param_expressions, 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
         })
     }
 }
+
+/// Position of the comma (or the closing bracket) after the parameter 
+/// with the index, brackets are balanced 
+fn get_separator_position(tokens: &[Token], start: usize, end: usize, index: usize) -> Position {
+    let mut level = 0; 
+    let mut separators: Vec<&Token> = vec![]; 
+    for token in tokens.iter().take(end).skip(start) {
+        match token.value.as_str() {
+            "(" => level += 1, 
+            ")" => level -= 1, 
+            "," if level == 0 => separators.push(token), 
+            _ => { }, 
+        }
+    }
+
+    separators.push(&tokens[end]); 
+    separators[index.min(separators.len() - 1)].position.clone()
+}
@@ -1,4 +1,6 @@
 use crate::commands::Command;
+use crate::ast::FunctionDef; 
+use crate::ast::get_docs; 
 use crate::interfaces::CommandRecognizer;
 use crate::entities::Statement; 
 use crate::keywords::keyword;
@@ -6,22 +8,16 @@ use crate::commands::is_first_token_specific_word;
 use crate::commands::variant_eq; 
 use crate::entities::TokenType; 
 use crate::commands::get_args; 
-use crate::entities::Expression; 
 use crate::entities::get_expression;
 use crate::commands::find_token; 
 use crate::entities::Token;
 use crate::entities::Position; 
+use crate::entities::Span; 
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 use crate::keywords::is_keyword;
-
-#[derive(Clone)]
-pub struct FunctionCommand {
-    pub name: String, 
-    pub param_names: Vec<String>, 
-    pub statement: Statement, 
-    pub expression: Expression,
-}
+use crate::entities::FunctionEnvironment; 
+use crate::entities::UserFunction; 
 
 pub struct FunctionCommandRecognizer{}
 impl CommandRecognizer for FunctionCommandRecognizer {
@@ -35,8 +31,10 @@ impl CommandRecognizer for FunctionCommandRecognizer {
         let len = tokens.len(); 
         let null_exp = get_expression(&vec![Token{
             token_type: TokenType::Number, 
+            unit: Option::None, 
             value: format!("0"),
-            position: Position{ line: 0, line_position: 0 }
+            position: Position{ line: 0, line_position: 0 }, 
+            span: Span::default(), 
         }], &mut vec![]).unwrap(); 
 
         let init_token = tokens[0].clone(); 
@@ -46,11 +44,13 @@ impl CommandRecognizer for FunctionCommandRecognizer {
                 &init_token.position, 
                 &vec![format!("{} FunctionName(arg1, arg2, ...) => arg1 + arg2; ", keyword::FUNCTION)])); 
 
-            return Command::Function(FunctionCommand{
-                name: name, 
-                param_names: vec![], 
-                statement: statement.clone(), 
+            return Command::Function(FunctionDef{
+                name, 
+                name_span: init_token.span.clone(), 
+                params: vec![], 
                 expression: null_exp,
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
             });
         }
 
@@ -64,13 +64,13 @@ impl CommandRecognizer for FunctionCommandRecognizer {
                         &vec![name_token.value.clone()])); 
                 }
 
-                name_token.value
+                name_token.value.clone()
             }, 
             _ => {
                 errs_acc.push(ErrorEntry::new(
                     errors::INVALID_NAME, 
                     &name_token.position, 
-                    &vec![name_token.value]));
+                    &vec![name_token.value.clone()]));
 
                 name 
             }
@@ -91,11 +91,13 @@ impl CommandRecognizer for FunctionCommandRecognizer {
         }
 
         if end == 0 {
-            return Command::Function(FunctionCommand{
-                name: name, 
-                param_names: vec![], 
-                statement: statement.clone(), 
+            return Command::Function(FunctionDef{
+                name, 
+                name_span: name_token.span, 
+                params: vec![], 
                 expression: null_exp,
+                docs: get_docs(&statement.comments), 
+                span: statement.span.clone(), 
             });
         }
 
@@ -107,7 +109,7 @@ impl CommandRecognizer for FunctionCommandRecognizer {
         }
 
         let start = 3;
-        let params = get_args(tokens, start, end, false); 
+        let params = get_args(tokens, start, end, false, errs_acc); 
         if statement.substatements.len() != 0 {
             errs_acc.push(ErrorEntry::new(
                 errors::ARE_SUBSTATEMENTS, 
@@ -116,7 +118,24 @@ impl CommandRecognizer for FunctionCommandRecognizer {
         }
 
         let rocket_sign_index = end + 1; 
-        let rocket_sign_token = tokens[rocket_sign_index].clone(); 
+        let rocket_sign_token = match tokens.get(rocket_sign_index) {
+            Option::Some(token) => token.clone(), 
+            Option::None => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::SYMBOL_NOT_FOUND, 
+                    &tokens[end].position, 
+                    &vec![format!("=>")]));
+
+                return Command::Function(FunctionDef{
+                    name, 
+                    name_span: name_token.span, 
+                    params, 
+                    expression: null_exp,
+                    docs: get_docs(&statement.comments), 
+                    span: statement.span.clone(), 
+                });
+            }
+        }; 
         if !(variant_eq(&TokenType::Symbol, &rocket_sign_token.token_type) && rocket_sign_token.value == "=>") {
             errs_acc.push(ErrorEntry::new(
                 errors::UNEXPECTED_SYMBOL, 
@@ -134,11 +153,32 @@ impl CommandRecognizer for FunctionCommandRecognizer {
             Option::None => null_exp,
         }; 
 
-        Command::Function(FunctionCommand{
-            name: name, 
-            param_names: params, 
+        Command::Function(FunctionDef{
+            name, 
+            name_span: name_token.span, 
+            params, 
             expression: expr, 
-            statement: statement.clone(), 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
         })
     }
-}
\ No newline at end of file
+}
+
+/// Collects all `function` declarations of the file into the environment 
+/// used by `Expression::evaluate`. Environments of imported packages 
+/// should be merged with the environment of the main file. 
+pub fn get_function_environment(commands: &Vec<Command>, file_name: &str) -> FunctionEnvironment {
+    let mut functions = FunctionEnvironment::new(); 
+    for command in commands {
+        if let Command::Function(c) = command {
+            functions.add(UserFunction {
+                name: c.name.clone(), 
+                param_names: c.params.iter().map(|x| x.name.clone()).collect(), 
+                expression: c.expression.clone(), 
+                file_name: file_name.to_string(), 
+            }); 
+        }
+    }
+
+    functions
+}
@@ -1,3 +1,4 @@
+use crate::ast::Ident; 
 use crate::entities::Token; 
 use crate::entities::TokenType; 
 use crate::interfaces::CommandRecognizer;
@@ -6,90 +7,105 @@ use crate::commands::Command;
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 
-pub fn is_first_token_specific_word(tokens: &Vec<Token>, word: &str) -> bool {
+pub fn is_first_token_specific_word(tokens: &[Token], word: &str) -> bool {
     match tokens.first() {
         Option::None => false,
-        Option::Some(token) => token.value == word.to_string() && 
+        Option::Some(token) => token.value == word && 
                                variant_eq(&token.token_type, &TokenType::Word),
     }
 }
 
-pub fn find_token(tokens: &Vec<Token>, word: &str, token_type: TokenType) -> Option<usize> {
-    let mut i: usize = 0; 
-    
-    for token in tokens {
-        if token.value == word.to_string() && variant_eq(&token.token_type, &token_type) {
+pub fn find_token(tokens: &[Token], word: &str, token_type: TokenType) -> Option<usize> {
+    for (i, token) in tokens.iter().enumerate() {
+        if token.value == word && variant_eq(&token.token_type, &token_type) {
             return Option::Some(i); 
         }
-
-        i += 1;
     }
 
-    return Option::None; 
+    Option::None
 }
 
 pub fn variant_eq<T>(a: &T, b: &T) -> bool {
     std::mem::discriminant(a) == std::mem::discriminant(b)
 }
 
-pub fn get_args(tokens: &Vec<Token>, start: usize, end: usize, allow_nums: bool) -> Vec<String> {
-    let mut cntr: usize = 0; 
-    let mut args: Vec<String> = vec![]; 
+/// Names (and numbers if `allow_nums`) separated by commas in tokens 
+/// from `start` up to `end`, which is the closing bracket. After 
+/// a missing comma the token is still taken as the next argument. 
+/// The trailing comma is allowed, e.g. 'circuit Main(a, b,)'. 
+pub fn get_args(tokens: &[Token], start: usize, end: usize, allow_nums: bool, errs_acc: &mut Vec<ErrorEntry>) -> Vec<Ident> {
+    let expected = match allow_nums {
+        true => "name or number".to_string(), 
+        false => "name".to_string(), 
+    }; 
+
+    let mut args: Vec<Ident> = vec![]; 
+    let mut expect_arg = true; 
+    for token in tokens.iter().take(end).skip(start).cloned() {
+        let is_comma = variant_eq(&TokenType::Symbol, &token.token_type) && token.value == ","; 
+        let is_arg = variant_eq(&TokenType::Word, &token.token_type) || 
+            (variant_eq(&TokenType::Number, &token.token_type) && allow_nums); 
 
-    for i in start..end {
-        let token = tokens[i].clone();
-        match cntr % 2 {
-            0 => {
-                if variant_eq(&TokenType::Word, &token.token_type) {
-                    args.push(token.value); 
-                }
-                else if variant_eq(&TokenType::Number, &token.token_type) && allow_nums {
-                    args.push(token.value); 
-                }
-                else {
-                    // TODO: Add error 
-                }
-            },
-            1 => {
-                if !(variant_eq(&TokenType::Symbol, &token.token_type) && token.value == ",") {
-                    // TODO: Add error 
-                }
-            },
-            _ => { } // this code is unreachable
-        } 
+        if !expect_arg {
+            if is_comma {
+                expect_arg = true; 
+                continue; 
+            }
+
+            errs_acc.push(ErrorEntry::new(
+                errors::EXPECTED_COMMA, 
+                &token.position, 
+                &vec![token.value.clone()])); 
+
+            if is_arg {
+                args.push(Ident { name: token.value, span: token.span }); 
+            }
 
-        cntr += 1; 
+            continue; 
+        }
+
+        if is_arg {
+            args.push(Ident { name: token.value, span: token.span }); 
+        }
+        else {
+            errs_acc.push(ErrorEntry::new(
+                errors::EXPECTED_ARGUMENT, 
+                &token.position, 
+                &vec![expected.clone(), token.value.clone()])); 
+        }
+
+        expect_arg = is_comma; 
     }
 
     args
 }
 
-pub fn get_expressions_tokens(tokens: &Vec<Token>, start: usize, end: usize) -> Vec<Vec<Token>> {
+/// Splits tokens from `start` up to `end` into expressions at commas 
+/// outside brackets. Unbalanced brackets are reported. 
+pub fn get_expressions_tokens(tokens: &[Token], start: usize, end: usize, errs_acc: &mut Vec<ErrorEntry>) -> Vec<Vec<Token>> {
     let mut expressions: Vec<Vec<Token>> = vec![]; 
     let mut current_exp: Vec<Token> = vec![]; 
-    let mut level = 0; 
+    let mut opened: Vec<Token> = vec![]; 
 
-    for i in start..end {
-        let token = tokens[i].clone();
+    for token in tokens.iter().take(end).skip(start).cloned() {
         let is_comma = variant_eq(&TokenType::Symbol, &token.token_type) && token.value == ","; 
 
         match is_comma {
-            true if level == 0 => {
+            true if opened.is_empty() => {
                 expressions.push(current_exp.clone());
                 current_exp = vec![];  
             },
             _ => {
                 if variant_eq(&TokenType::Symbol, &token.token_type) {
                     if token.value == "(" {
-                        level += 1; 
+                        opened.push(token.clone()); 
                     }
 
-                    if token.value == ")" {
-                        if level == 0 {
-                            // TODO: Add error 
-                        }
-
-                        level -= 1; 
+                    if token.value == ")" && opened.pop().is_none() {
+                        errs_acc.push(ErrorEntry::new(
+                            errors::UNMATCHED_CLOSED_BRACKET, 
+                            &token.position, 
+                            &vec![])); 
                     }
                 }
 
@@ -98,17 +114,26 @@ pub fn get_expressions_tokens(tokens: &Vec<Token>, start: usize, end: usize) ->
         }
     }
 
-    if current_exp.len() > 0 {
+    // The last expression is empty after the trailing comma 
+    if !current_exp.is_empty() || !expressions.is_empty() {
         expressions.push(current_exp.clone()); 
     }
 
-    if level != 0 {
-        // TODO: Add error 
+    for token in &opened {
+        errs_acc.push(ErrorEntry::new(
+            errors::UNCLOSED_BRACKET, 
+            &token.position, 
+            &vec![])); 
     }
 
     expressions
 }
 
+fn get_first_token(statement: &Statement) -> Option<&Token> {
+    statement.tokens.first()
+        .or_else(|| statement.substatements.iter().find_map(get_first_token))
+}
+
 pub fn get_commands(statements: &Vec<Statement>, recognizers: &Vec<&dyn CommandRecognizer>, errs_acc: &mut Vec<ErrorEntry>) -> Vec<Command> {
     let mut cmds: Vec<Command> = vec![];
 
@@ -126,13 +151,22 @@ pub fn get_commands(statements: &Vec<Statement>, recognizers: &Vec<&dyn CommandR
             }
         }
 
-        // Throwing an error (unrecognized statement)
-        let first_token = statement.tokens[0].clone();
-        let mut stat_text: String = format!(""); 
+        // Throwing an error (unrecognized statement). A block 
+        // without a header is reported at its first token
+        let first_token = match get_first_token(statement) {
+            Option::Some(token) => token, 
+            Option::None => continue 'outer, 
+        }; 
+
+        let mut stat_text: String = String::new(); 
         for token in &statement.tokens {
             stat_text.push_str(&token.value); 
         }
 
+        if stat_text.is_empty() {
+            stat_text = "{ ... }".to_string(); 
+        }
+
         errs_acc.push(ErrorEntry::new(
             errors::UNDEFINED_STATEMENT,
             &first_token.position, 
@@ -1,4 +1,6 @@
 use crate::commands::Command;
+use crate::ast::Import; 
+use crate::ast::get_docs; 
 use crate::entities::Statement; 
 use crate::interfaces::CommandRecognizer; 
 use crate::commands::is_first_token_specific_word; 
@@ -8,12 +10,6 @@ use crate::keywords::is_keyword;
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 
-#[derive(Clone)]
-pub struct ImportCommand {
-    pub statement: Statement, 
-    pub package_name: String, 
-}
-
 pub struct ImportCommandRecognizer{}
 impl CommandRecognizer for ImportCommandRecognizer {
     fn from_statement(&self, statement: &Statement, errs_acc: &mut Vec<ErrorEntry>) -> Command {
@@ -38,8 +34,10 @@ impl CommandRecognizer for ImportCommandRecognizer {
         }
 
         let mut name: String = format!(""); 
+        let mut name_span = init_token.span.clone(); 
         if tokens.len() > 1 {
             let name_token = tokens[1].clone(); 
+            name_span = name_token.span.clone(); 
 
             match name_token.token_type {
                 TokenType::Word => { 
@@ -60,9 +58,11 @@ impl CommandRecognizer for ImportCommandRecognizer {
             }
         }
         
-        Command::Import(ImportCommand {
+        Command::Import(Import {
             package_name: name, 
-            statement: statement.clone(),
+            name_span, 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
         })
     }
 }
@@ -6,25 +6,24 @@ mod package;
 mod helper; 
 mod command;
 mod function;
+mod primitive; 
 
-pub use circle::CircleCommand;
 pub use circle::CircleCommandRecognizer;
-pub use element::ElementCommand;
 pub use element::ElementCommandRecognizer; 
-pub use element_entry::ElementEntryCommand;
 pub use element_entry::ElementEntryCommandRecognizer; 
-pub use import::ImportCommand;
 pub use import::ImportCommandRecognizer; 
-pub use package::PackageCommand;
 pub use package::PackageCommandRecognizer; 
-pub use function::FunctionCommand;
 pub use function::FunctionCommandRecognizer; 
+pub use function::get_function_environment; 
+pub use primitive::PrimitiveCommandRecognizer; 
 pub use command::Command; 
 
-pub use helper::is_first_token_specific_word; // TODO: Make private 
-pub use helper::variant_eq; // TODO: Make private 
-pub use helper::find_token; // TODO: Make private
-pub use helper::get_args; 
-pub use helper::get_expressions_tokens;
 pub use helper::get_commands; 
 
+// Helpers of the recognizers, not a part of the library API 
+pub(crate) use helper::is_first_token_specific_word; 
+pub(crate) use helper::variant_eq; 
+pub(crate) use helper::find_token; 
+pub(crate) use helper::get_args; 
+pub(crate) use helper::get_expressions_tokens; 
+
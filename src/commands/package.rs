@@ -1,4 +1,6 @@
-use crate::commands::Command; 
+use crate::commands::Command;
+use crate::ast::Package; 
+use crate::ast::get_docs;  
 use crate::entities::Statement; 
 use crate::interfaces::CommandRecognizer;
 use crate::entities::TokenType; 
@@ -8,12 +10,6 @@ use crate::keywords::is_keyword;
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 
-#[derive(Clone)]
-pub struct PackageCommand {
-    pub statement: Statement, 
-    pub name: String, 
-}
-
 pub struct PackageCommandRecognizer{}
 impl CommandRecognizer for PackageCommandRecognizer {
     fn from_statement(&self, statement: &Statement, errs_acc: &mut Vec<ErrorEntry>) -> Command {
@@ -38,8 +34,10 @@ impl CommandRecognizer for PackageCommandRecognizer {
         }
 
         let mut name: String = format!(""); 
+        let mut name_span = init_token.span.clone(); 
         if tokens.len() > 1 {
             let name_token = tokens[1].clone(); 
+            name_span = name_token.span.clone(); 
 
             match name_token.token_type {
                 TokenType::Word => { 
@@ -60,9 +58,11 @@ impl CommandRecognizer for PackageCommandRecognizer {
             }
         }
         
-        Command::Package(PackageCommand {
-            name: name, 
-            statement: statement.clone(),
+        Command::Package(Package {
+            name, 
+            name_span, 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
         })
     }
 }
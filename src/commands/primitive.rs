@@ -0,0 +1,78 @@
+use crate::commands::Command; 
+use crate::ast::PrimitiveDef; 
+use crate::ast::get_docs; 
+use crate::entities::Statement; 
+use crate::interfaces::CommandRecognizer; 
+use crate::keywords::keyword; 
+use crate::commands::is_first_token_specific_word; 
+use crate::commands::find_token; 
+use crate::entities::TokenType; 
+use crate::commands::get_args; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::keywords::is_keyword; 
+
+pub struct PrimitiveCommandRecognizer{}
+impl CommandRecognizer for PrimitiveCommandRecognizer {
+    fn from_statement(&self, statement: &Statement, errs_acc: &mut Vec<ErrorEntry>) -> Command {
+        let tokens = &statement.tokens; 
+        if !is_first_token_specific_word(tokens, keyword::PRIMITIVE) {
+            return Command::None; 
+        }
+
+        let init_token = tokens[0].clone(); 
+        let mut command = PrimitiveDef {
+            name: String::new(), 
+            name_span: init_token.span.clone(), 
+            nodes: vec![], 
+            params: vec![], 
+            docs: get_docs(&statement.comments), 
+            span: statement.span.clone(), 
+        }; 
+
+        if !statement.substatements.is_empty() {
+            errs_acc.push(ErrorEntry::new(
+                errors::ARE_SUBSTATEMENTS, 
+                &init_token.position, 
+                &vec![])); 
+        }
+
+        // primitive [ nodes ] Name ( params )
+        let is_symbol = |index: usize, value: &str| tokens.get(index)
+            .is_some_and(|x| matches!(x.token_type, TokenType::Symbol) && x.value == value); 
+
+        let nodes_end = find_token(tokens, "]", TokenType::Symbol).unwrap_or(0); 
+        let name_index = nodes_end + 1; 
+        let len = tokens.len(); 
+        if !is_symbol(1, "[") || nodes_end == 0 || !is_symbol(name_index + 1, "(") || !is_symbol(len - 1, ")") {
+            errs_acc.push(ErrorEntry::new(
+                errors::WROND_COMMAND_SEMANTIC, 
+                &init_token.position, 
+                &vec![format!("{} [node1, node2, ...] ElementName(param1, param2, ...); ", keyword::PRIMITIVE)])); 
+
+            return Command::Primitive(command); 
+        }
+
+        let name_token = tokens[name_index].clone(); 
+        command.name_span = name_token.span.clone(); 
+        match name_token.token_type {
+            TokenType::Word if is_keyword(&name_token.value) => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::NAME_KEYWORD, 
+                    &name_token.position, 
+                    &vec![name_token.value.clone()])); 
+            }, 
+            TokenType::Word => command.name = name_token.value.clone(), 
+            _ => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::INVALID_NAME, 
+                    &name_token.position, 
+                    &vec![name_token.value.clone()])); 
+            }, 
+        }
+
+        command.nodes = get_args(tokens, 2, nodes_end, false, errs_acc); 
+        command.params = get_args(tokens, name_index + 2, len - 1, false, errs_acc); 
+        Command::Primitive(command)
+    }
+}
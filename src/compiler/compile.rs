@@ -0,0 +1,79 @@
+use std::collections::HashMap; 
+use crate::commands; 
+use crate::ast::Circuit; 
+use crate::diagnostics::Diagnostic; 
+use crate::diagnostics::has_errors; 
+use crate::elaborator; 
+use crate::elaborator::Netlist; 
+use crate::entities::FunctionEnvironment; 
+use crate::packages; 
+use crate::packages::PackageResolver; 
+use crate::packages::SourceFile; 
+use crate::semantic; 
+use crate::semantic::SymbolTable; 
+
+/// Where the source comes from and how the top circuit is elaborated
+#[derive(Clone, Default)]
+pub struct CompileOptions {
+    pub file_name: String, // imported packages are searched next to the file
+    pub include_dirs: Vec<String>, // package directories from '-I' options
+    pub circuit_name: Option<String>, // the only circuit is taken if it's not set
+    pub params: HashMap<String, f64>, // parameters of the top circuit
+}
+
+/// Loaded and analyzed files of the program, the main file goes first
+pub struct Program {
+    pub files: Vec<SourceFile>, 
+    pub table: SymbolTable, 
+    pub functions: FunctionEnvironment, 
+    pub warnings: Vec<Diagnostic>, 
+}
+
+/// Program with the elaborated top circuit, ready for the simulation
+pub struct Design {
+    pub program: Program, 
+    pub circuit: Circuit, 
+    pub netlist: Netlist, 
+}
+
+/// Parses the source with imported packages and analyzes the whole
+/// program. Diagnostics are returned if some of them is an error.
+pub fn check(source: &str, options: &CompileOptions) -> Result<Program, Vec<Diagnostic>> {
+    let resolver = PackageResolver::new(&options.file_name, &options.include_dirs); 
+    let mut diagnostics: Vec<Diagnostic> = vec![]; 
+    let files = packages::load_program(&options.file_name, source.to_string(), &resolver, &mut diagnostics); 
+    if has_errors(&diagnostics) {
+        return Result::Err(diagnostics); 
+    }
+
+    let table = semantic::analyze(&files, &mut diagnostics); 
+    if has_errors(&diagnostics) {
+        return Result::Err(diagnostics); 
+    }
+
+    let mut functions = FunctionEnvironment::new(); 
+    for file in &files {
+        functions.extend(commands::get_function_environment(&file.commands, &file.file_name)); 
+    }
+
+    Result::Ok(Program { files, table, functions, warnings: diagnostics })
+}
+
+/// Checks the program and elaborates its top circuit into the netlist
+pub fn compile(source: &str, options: &CompileOptions) -> Result<Design, Vec<Diagnostic>> {
+    let mut program = check(source, options)?; 
+    let circuit = match elaborator::find_top_circuit(&program.table, options.circuit_name.as_deref()) {
+        Result::Ok(circuit) => circuit, 
+        Result::Err(err) => {
+            program.warnings.push(*err); 
+            return Result::Err(program.warnings); 
+        }, 
+    }; 
+
+    let netlist = elaborator::elaborate(&program.table, &program.functions, &circuit, &options.params, &mut program.warnings); 
+    if has_errors(&program.warnings) {
+        return Result::Err(program.warnings); 
+    }
+
+    Result::Ok(Design { program, circuit, netlist })
+}
@@ -0,0 +1,7 @@
+mod compile; 
+
+pub use compile::CompileOptions; 
+pub use compile::Program; 
+pub use compile::Design; 
+pub use compile::check; 
+pub use compile::compile; 
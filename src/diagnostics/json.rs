@@ -0,0 +1,55 @@
+use crate::entities::ErrorEntry; 
+
+/// Escapes the text for a JSON string literal
+pub fn escape(text: &str) -> String {
+    let mut result = String::new(); 
+    for x in text.chars() {
+        match x {
+            '"' => result += "\\\"", 
+            '\\' => result += "\\\\", 
+            '\n' => result += "\\n", 
+            '\r' => result += "\\r", 
+            '\t' => result += "\\t", 
+            x if (x as u32) < 0x20 => result += &format!("\\u{:04x}", x as u32), 
+            x => result.push(x), 
+        }
+    }
+
+    format!("\"{}\"", result)
+}
+
+/// One JSON object per diagnostic, positions are 1-based like in the
+/// rendered text. The end of the span is null if only the token at the
+/// position is meant, all positions are null without the location.
+pub fn to_json(entry: &ErrorEntry) -> String {
+    let args: Vec<String> = entry.args.iter().map(|x| escape(x)).collect(); 
+    let labels: Vec<String> = entry.labels.iter()
+        .map(|x| format!("{{\"line\":{},\"column\":{},\"message\":{}}}", 
+            x.position.line + 1, x.position.line_position + 1, escape(&x.message)))
+        .collect(); 
+    let (line, column) = match &entry.position {
+        Option::Some(position) => ((position.line + 1).to_string(), (position.line_position + 1).to_string()), 
+        Option::None => ("null".to_string(), "null".to_string()), 
+    }; 
+    let (end_line, end_column) = match &entry.end_position {
+        Option::Some(end) => ((end.line + 1).to_string(), (end.line_position + 1).to_string()), 
+        Option::None => ("null".to_string(), "null".to_string()), 
+    }; 
+    let help = match &entry.help {
+        Option::Some(help) => escape(help), 
+        Option::None => "null".to_string(), 
+    }; 
+
+    format!("{{\"code\":{},\"severity\":{},\"message\":{},\"args\":[{}],\"file\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{},\"labels\":[{}],\"help\":{}}}", 
+        escape(entry.error.code), 
+        escape(&entry.severity.to_string()), 
+        escape(&entry.get_message()), 
+        args.join(","), 
+        escape(&entry.file_name), 
+        line, 
+        column, 
+        end_line, 
+        end_column, 
+        labels.join(","), 
+        help)
+}
@@ -0,0 +1,9 @@
+use crate::entities::Position; 
+
+/// Secondary span of the diagnostic with its own message, 
+/// e.g. the first declaration of a duplicated name
+#[derive(Clone)]
+pub struct Label {
+    pub position: Position, 
+    pub message: String, 
+}
@@ -0,0 +1,15 @@
+mod severity; 
+mod label; 
+mod render; 
+mod json; 
+mod sarif; 
+
+pub use severity::Severity; 
+pub use label::Label; 
+pub use render::render; 
+pub use render::has_errors; 
+pub use json::to_json; 
+pub use sarif::to_sarif; 
+
+/// Errors, warnings and notes of all passes share one type 
+pub type Diagnostic = crate::entities::ErrorEntry; 
@@ -0,0 +1,94 @@
+use crate::entities::ErrorEntry; 
+use crate::entities::Position; 
+
+/// True if some diagnostic stops the compilation
+pub fn has_errors(diagnostics: &[ErrorEntry]) -> bool {
+    diagnostics.iter().any(|x| x.is_error())
+}
+
+/// Renders the diagnostic like rustc: the header with the severity and
+/// the code, the location, source lines with the primary span underlined
+/// by '^' and secondary ones by '-', then the help text. The span which
+/// continues on the next lines is underlined up to the end of its first
+/// line. `source` is the code of the file the diagnostic belongs to, the
+/// snippet is skipped without it.
+pub fn render(entry: &ErrorEntry, source: Option<&str>) -> String {
+    let file_name = match entry.file_name.is_empty() {
+        true => "CODE", 
+        false => entry.file_name.as_str(), 
+    }; 
+
+    let mut result = format!("{}[{}]: {}\n", entry.severity, entry.error.code, entry.get_message()); 
+    let position = match &entry.position {
+        Option::Some(position) => position, 
+        Option::None => {
+            result += &format!("  --> {}\n", file_name); 
+            return result + &render_help(entry, "   "); 
+        },
+    }; 
+
+    let lines: Vec<&str> = source.map(|x| x.lines().collect()).unwrap_or_default(); 
+    if lines.get(position.line).is_none() {
+        result += &format!("  --> {}:{}:{}\n", file_name, position.line + 1, position.line_position + 1); 
+        return result + &render_help(entry, "   "); 
+    }
+
+    // Spans of one line are drawn under it from left to right
+    let mut spans: Vec<(&Position, Option<&Position>, char, &str)> = vec![(position, entry.end_position.as_ref(), '^', "")]; 
+    spans.extend(entry.labels.iter()
+        .filter(|x| lines.get(x.position.line).is_some())
+        .map(|x| (&x.position, Option::None, '-', x.message.as_str()))); 
+    spans.sort_by_key(|x| x.0.line_position); 
+
+    let mut line_numbers: Vec<usize> = spans.iter().map(|x| x.0.line).collect(); 
+    line_numbers.sort(); 
+    line_numbers.dedup(); 
+
+    let width = (line_numbers.last().unwrap() + 1).to_string().len(); 
+    let gutter = " ".repeat(width); 
+    result += &format!("{}--> {}:{}:{}\n", gutter, file_name, position.line + 1, position.line_position + 1); 
+    result += &format!("{} |\n", gutter); 
+    for (i, line_number) in line_numbers.iter().enumerate() {
+        if i > 0 && line_numbers[i - 1] + 1 < *line_number {
+            result += &format!("{} |\n", ".".repeat(width)); 
+        }
+
+        let line = lines[*line_number]; 
+        result += &format!("{:>width$} | {}\n", line_number + 1, line.trim_end(), width = width); 
+        for (span, end, mark, message) in spans.iter().filter(|x| x.0.line == *line_number) {
+            // Tabs are kept, so the underline is aligned with the line
+            let padding: String = line.chars()
+                .take(span.line_position)
+                .map(|x| if x == '\t' { '\t' } else { ' ' })
+                .collect();
+            let underline = mark.to_string().repeat(get_span_length(line, span, *end)); 
+            result += format!("{} | {}{} {}", gutter, padding, underline, message).trim_end(); 
+            result += "\n"; 
+        }
+    }
+
+    result + &render_help(entry, &format!("{} ", gutter))
+}
+
+fn render_help(entry: &ErrorEntry, indent: &str) -> String {
+    match &entry.help {
+        Option::Some(help) => format!("{}= help: {}\n", indent, help), 
+        Option::None => String::new(), 
+    }
+}
+
+/// Length of the span on its first line. Without the end it's the token 
+/// starting at the position: a word or a number, otherwise one char
+fn get_span_length(line: &str, start: &Position, end: Option<&Position>) -> usize {
+    let column = start.line_position; 
+    let length = match end {
+        Option::Some(end) if end.line == start.line => end.line_position.saturating_sub(column), 
+        Option::Some(_) => line.trim_end().chars().count().saturating_sub(column), 
+        Option::None => {
+            let is_word_char = |x: &char| x.is_alphanumeric() || *x == '_' || *x == '.'; 
+            line.chars().skip(column).take_while(is_word_char).count()
+        }, 
+    }; 
+
+    length.max(1)
+}
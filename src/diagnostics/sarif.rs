@@ -0,0 +1,70 @@
+use crate::entities::ErrorEntry; 
+use crate::entities::Position; 
+use crate::diagnostics::Severity; 
+use crate::diagnostics::json::escape; 
+
+const SARIF_VERSION: &str = "2.1.0"; 
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json"; 
+
+/// SARIF 2.1 log with one run of the compiler: codes of the diagnostics
+/// are rules, labels are related locations
+pub fn to_sarif(entries: &[ErrorEntry]) -> String {
+    let mut codes: Vec<&str> = entries.iter().map(|x| x.error.code).collect(); 
+    codes.sort(); 
+    codes.dedup(); 
+
+    let rules: Vec<String> = codes.iter()
+        .map(|x| format!("{{\"id\":{}}}", escape(x)))
+        .collect(); 
+    let results: Vec<String> = entries.iter().map(get_result).collect(); 
+
+    format!("{{\"version\":{},\"$schema\":{},\"runs\":[{{\"tool\":{{\"driver\":{{\"name\":{},\"version\":{},\"rules\":[{}]}}}},\"results\":[{}]}}]}}", 
+        escape(SARIF_VERSION), 
+        escape(SARIF_SCHEMA), 
+        escape(env!("CARGO_PKG_NAME")), 
+        escape(env!("CARGO_PKG_VERSION")), 
+        rules.join(","), 
+        results.join(","))
+}
+
+fn get_result(entry: &ErrorEntry) -> String {
+    let level = match entry.severity {
+        Severity::Error => "error", 
+        Severity::Warning => "warning", 
+    }; 
+
+    let mut message = entry.get_message(); 
+    if let Option::Some(help) = &entry.help {
+        message = format!("{} Help: {}", message, help); 
+    }
+
+    let related: Vec<String> = entry.labels.iter()
+        .map(|x| format!("{{{},\"message\":{{\"text\":{}}}}}", 
+            get_physical_location(&entry.file_name, Option::Some(&x.position), Option::None), escape(&x.message)))
+        .collect(); 
+
+    format!("{{\"ruleId\":{},\"level\":{},\"message\":{{\"text\":{}}},\"locations\":[{{{}}}],\"relatedLocations\":[{}]}}", 
+        escape(entry.error.code), 
+        escape(level), 
+        escape(&message), 
+        get_physical_location(&entry.file_name, entry.position.as_ref(), entry.end_position.as_ref()), 
+        related.join(","))
+}
+
+/// The region ends with the span if it's known, SARIF columns of 
+/// the end are exclusive like ours. Without the position only the 
+/// file is located. 
+fn get_physical_location(file_name: &str, position: Option<&Position>, end: Option<&Position>) -> String {
+    let end = match end {
+        Option::Some(end) => format!(",\"endLine\":{},\"endColumn\":{}", end.line + 1, end.line_position + 1), 
+        Option::None => String::new(), 
+    }; 
+
+    let region = match position {
+        Option::Some(position) => format!(",\"region\":{{\"startLine\":{},\"startColumn\":{}{}}}", 
+            position.line + 1, position.line_position + 1, end), 
+        Option::None => String::new(), 
+    }; 
+
+    format!("\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":{}}}{}}}", escape(file_name), region)
+}
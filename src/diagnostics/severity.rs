@@ -0,0 +1,18 @@
+use std::fmt::Display; 
+use std::fmt::Formatter; 
+
+/// How serious the diagnostic is. Only errors stop the compilation. 
+#[derive(Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error, 
+    Warning, 
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"), 
+            Severity::Warning => write!(f, "warning"), 
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap; 
+use crate::ast::ElementDef; 
+use crate::entities::ErrorEntry; 
+use crate::entities::Position; 
+use crate::entities::errors; 
+use crate::semantic::SymbolTable; 
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress, 
+    Done, 
+}
+
+/// Step of the instantiation chain: element entry `position` 
+/// which instantiates the element `name` 
+struct Step {
+    name: String, 
+    position: Position, 
+}
+
+/// Checks the graph of element dependencies (element -> elements of 
+/// its entries) for cycles. Returns `false` if any cycle is found, 
+/// every cycle is reported with its instantiation chain. 
+pub fn check_cycles(table: &SymbolTable, errs_acc: &mut Vec<ErrorEntry>) -> bool {
+    let mut elements: Vec<&ElementDef> = table.elements.values().collect(); 
+    elements.sort_by_key(|x| (x.span.position.line, x.span.position.line_position)); 
+
+    let mut visits: HashMap<String, Visit> = HashMap::new(); 
+    let errors_count = errs_acc.len(); 
+    for element in elements {
+        if !visits.contains_key(&element.name) {
+            let mut chain: Vec<Step> = vec![Step { 
+                name: element.name.clone(), 
+                position: Position { line: 0, line_position: 0 }, 
+            }]; 
+
+            visit(table, element, &mut visits, &mut chain, errs_acc); 
+        }
+    }
+
+    errs_acc.len() == errors_count
+}
+
+fn visit(table: &SymbolTable, 
+         element: &ElementDef, 
+         visits: &mut HashMap<String, Visit>, 
+         chain: &mut Vec<Step>, 
+         errs_acc: &mut Vec<ErrorEntry>) {
+
+    visits.insert(element.name.clone(), Visit::InProgress); 
+    for entry in &element.instances {
+        let inner = match table.elements.get(&entry.element_name) {
+            Option::Some(inner) => inner, 
+            Option::None => continue, // primitive or undefined element
+        }; 
+
+        chain.push(Step { name: inner.name.clone(), position: entry.span.position.clone() }); 
+        match visits.get(&inner.name) {
+            Option::None => visit(table, inner, visits, chain, errs_acc), 
+            Option::Some(Visit::InProgress) => push_cycle(table, chain, errs_acc), 
+            Option::Some(Visit::Done) => { }, 
+        }
+
+        chain.pop(); 
+    }
+
+    visits.insert(element.name.clone(), Visit::Done); 
+}
+
+fn push_cycle(table: &SymbolTable, chain: &[Step], errs_acc: &mut Vec<ErrorEntry>) {
+    let last = chain.last().unwrap(); 
+    // The last entry belongs to the element before it 
+    let file_name = table.file_names.get(&chain[chain.len() - 2].name).cloned().unwrap_or_default(); 
+    let start = chain.iter()
+        .position(|x| x.name == last.name)
+        .unwrap(); 
+
+    let cycle = &chain[start..]; 
+    let names: Vec<String> = cycle.iter()
+        .map(|x| x.name.clone())
+        .collect(); 
+
+    // The first step is the element itself, not an entry
+    let positions: Vec<String> = cycle.iter()
+        .skip(1)
+        .map(|x| x.position.to_string())
+        .collect(); 
+
+    errs_acc.push(ErrorEntry::new(
+        errors::RECURSIVE_ELEMENT, 
+        &last.position, 
+        &vec![names.join(" -> "), positions.join(", ")]).in_file(&file_name)); 
+}
@@ -0,0 +1,392 @@
+use std::collections::HashMap; 
+use std::collections::HashSet; 
+use std::rc::Rc; 
+use crate::ast::Circuit; 
+use crate::ast::Instance; 
+use crate::ast::get_names; 
+use crate::elaborator::BoundExpression; 
+use crate::elaborator::Device; 
+use crate::elaborator::Netlist; 
+use crate::elaborator::check_cycles; 
+use crate::entities::FunctionEnvironment; 
+use crate::entities::ErrorEntry; 
+use crate::entities::Position; 
+use crate::entities::errors; 
+use crate::keywords::primitive; 
+use crate::keywords::is_quantity; 
+use crate::keywords::member; 
+use crate::keywords::ALL_VARIABLES; 
+use crate::semantic::SymbolTable; 
+use crate::simulation::GROUND; 
+
+/// Scope of an element instance during the expansion 
+struct Scope<'a> {
+    prefix: String, // instance path with trailing dot, empty for the circuit 
+    params: Rc<HashMap<String, BoundExpression>>, 
+    nodes: HashMap<String, String>, // element nodes mapped to the caller nodes 
+    file_name: String, // file which declares the element 
+    functions: &'a FunctionEnvironment, 
+    variables: &'a HashMap<String, f64>, 
+}
+
+impl<'a> Scope<'a> {
+    /// Nodes of the element are mapped to the caller nodes, 
+    /// internal nodes are prefixed with the instance path. Node '0' 
+    /// of an element is internal too (e.g. 'K1.0'), only the circuit 
+    /// uses the ground, elements get it through their pins. 
+    fn get_node(&self, name: &String) -> String {
+        match self.nodes.get(name) {
+            Option::Some(node) => node.clone(), 
+            Option::None => format!("{}{}", self.prefix, name), 
+        }
+    }
+}
+
+/// Returns the circuit with the given name or the only circuit of the file 
+pub fn find_top_circuit(table: &SymbolTable, name: Option<&str>) -> Result<Circuit, Box<ErrorEntry>> {
+    match name {
+        Option::Some(name) => match table.circuits.get(name) {
+            Option::Some(circuit) => Result::Ok(circuit.clone()), 
+            Option::None => Result::Err(Box::new(ErrorEntry::global(
+                errors::UNDEFINED_CIRCUIT, 
+                &vec![name.to_string()]))), 
+        },
+        Option::None => {
+            let circuits: Vec<&Circuit> = table.circuits.values().collect(); 
+            match circuits.len() {
+                1 => Result::Ok(circuits[0].clone()), 
+                _ => Result::Err(Box::new(ErrorEntry::global(
+                    errors::AMBIGUOUS_CIRCUIT, 
+                    &vec![circuits.len().to_string()]))), 
+            }
+        },
+    }
+}
+
+/// Expands the circuit into a flat list of primitives. Parameters of the 
+/// circuit are taken from `params`, parameter expressions of every entry 
+/// are evaluated in the scope of the element which contains the entry 
+/// (built-in variables 'time', 'freq' and 's' are 0). 
+/// Nothing is expanded if element declarations contain cycles. 
+/// Floating nodes of the netlist are reported as warnings. 
+pub fn elaborate(table: &SymbolTable, 
+                 functions: &FunctionEnvironment, 
+                 circuit: &Circuit, 
+                 params: &HashMap<String, f64>, 
+                 errs_acc: &mut Vec<ErrorEntry>) -> Netlist {
+
+    let mut variables = params.clone(); 
+    for name in ALL_VARIABLES.iter() {
+        variables.insert(name.to_string(), 0.0); 
+    }
+
+    let mut devices: Vec<Device> = vec![]; 
+    if check_cycles(table, errs_acc) {
+        let scope = Scope {
+            prefix: String::new(), 
+            params: Rc::new(HashMap::new()), 
+            nodes: HashMap::new(), 
+            file_name: table.file_names.get(&circuit.name).cloned().unwrap_or_default(), 
+            functions, 
+            variables: &variables, 
+        }; 
+
+        let errors_count = errs_acc.len(); 
+        expand_entries(table, &circuit.instances, &scope, &mut devices, errs_acc); 
+        check_controls(&devices, errs_acc); 
+
+        // Devices with errors are skipped, their nodes would look floating 
+        if errs_acc.len() == errors_count {
+            check_floating_nodes(&devices, errs_acc); 
+        }
+    }
+
+    Netlist {
+        circuit_name: circuit.name.clone(), 
+        devices, 
+        functions: functions.clone(), 
+        variables, 
+    }
+}
+
+fn expand_entries(table: &SymbolTable, 
+                  entries: &Vec<Instance>, 
+                  scope: &Scope, 
+                  devices: &mut Vec<Device>, 
+                  errs_acc: &mut Vec<ErrorEntry>) {
+
+    let entries_scope = get_entries_scope(table, entries, &scope.params, &scope.prefix); 
+    for entry in entries {
+        let position = entry.span.position.clone(); 
+
+        let param_expressions: Vec<BoundExpression> = entry.param_expressions.iter()
+            .map(|x| BoundExpression {
+                expression: x.clone(), 
+                scope: entries_scope.clone(), 
+                prefix: scope.prefix.clone(), 
+            })
+            .collect(); 
+
+        let mut params: Vec<f64> = vec![]; 
+        for expression in &param_expressions {
+            // Values depending on other instances are known only 
+            // during the simulation 
+            if !expression.get_members().is_empty() {
+                params.push(f64::NAN); 
+                continue; 
+            }
+
+            match expression.evaluate(scope.functions, scope.variables) {
+                Result::Ok(value) => params.push(value), 
+                Result::Err(err) => errs_acc.push(err.in_file(&scope.file_name)), 
+            }
+        }
+
+        if params.len() != entry.param_expressions.len() {
+            continue; 
+        }
+
+        let name = format!("{}{}", scope.prefix, entry.entry_name); 
+        let nodes: Vec<String> = entry.nodes.iter()
+            .map(|x| scope.get_node(&x.name))
+            .collect(); 
+
+        if table.primitives.contains_key(&entry.element_name) {
+            devices.push(Device {
+                name, 
+                element_name: entry.element_name.clone(), 
+                nodes, 
+                params, 
+                param_expressions, 
+                position, 
+                file_name: scope.file_name.clone(), 
+            }); 
+
+            continue; 
+        }
+
+        let element = match table.elements.get(&entry.element_name) {
+            Option::Some(element) => element, 
+            Option::None => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::UNDEFINED_ELEMENT, 
+                    &position, 
+                    &vec![entry.element_name.clone()]).in_file(&scope.file_name)); 
+                continue; 
+            }
+        }; 
+
+        let inner_scope = Scope {
+            prefix: format!("{}.", name), 
+            params: Rc::new(get_names(&element.params).into_iter().zip(param_expressions).collect()), 
+            nodes: get_names(&element.nodes).into_iter().zip(nodes).collect(), 
+            file_name: table.file_names.get(&element.name).cloned().unwrap_or_default(), 
+            functions: scope.functions, 
+            variables: scope.variables, 
+        }; 
+
+        expand_entries(table, &element.instances, &inner_scope, devices, errs_acc); 
+    }
+}
+
+/// Adds parameters of the entries (e.g. 'R1.R') to the parameters of the 
+/// element. An entry is added after the entries it reads, so its parameters 
+/// are bound to the scope which already contains their dependencies. 
+fn get_entries_scope(table: &SymbolTable, 
+                     entries: &[Instance], 
+                     element_params: &Rc<HashMap<String, BoundExpression>>, 
+                     prefix: &str) -> Rc<HashMap<String, BoundExpression>> {
+
+    let mut params: HashMap<String, BoundExpression> = (**element_params).clone(); 
+    let mut current = element_params.clone(); 
+    let mut pending: Vec<&Instance> = entries.iter().collect(); 
+    while !pending.is_empty() {
+        let pending_names: Vec<String> = pending.iter().map(|x| x.entry_name.clone()).collect(); 
+        let (ready, rest): (Vec<&Instance>, Vec<&Instance>) = pending.into_iter()
+            .partition(|entry| entry.param_expressions.iter()
+                .flat_map(|x| x.get_members())
+                .all(|(instance, member, _)| is_quantity(&member) || !pending_names.contains(&instance))); 
+
+        // Cycles are reported by the semantic analysis 
+        if ready.is_empty() {
+            break; 
+        }
+
+        for entry in ready {
+            let names = table.get_param_names(&entry.element_name).unwrap_or_default(); 
+            for (name, expression) in names.iter().zip(&entry.param_expressions) {
+                if is_quantity(name) {
+                    continue; 
+                }
+
+                params.insert(format!("{}.{}", entry.entry_name, name), BoundExpression {
+                    expression: expression.clone(), 
+                    scope: current.clone(), 
+                    prefix: prefix.to_string(), 
+                }); 
+            }
+        }
+
+        current = Rc::new(params.clone()); 
+        pending = rest; 
+    }
+
+    current
+}
+
+/// Parameter expressions of the circuit entries bound to the scope of 
+/// the circuit, so they can be evaluated without expanding the circuit 
+pub fn bind_circuit_entries(table: &SymbolTable, circuit: &Circuit) -> Vec<Vec<BoundExpression>> {
+    let scope = get_entries_scope(table, &circuit.instances, &Rc::new(HashMap::new()), ""); 
+    circuit.instances.iter()
+        .map(|entry| entry.param_expressions.iter()
+            .map(|x| BoundExpression {
+                expression: x.clone(), 
+                scope: scope.clone(), 
+                prefix: String::new(), 
+            })
+            .collect())
+        .collect()
+}
+
+/// Only current sources may depend on other instances, and only 
+/// on branch voltages of primitives 
+fn check_controls(devices: &Vec<Device>, errs_acc: &mut Vec<ErrorEntry>) {
+    let errors_count = errs_acc.len(); 
+    for device in devices {
+        let members: Vec<(String, Position)> = device.param_expressions.iter()
+            .flat_map(|x| x.get_members())
+            .collect(); 
+
+        if !members.is_empty() && device.element_name != primitive::CURRENT_SOURCE {
+            errs_acc.push(ErrorEntry::new(
+                errors::CONTROLLED_PRIMITIVE, 
+                &device.position, 
+                &vec![device.name.clone()]).in_file(&device.file_name)); 
+            continue; 
+        }
+
+        for (member, position) in members {
+            let instance = member.rsplit_once('.').map(|x| x.0).unwrap_or(""); 
+            if !devices.iter().any(|x| x.name == instance) {
+                errs_acc.push(ErrorEntry::new(
+                    errors::UNDEFINED_CONTROL, 
+                    &position, 
+                    &vec![member.clone(), instance.to_string()]).in_file(&device.file_name)); 
+            }
+        }
+    }
+
+    if errs_acc.len() == errors_count {
+        check_control_cycles(devices, errs_acc); 
+    }
+}
+
+/// Node connected to a single terminal or not connected to the ground 
+/// through devices doesn't affect the circuit, it's usually a mistake 
+/// like the ground '0' used inside an element 
+fn check_floating_nodes(devices: &Vec<Device>, warns_acc: &mut Vec<ErrorEntry>) {
+    // The missing ground is reported by the simulation 
+    if !devices.iter().any(|x| x.nodes.iter().any(|node| node == GROUND)) {
+        return; 
+    }
+
+    // Nodes connected to the ground, the connection spreads through devices 
+    let mut grounded: HashSet<&str> = HashSet::from([GROUND]); 
+    let mut changed = true; 
+    while changed {
+        changed = false; 
+        for device in devices {
+            if device.nodes.iter().any(|x| grounded.contains(x.as_str())) {
+                for node in &device.nodes {
+                    changed |= grounded.insert(node); 
+                }
+            }
+        }
+    }
+
+    let mut reported: HashSet<&str> = HashSet::new(); 
+    for device in devices {
+        for node in device.nodes.iter().filter(|x| *x != GROUND) {
+            let terminals_count = devices.iter()
+                .flat_map(|x| x.nodes.iter())
+                .filter(|x| *x == node)
+                .count(); 
+
+            let reason = match (terminals_count, grounded.contains(node.as_str())) {
+                (1, _) => "it's connected to one terminal only", 
+                (_, false) => "it has no connection to the ground '0'", 
+                _ => continue, 
+            }; 
+
+            if !reported.insert(node) {
+                continue; 
+            }
+
+            let mut warning = ErrorEntry::warning(
+                errors::FLOATING_NODE, 
+                &device.position, 
+                &vec![node.clone(), reason.to_string()]).in_file(&device.file_name); 
+
+            if node.ends_with(&format!(".{}", GROUND)) {
+                warning = warning.with_help("node '0' of an element is internal, pass the ground to the element through a pin"); 
+            }
+
+            warns_acc.push(warning); 
+        }
+    }
+}
+
+/// Current of the controlled source is its value, so the sources 
+/// can't read currents or powers of each other in a cycle 
+fn check_control_cycles(devices: &Vec<Device>, errs_acc: &mut Vec<ErrorEntry>) {
+    let mut finished: Vec<bool> = vec![false; devices.len()]; 
+    for index in 0..devices.len() {
+        let mut chain: Vec<usize> = vec![]; 
+        if find_control_cycle(devices, index, &mut chain, &mut finished, errs_acc) {
+            return; 
+        }
+    }
+}
+
+/// Controlled sources whose currents or powers the device reads 
+fn get_control_dependencies(devices: &[Device], device: &Device) -> Vec<usize> {
+    device.get_controls().iter()
+        .filter_map(|x| x.rsplit_once('.'))
+        .filter(|x| x.1 != member::VOLTAGE)
+        .filter_map(|x| devices.iter().position(|device| device.name == x.0))
+        .filter(|x| devices[*x].is_controlled())
+        .collect()
+}
+
+fn find_control_cycle(devices: &Vec<Device>, 
+                      index: usize, 
+                      chain: &mut Vec<usize>, 
+                      finished: &mut Vec<bool>, 
+                      errs_acc: &mut Vec<ErrorEntry>) -> bool {
+
+    if finished[index] {
+        return false; 
+    }
+
+    if let Option::Some(start) = chain.iter().position(|x| *x == index) {
+        let mut cycle: Vec<String> = chain[start..].iter().map(|x| devices[*x].name.clone()).collect(); 
+        cycle.push(devices[index].name.clone()); 
+        errs_acc.push(ErrorEntry::new(
+            errors::CONTROL_CYCLE, 
+            &devices[index].position, 
+            &vec![cycle.join(" -> ")]).in_file(&devices[index].file_name)); 
+        return true; 
+    }
+
+    chain.push(index); 
+    for next in get_control_dependencies(devices, &devices[index]) {
+        if find_control_cycle(devices, next, chain, finished, errs_acc) {
+            return true; 
+        }
+    }
+
+    chain.pop(); 
+    finished[index] = true; 
+    false
+}
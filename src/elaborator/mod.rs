@@ -0,0 +1,202 @@
+mod netlist; 
+mod elaborate; 
+mod cycles; 
+
+pub use netlist::BoundExpression; 
+pub use netlist::Device; 
+pub use netlist::Netlist; 
+pub use elaborate::elaborate; 
+pub use elaborate::find_top_circuit;
+pub use elaborate::bind_circuit_entries; 
+pub use cycles::check_cycles; 
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap; 
+    use crate::compiler::compile; 
+    use crate::compiler::CompileOptions; 
+    use crate::compiler::Design; 
+    use crate::entities::ErrorEntry; 
+
+    fn compile_code(code: &str, params: &[(&str, f64)]) -> Result<Design, Vec<ErrorEntry>> {
+        let options = CompileOptions {
+            params: params.iter().map(|x| (x.0.to_string(), x.1)).collect::<HashMap<String, f64>>(), 
+            ..CompileOptions::default()
+        }; 
+
+        compile(code, &options)
+    }
+
+    /// Devices as 'name element [nodes] (params)' 
+    fn get_devices(design: &Design) -> Vec<String> {
+        design.netlist.devices.iter()
+            .map(|x| format!("{} {} [{}] ({})", x.name, x.element_name, x.nodes.join(", "), 
+                x.params.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", ")))
+            .collect()
+    }
+
+    #[test]
+    fn hierarchy_is_flattened() {
+        let code = "
+circuit Main(U)
+{
+    V1[1, 0] = VoltageSource(U); 
+    K1[1, 0] = Divider(2 * 1000); 
+}
+
+element [In, Out] Divider(R)
+{
+    R1[In, mid] = Resistor(R / 2); 
+    K2[mid, Out] = Half(R); 
+}
+
+element [A, B] Half(R)
+{
+    R1[A, B] = Resistor(R / 2); 
+}
+"; 
+        let design = compile_code(code, &[("U", 5.0)]).ok().unwrap(); 
+        assert_eq!(get_devices(&design), vec![
+            "V1 VoltageSource [1, 0] (5)", 
+            "K1.R1 Resistor [1, K1.mid] (1000)", 
+            "K1.K2.R1 Resistor [K1.mid, 0] (1000)", 
+        ]); 
+        assert!(design.program.warnings.is_empty()); 
+    }
+
+    #[test]
+    fn missing_circuit_parameter_is_reported() {
+        let code = "circuit Main(U) { V1[1, 0] = VoltageSource(U); R1[1, 0] = Resistor(1); }"; 
+        let errors = compile_code(code, &[]).err().unwrap(); 
+        assert_eq!(errors.len(), 1); 
+        assert_eq!(errors[0].error.code, "EVL002"); 
+    }
+
+    #[test]
+    fn element_ground_is_internal() {
+        let code = "
+circuit Main()
+{
+    V1[In, 0] = VoltageSource(10); 
+    K1[In] = Shunt(); 
+}
+
+element [In] Shunt()
+{
+    R1[In, 0] = Resistor(100); 
+}
+"; 
+        let design = compile_code(code, &[]).ok().unwrap(); 
+        assert_eq!(get_devices(&design)[1], "K1.R1 Resistor [In, K1.0] (100)"); 
+
+        let warnings = &design.program.warnings; 
+        assert_eq!(warnings.len(), 1); 
+        assert_eq!(warnings[0].error.code, "ELB007"); 
+        assert_eq!(warnings[0].get_message(), "Node 'K1.0' is floating: it's connected to one terminal only."); 
+        assert_eq!(warnings[0].position.as_ref().unwrap().line, 9); 
+        assert!(warnings[0].help.is_some()); 
+
+        // The ground passed through a pin is the ground of the circuit 
+        let code = code.replace("K1[In] = Shunt()", "K1[In, 0] = Shunt()").replace("[In] Shunt()", "[In, Gnd] Shunt()").replace("R1[In, 0]", "R1[In, Gnd]"); 
+        let design = compile_code(&code, &[]).ok().unwrap(); 
+        assert_eq!(get_devices(&design)[1], "K1.R1 Resistor [In, 0] (100)"); 
+        assert!(design.program.warnings.is_empty()); 
+    }
+
+    #[test]
+    fn nodes_without_ground_connection_are_reported() {
+        let code = "
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(1); 
+    R1[1, 0] = Resistor(1); 
+    R2[2, 3] = Resistor(1); 
+    R3[3, 2] = Resistor(1); 
+}
+"; 
+        let design = compile_code(code, &[]).ok().unwrap(); 
+        let messages: Vec<String> = design.program.warnings.iter().map(|x| x.get_message()).collect(); 
+        assert_eq!(messages, vec![
+            "Node '2' is floating: it has no connection to the ground '0'.", 
+            "Node '3' is floating: it has no connection to the ground '0'.", 
+        ]); 
+    }
+
+    fn get_messages(diagnostics: &Vec<ErrorEntry>) -> Vec<String> {
+        diagnostics.iter()
+            .map(|x| format!("{} {}", x.error.code, x.get_message()))
+            .collect()
+    }
+
+    #[test]
+    fn top_circuit_is_chosen() {
+        let code = "
+circuit First() { V1[1, 0] = VoltageSource(1); R1[1, 0] = Resistor(1); }
+circuit Second() { V1[1, 0] = VoltageSource(2); R1[1, 0] = Resistor(1); }
+"; 
+        let errors = compile_code(code, &[]).err().unwrap(); 
+        assert_eq!(get_messages(&errors), vec!["ELB002 Can't choose the circuit to elaborate: file contains 2 circuits."]); 
+
+        let options = CompileOptions { circuit_name: Option::Some(format!("Third")), ..CompileOptions::default() }; 
+        let errors = compile(code, &options).err().unwrap(); 
+        assert_eq!(get_messages(&errors), vec!["ELB001 Circuit 'Third' not found."]); 
+
+        let options = CompileOptions { circuit_name: Option::Some(format!("Second")), ..CompileOptions::default() }; 
+        let design = compile(code, &options).ok().unwrap(); 
+        assert_eq!(design.netlist.circuit_name, "Second"); 
+        assert_eq!(design.netlist.devices[0].params, vec![2.0]); 
+    }
+
+    #[test]
+    fn recursive_elements_are_reported() {
+        let code = "
+circuit Main() { K1[1, 0] = A(); }
+element [P, N] A() { K1[P, N] = B(); }
+element [P, N] B() { K1[P, N] = A(); }
+element [P, N] C() { R1[P, N] = Resistor(1); K1[P, N] = C(); }
+"; 
+        let errors = compile_code(code, &[]).err().unwrap(); 
+        assert_eq!(get_messages(&errors), vec![
+            "ELB003 Recursive element definition: A -> B -> A. Instantiated at (3, 22), (4, 22).", 
+            "ELB003 Recursive element definition: C -> C. Instantiated at (5, 46).", 
+        ]); 
+        assert_eq!(errors[0].position.as_ref().unwrap().line, 3); 
+    }
+
+    #[test]
+    fn controls_of_sources_are_checked() {
+        let code = "
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(1); 
+    R1[1, 2] = Resistor(V1.U); 
+    R2[2, 0] = Resistor(1); 
+}
+"; 
+        let errors: Vec<ErrorEntry> = compile_code(code, &[]).err().unwrap().into_iter()
+            .filter(|x| x.error.code.starts_with("ELB"))
+            .collect(); 
+        assert_eq!(get_messages(&errors), vec![
+            "ELB004 Parameters of 'R1' depend on other instances, but only current sources can be controlled.", 
+        ]); 
+        assert_eq!(errors[0].position.as_ref().unwrap().line, 4); 
+    }
+
+    #[test]
+    fn control_cycles_are_reported() {
+        let code = "
+circuit Main()
+{
+    R1[1, 0] = Resistor(1); 
+    J1[1, 0] = CurrentSource(0.5 * J2.I); 
+    J2[1, 0] = CurrentSource(0.5 * J1.I + 1); 
+}
+"; 
+        let errors = compile_code(code, &[]).err().unwrap(); 
+        assert_eq!(get_messages(&errors), vec!["ELB006 Controlled sources depend on each other: J1 -> J2 -> J1."]); 
+
+        // Voltages of controlled sources are unknowns, they don't make cycles 
+        let code = code.replace("J2.I", "J2.U").replace("J1.I", "J1.U"); 
+        assert!(compile_code(&code, &[]).is_ok()); 
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap; 
+use std::rc::Rc; 
+use crate::entities::Expression; 
+use crate::entities::ErrorEntry; 
+use crate::entities::FunctionEnvironment; 
+use crate::entities::Position; 
+use crate::entities::Complex; 
+use crate::keywords::variable; 
+
+/// Parameter expression of an element entry together with the parameters 
+/// of the element which contains the entry. Parameters are bound lazily, 
+/// so the expression may be evaluated again with other built-in 
+/// variables (e.g. 'time'). 
+#[derive(Clone)]
+pub struct BoundExpression {
+    pub expression: Expression, 
+    pub scope: Rc<HashMap<String, BoundExpression>>, 
+    pub prefix: String, // instance path of the element which contains the entry
+}
+
+impl BoundExpression {
+    /// `variables` are visible in every scope unless an element parameter 
+    /// has the same name. Parameters of instances (e.g. 'R1.R') are taken 
+    /// from the scope, quantities (e.g. 'JK.U') from `variables` by their 
+    /// global names (e.g. 'K1.JK.U'). 
+    pub fn evaluate(&self, functions: &FunctionEnvironment, variables: &HashMap<String, f64>) -> Result<f64, Box<ErrorEntry>> {
+        let mut input = variables.clone(); 
+        for name in self.get_names() {
+            if let Option::Some(param) = self.scope.get(&name) {
+                let value = param.evaluate(functions, variables)?; 
+                input.insert(name, value); 
+            }
+            else if let Option::Some(value) = variables.get(&format!("{}{}", self.prefix, name)) {
+                input.insert(name, *value); 
+            }
+        }
+
+        self.expression.evaluate(&input, functions)
+    }
+
+    /// Same as `evaluate`, but in complex numbers 
+    pub fn evaluate_complex(&self, functions: &FunctionEnvironment, variables: &HashMap<String, Complex>) -> Result<Complex, Box<ErrorEntry>> {
+        let mut input = variables.clone(); 
+        for name in self.get_names() {
+            if let Option::Some(param) = self.scope.get(&name) {
+                let value = param.evaluate_complex(functions, variables)?; 
+                input.insert(name, value); 
+            }
+            else if let Option::Some(value) = variables.get(&format!("{}{}", self.prefix, name)) {
+                input.insert(name, *value); 
+            }
+        }
+
+        self.expression.evaluate_complex(&input, functions)
+    }
+
+    /// Names of variables and member accesses of the expression 
+    fn get_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.expression.get_variables().into_iter()
+            .map(|x| x.0)
+            .collect(); 
+
+        names.extend(self.expression.get_members().into_iter().map(|x| format!("{}.{}", x.0, x.1))); 
+        names
+    }
+
+    /// Global names of instance quantities the expression depends on, 
+    /// parameters of the containing elements and instances included 
+    pub fn get_members(&self) -> Vec<(String, Position)> {
+        let mut members: Vec<(String, Position)> = vec![]; 
+        for (name, _) in self.expression.get_variables() {
+            if let Option::Some(param) = self.scope.get(&name) {
+                members.extend(param.get_members()); 
+            }
+        }
+
+        for (instance, member, position) in self.expression.get_members() {
+            let name = format!("{}.{}", instance, member); 
+            match self.scope.get(&name) {
+                Option::Some(param) => members.extend(param.get_members()), 
+                Option::None => members.push((format!("{}{}", self.prefix, name), position)), 
+            }
+        }
+
+        members
+    }
+}
+
+/// Primitive device of the flat netlist 
+#[derive(Clone)]
+pub struct Device {
+    pub name: String, // instance path, e.g. 'K2.R1'
+    pub element_name: String, // primitive name, e.g. 'Resistor'
+    pub nodes: Vec<String>, // global node names
+    pub params: Vec<f64>, // values of parameters at time 0, NaN if they depend on other devices
+    pub param_expressions: Vec<BoundExpression>, 
+    pub position: Position, // position of the element entry
+    pub file_name: String, // file of the element entry
+}
+
+impl Device {
+    /// Global names of instance quantities (e.g. 'K1.JK.U') 
+    /// the parameters depend on 
+    pub fn get_controls(&self) -> Vec<String> {
+        let mut controls: Vec<String> = vec![]; 
+        for expression in &self.param_expressions {
+            for (name, _) in expression.get_members() {
+                if !controls.contains(&name) {
+                    controls.push(name); 
+                }
+            }
+        }
+
+        controls
+    }
+
+    pub fn is_controlled(&self) -> bool {
+        self.param_expressions.iter().any(|x| !x.get_members().is_empty())
+    }
+}
+
+/// Circuit expanded down to primitives 
+#[derive(Clone)]
+pub struct Netlist {
+    pub circuit_name: String, 
+    pub devices: Vec<Device>, 
+    pub functions: FunctionEnvironment, 
+    pub variables: HashMap<String, f64>, // circuit parameters and built-in variables
+}
+
+impl Netlist {
+    /// Returns all node names in order of appearance 
+    pub fn get_nodes(&self) -> Vec<String> {
+        let mut nodes: Vec<String> = vec![]; 
+        for device in &self.devices {
+            for node in &device.nodes {
+                if !nodes.contains(node) {
+                    nodes.push(node.clone()); 
+                }
+            }
+        }
+
+        nodes
+    }
+
+    pub fn get_device(&self, name: &str) -> Option<&Device> {
+        self.devices.iter().find(|x| x.name == name)
+    }
+
+    /// Evaluates parameters of the device, `variables` (built-in variables 
+    /// and instance quantities) override variables of the netlist 
+    pub fn evaluate_params(&self, device: &Device, variables: &HashMap<String, f64>) -> Result<Vec<f64>, Box<ErrorEntry>> {
+        let mut all_variables = self.variables.clone(); 
+        all_variables.extend(variables.iter().map(|(name, value)| (name.clone(), *value))); 
+
+        device.param_expressions.iter()
+            .map(|x| x.evaluate(&self.functions, &all_variables))
+            .collect()
+    }
+
+    /// Evaluates parameters of the device in complex numbers at the 
+    /// frequency `freq`: 'freq' is set to it and 's' to j * 2 * pi * freq 
+    pub fn evaluate_ac_params(&self, device: &Device, freq: f64) -> Result<Vec<Complex>, Box<ErrorEntry>> {
+        let mut variables: HashMap<String, Complex> = self.variables.iter()
+            .map(|(name, value)| (name.clone(), Complex::from(*value)))
+            .collect(); 
+
+        variables.insert(variable::FREQUENCY.to_string(), Complex::from(freq)); 
+        variables.insert(variable::LAPLACE.to_string(), Complex::new(0.0, 2.0 * std::f64::consts::PI * freq)); 
+
+        device.param_expressions.iter()
+            .map(|x| x.evaluate_complex(&self.functions, &variables))
+            .collect()
+    }
+}
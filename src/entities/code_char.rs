@@ -4,5 +4,6 @@ use crate::entities::Position;
 pub struct CodeChar {
     pub symbol: char,
     pub position: Position,
+    pub offset: usize, // byte offset in the code
 }
 
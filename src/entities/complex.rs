@@ -0,0 +1,134 @@
+use std::fmt::Formatter;
+use std::fmt::Display; 
+use std::ops::Add; 
+use std::ops::Sub; 
+use std::ops::Mul; 
+use std::ops::Div; 
+use std::ops::Neg; 
+
+/// Complex number for the small-signal (AC) analysis 
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Complex {
+    pub re: f64, 
+    pub im: f64, 
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn from_polar(abs: f64, arg: f64) -> Complex {
+        Complex::new(abs * arg.cos(), abs * arg.sin())
+    }
+
+    pub fn abs(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Argument in radians 
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Complex {
+        Complex::new(self.re, -self.im)
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    pub fn exp(&self) -> Complex {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Principal value of the logarithm 
+    pub fn ln(&self) -> Complex {
+        Complex::new(self.abs().ln(), self.arg())
+    }
+
+    pub fn sqrt(&self) -> Complex {
+        Complex::from_polar(self.abs().sqrt(), self.arg() / 2.0)
+    }
+
+    pub fn pow(&self, exponent: Complex) -> Complex {
+        if self.re == 0.0 && self.im == 0.0 {
+            return match exponent.re == 0.0 && exponent.im == 0.0 {
+                true => Complex::new(1.0, 0.0), 
+                false => Complex::new(0.0, 0.0), 
+            }; 
+        }
+
+        (self.ln() * exponent).exp()
+    }
+
+    pub fn sin(&self) -> Complex {
+        Complex::new(self.re.sin() * self.im.cosh(), self.re.cos() * self.im.sinh())
+    }
+
+    pub fn cos(&self) -> Complex {
+        Complex::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+
+    pub fn sinh(&self) -> Complex {
+        Complex::new(self.re.sinh() * self.im.cos(), self.re.cosh() * self.im.sin())
+    }
+
+    pub fn cosh(&self) -> Complex {
+        Complex::new(self.re.cosh() * self.im.cos(), self.re.sinh() * self.im.sin())
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Complex {
+        Complex::new(re, 0.0)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex; 
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex; 
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex; 
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex; 
+    fn div(self, other: Complex) -> Complex {
+        let norm = other.re * other.re + other.im * other.im; 
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / norm, 
+            (self.im * other.re - self.re * other.im) / norm)
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex; 
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self.im < 0.0 {
+            true => write!(f, "{}-{}j", self.re, -self.im), 
+            false => write!(f, "{}+{}j", self.re, self.im), 
+        }
+    }
+}
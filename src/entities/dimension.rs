@@ -0,0 +1,101 @@
+use std::fmt::Display; 
+use std::fmt::Formatter; 
+use std::ops::Div; 
+use std::ops::Mul; 
+
+/// Physical dimension as exponents of the SI base units: kg, m, s, A
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct Dimension {
+    exponents: [i32; 4], 
+}
+
+const BASE_UNITS: [&str; 4] = ["kg", "m", "s", "A"]; 
+
+/// Units of number literals and their dimensions
+const NAMED_UNITS: [(&str, Dimension); 8] = [
+    ("V", Dimension { exponents: [1, 2, -3, -1] }), 
+    ("A", Dimension { exponents: [0, 0, 0, 1] }), 
+    ("Ohm", Dimension { exponents: [1, 2, -3, -2] }), 
+    ("F", Dimension { exponents: [-1, -2, 4, 2] }), 
+    ("H", Dimension { exponents: [1, 2, -2, -2] }), 
+    ("W", Dimension { exponents: [1, 2, -3, 0] }), 
+    ("s", Dimension { exponents: [0, 0, 1, 0] }), 
+    ("Hz", Dimension { exponents: [0, 0, -1, 0] }), 
+]; 
+
+impl Dimension {
+    pub fn dimensionless() -> Dimension {
+        Dimension::default()
+    }
+
+    /// Dimension of the unit, e.g. 'Ohm'. The empty unit is dimensionless.
+    pub fn from_unit(unit: &str) -> Option<Dimension> {
+        if unit.is_empty() {
+            return Option::Some(Dimension::dimensionless()); 
+        }
+
+        NAMED_UNITS.iter().find(|x| x.0 == unit).map(|x| x.1)
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        *self == Dimension::dimensionless()
+    }
+
+    pub fn powi(&self, power: i32) -> Dimension {
+        Dimension { exponents: self.exponents.map(|x| x * power) }
+    }
+
+    /// Square root, `None` if some exponent is odd
+    pub fn sqrt(&self) -> Option<Dimension> {
+        match self.exponents.iter().all(|x| x % 2 == 0) {
+            true => Option::Some(Dimension { exponents: self.exponents.map(|x| x / 2) }), 
+            false => Option::None, 
+        }
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension; 
+    // Exponents of base units add up in the product 
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Dimension) -> Dimension {
+        let mut exponents = self.exponents; 
+        for (x, y) in exponents.iter_mut().zip(other.exponents) {
+            *x += y; 
+        }
+
+        Dimension { exponents }
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension; 
+    fn div(self, other: Dimension) -> Dimension {
+        self * other.powi(-1)
+    }
+}
+
+/// Prints the named unit if there is one, e.g. 'Ohm', otherwise
+/// the product of base units, e.g. 'kg*m^2*s^-3'
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "dimensionless"); 
+        }
+
+        if let Option::Some((name, _)) = NAMED_UNITS.iter().find(|x| x.1 == *self) {
+            return write!(f, "{}", name); 
+        }
+
+        let parts: Vec<String> = BASE_UNITS.iter()
+            .zip(self.exponents)
+            .filter(|x| x.1 != 0)
+            .map(|(unit, power)| match power {
+                1 => unit.to_string(), 
+                _ => format!("{}^{}", unit, power), 
+            })
+            .collect(); 
+
+        write!(f, "{}", parts.join("*"))
+    }
+}
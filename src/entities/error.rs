@@ -1,4 +1,7 @@
 use crate::entities::Position; 
+use crate::entities::Span; 
+use crate::diagnostics::Severity; 
+use crate::diagnostics::Label; 
 
 #[derive(Clone)]
 pub struct Error<'a> {
@@ -9,51 +12,92 @@ pub struct Error<'a> {
 #[derive(Clone)]
 pub struct ErrorEntry {
     pub error: &'static Error<'static>, // ref to global errors objects
-    pub position: Position,
+    pub position: Option<Position>, // `None` for diagnostics of the whole circuit, e.g. the singular matrix
+    pub end_position: Option<Position>, // end of the primary span, the token at the position is underlined without it
     pub args: Vec<String>,
     pub file_name: String,
+    pub severity: Severity, 
+    pub labels: Vec<Label>, // secondary spans in the same file
+    pub help: Option<String>, 
 }
 
 impl ErrorEntry {
     pub fn new(error: &'static Error, position: &Position, args: &Vec<String>) -> ErrorEntry {
         ErrorEntry {
-            error: error,
-            position: position.clone(),
+            error,
+            position: Option::Some(position.clone()),
+            end_position: Option::None, 
             args: args.clone(),
             file_name: format!(""),
+            severity: Severity::Error, 
+            labels: vec![], 
+            help: Option::None, 
         }
     }
 
-    //pub tokens: Vec<Token>,
-    pub fn console_print(&self) {
-        let file_name = match self.file_name == format!("") {
-            true => format!("CODE"),
-            false => self.file_name.clone(),   
-        };
+    /// Diagnostic without the location in the source 
+    pub fn global(error: &'static Error, args: &Vec<String>) -> ErrorEntry {
+        ErrorEntry {
+            position: Option::None, 
+            ..ErrorEntry::new(error, &Position { line: 0, line_position: 0 }, args)
+        }
+    }
+
+    /// Diagnostic which doesn't stop the compilation 
+    pub fn warning(error: &'static Error, position: &Position, args: &Vec<String>) -> ErrorEntry {
+        ErrorEntry {
+            severity: Severity::Warning, 
+            ..ErrorEntry::new(error, position, args)
+        }
+    }
+
+    /// Sets the file name unless the error already has one 
+    pub fn in_file(mut self, file_name: &str) -> ErrorEntry {
+        if self.file_name.is_empty() {
+            self.file_name = file_name.to_string(); 
+        }
 
-        println!("{} {}:", file_name, self.position);
-        
-        let mut i: usize = 1; 
+        self
+    }
+
+    /// Points to the whole span, e.g. the statement, instead of one token 
+    pub fn with_span(mut self, span: &Span) -> ErrorEntry {
+        self.position = Option::Some(span.position.clone()); 
+        self.end_position = Option::Some(span.end_position.clone()); 
+        self
+    }
+
+    pub fn with_label(mut self, position: &Position, message: &str) -> ErrorEntry {
+        self.labels.push(Label { position: position.clone(), message: message.to_string() }); 
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> ErrorEntry {
+        self.help = Option::Some(help.to_string()); 
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Message with arguments in place of '~1~', '~2~', ... 
+    pub fn get_message(&self) -> String {
         let mut msg: String = self.error.message.to_string(); 
-        for arg in &self.args {
-            let entry: String = format!("~{}~", i);
-            msg = msg.replace(&entry, arg);
-            //msg = std::str::replace(msg, "")
-            i += 1;
+        for (i, arg) in self.args.iter().enumerate() {
+            msg = msg.replace(&format!("~{}~", i + 1), arg); 
         }
 
-        println!("{}", msg); 
+        msg.trim_end().to_string()
     }
 }
 
-#[allow(dead_code)]
-pub fn apply_file_name(errs: &Vec<ErrorEntry>, file_name: &String) -> Vec<ErrorEntry> {
+
+pub fn apply_file_name(errs: &[ErrorEntry], file_name: &str) -> Vec<ErrorEntry> {
     errs.iter() 
         .map(|x| ErrorEntry{
-            position: x.position.clone(),
-            error: x.error,
-            args: x.args.clone(),
-            file_name: file_name.clone(),
+            file_name: file_name.to_string(),
+            ..x.clone()
         })
         .collect::<Vec<ErrorEntry>>()
 }
@@ -61,88 +105,378 @@ pub fn apply_file_name(errs: &Vec<ErrorEntry>, file_name: &String) -> Vec<ErrorE
 pub mod errors {
     use crate::entities::Error;
 
-    pub const UNDEFINED_SYMBOL: &'static Error = &Error{
+    pub const UNDEFINED_SYMBOL: &Error = &Error{
         code: "STX001",
         message: "Undefined symbol: '~1~'. ", 
     };    
 
-    pub const UNFINISHED_COMMAND: &'static Error = &Error{
+    pub const UNFINISHED_COMMAND: &Error = &Error{
         code: "STX002",
         message: "Unfinished command. Missing separator. "
     }; 
 
-    pub const OPEN_CLOSED_BRACKETS: &'static Error = &Error{
+    pub const OPEN_CLOSED_BRACKETS: &Error = &Error{
         code: "STX003", 
         message: "Discrepancy in the number of open and closed brackets. "
     };
 
-    pub const UNDEFINED_STATEMENT: &'static Error = &Error{
+    pub const UNDEFINED_STATEMENT: &Error = &Error{
         code: "STX004",
         message: "Undefined statement: '~1~'. "
     };
 
-    pub const NAME_KEYWORD: &'static Error = &Error{
+    pub const NAME_KEYWORD: &Error = &Error{
         code: "STX005",
         message: "Expected unqualified-id, found '~1~'. ", 
     };
 
-    pub const NO_SUBSTATEMENTS: &'static Error = &Error{
+    pub const NO_SUBSTATEMENTS: &Error = &Error{
         code: "STX006",
         message: "This statement should have substatements. ", //TODO: Change
     };
 
-    pub const ARE_SUBSTATEMENTS: &'static Error = &Error{
+    pub const ARE_SUBSTATEMENTS: &Error = &Error{
         code: "STX007",
         message: "This statement shouldn't have substatements. ", 
     };
 
-    pub const WROND_COMMAND_SEMANTIC: &'static Error = &Error{
+    pub const WROND_COMMAND_SEMANTIC: &Error = &Error{
         code: "STX008", 
         message: "Wrong statement semantic. Expected: '~1~'. ",
     };
 
-    pub const INVALID_NAME: &'static Error = &Error{
+    pub const INVALID_NAME: &Error = &Error{
         code: "STX009", 
         message: "Invalid name: '~1~'. "
     }; 
 
-    pub const UNEXPECTED_SYMBOL: &'static Error = &Error{
+    pub const UNEXPECTED_SYMBOL: &Error = &Error{
         code: "STX010",
         message: "Expected '~1~', found '~2~'. ",
     };
 
-    pub const SYMBOL_NOT_FOUND: &'static Error = &Error{
+    pub const SYMBOL_NOT_FOUND: &Error = &Error{
         code: "STX011",
         message: "Symbol '~1~' not found. ",
     };
 
-    pub const UNDEFINED_UNARY_OPERATOR: &'static Error = &Error{
+    pub const UNDEFINED_UNARY_OPERATOR: &Error = &Error{
         code: "STX012", 
         message: "Undefined unary operator '~1~'. "
     };
 
-    pub const EXPRESSION_LAST_SYMBOL: &'static Error = &Error{
+    pub const EXPRESSION_LAST_SYMBOL: &Error = &Error{
         code: "STX013",
         message: "Last element of expression can't be an operator. ", 
     };
 
-    pub const EXPECTED_BINARY_OPERATOR: &'static Error = &Error{
+    pub const EXPECTED_BINARY_OPERATOR: &Error = &Error{
         code: "STX014", 
         message: "Expected binary operator, found '~1~'. "
     };
 
-    pub const EXPECTED_NUM_VAR: &'static Error = &Error{
+    pub const EXPECTED_NUM_VAR: &Error = &Error{
         code: "STX015",
         message: "Expected number or variable, found '~1~'. "
     };
 
-    pub const EXPRESSION_SHOULD_RET_NUMBER: &'static Error = &Error{
+    pub const EXPRESSION_SHOULD_RET_NUMBER: &Error = &Error{
         code: "STX016",
         message: "Expression should return a number. ",
     };
 
-    pub const NO_VAR_AFTER_UNARY: &'static Error = &Error{
+    pub const NO_VAR_AFTER_UNARY: &Error = &Error{
         code: "STX017",
         message: "No statement after unary operator. "
     };
-}
\ No newline at end of file
+
+    pub const INVALID_MEMBER_ACCESS: &Error = &Error{
+        code: "STX018",
+        message: "Invalid member access '~1~', expected 'instance.member'. ",
+    };
+
+    pub const INVALID_NUMBER: &Error = &Error{
+        code: "STX019",
+        message: "Invalid number '~1~'. ",
+    };
+
+    pub const EXPECTED_ARGUMENT: &Error = &Error{
+        code: "STX020",
+        message: "Expected ~1~, found '~2~'. ",
+    };
+
+    pub const EXPECTED_COMMA: &Error = &Error{
+        code: "STX021",
+        message: "Expected ',' between arguments, found '~1~'. ",
+    };
+
+    pub const UNMATCHED_CLOSED_BRACKET: &Error = &Error{
+        code: "STX022",
+        message: "Closed bracket ')' doesn't match any opened one. ",
+    };
+
+    pub const UNCLOSED_BRACKET: &Error = &Error{
+        code: "STX023",
+        message: "Opened bracket '(' isn't closed. ",
+    };
+
+    pub const MISSING_ASSIGNMENT: &Error = &Error{
+        code: "STX024",
+        message: "Expected '=' after nodes of '~1~', found '~2~'. ",
+    };
+
+    pub const EMPTY_PARAMETER: &Error = &Error{
+        code: "STX025",
+        message: "Parameter ~1~ of '~2~' is empty. ",
+    };
+
+    pub const EMPTY_EXPRESSION: &Error = &Error{
+        code: "EVL001",
+        message: "Expression is empty. ",
+    };
+
+    pub const UNDEFINED_VARIABLE: &Error = &Error{
+        code: "EVL002",
+        message: "Undefined variable: '~1~'. ",
+    };
+
+    pub const UNDEFINED_FUNCTION: &Error = &Error{
+        code: "EVL003",
+        message: "Undefined function: '~1~'. ",
+    };
+
+    pub const WRONG_ARGS_COUNT: &Error = &Error{
+        code: "EVL004",
+        message: "Function '~1~' takes ~2~ argument(s), but ~3~ given. ",
+    };
+
+    pub const INVALID_FUNCTION_ARGS: &Error = &Error{
+        code: "EVL005",
+        message: "Invalid arguments of '~1~': result is not a finite number. ",
+    };
+
+    pub const RECURSIVE_FUNCTION: &Error = &Error{
+        code: "EVL006",
+        message: "Recursive call of function '~1~': ~2~. ",
+    };
+
+    pub const UNDEFINED_ELEMENT: &Error = &Error{
+        code: "SEM001",
+        message: "Undefined element: '~1~'. ",
+    };
+
+    pub const UNDEFINED_PARAMETER: &Error = &Error{
+        code: "SEM002",
+        message: "Undefined parameter: '~1~'. ",
+    };
+
+    pub const UNRESOLVED_FUNCTION: &Error = &Error{
+        code: "SEM003",
+        message: "Undefined function: '~1~'. ",
+    };
+
+    pub const DUPLICATE_DEFINITION: &Error = &Error{
+        code: "SEM004",
+        message: "Name '~1~' is defined multiple times. ",
+    };
+
+    pub const SHADOWED_NAME: &Error = &Error{
+        code: "SEM005",
+        message: "Name '~1~' shadows the ~2~ with the same name. ",
+    };
+
+    pub const WRONG_NODES_COUNT: &Error = &Error{
+        code: "SEM006",
+        message: "Element '~1~' has ~2~ node(s), but ~3~ given. ",
+    };
+
+    pub const WRONG_PARAMS_COUNT: &Error = &Error{
+        code: "SEM007",
+        message: "Element '~1~' takes ~2~ parameter(s), but ~3~ given. ",
+    };
+
+    pub const UNDEFINED_CIRCUIT: &Error = &Error{
+        code: "ELB001",
+        message: "Circuit '~1~' not found. ",
+    };
+
+    pub const AMBIGUOUS_CIRCUIT: &Error = &Error{
+        code: "ELB002",
+        message: "Can't choose the circuit to elaborate: file contains ~1~ circuits. ",
+    };
+
+    pub const RECURSIVE_ELEMENT: &Error = &Error{
+        code: "ELB003",
+        message: "Recursive element definition: ~1~. Instantiated at ~2~. ",
+    };
+
+    pub const NO_GROUND: &Error = &Error{
+        code: "SIM001",
+        message: "Circuit '~1~' has no ground node '0'. ",
+    };
+
+    pub const INVALID_PARAM_VALUE: &Error = &Error{
+        code: "SIM002",
+        message: "Invalid parameter value of '~1~': ~2~. ",
+    };
+
+    pub const SINGULAR_MATRIX: &Error = &Error{
+        code: "SIM003",
+        message: "Circuit matrix is singular. Check for floating nodes and loops of voltage sources. ",
+    };
+
+    pub const SINGULAR_MATRIX_AT_TIME: &Error = &Error{
+        code: "SIM004",
+        message: "Circuit matrix is singular at time ~1~. ",
+    };
+
+    pub const SINGULAR_MATRIX_AT_FREQUENCY: &Error = &Error{
+        code: "SIM005",
+        message: "Circuit matrix is singular at frequency ~1~ Hz. ",
+    };
+
+    pub const UNDEFINED_INPUT: &Error = &Error{
+        code: "SIM006",
+        message: "Input '~1~' is not a source of the circuit. ",
+    };
+
+    pub const CONTROLLED_PRIMITIVE: &Error = &Error{
+        code: "ELB004",
+        message: "Parameters of '~1~' depend on other instances, but only current sources can be controlled. ",
+    };
+
+    pub const UNDEFINED_CONTROL: &Error = &Error{
+        code: "ELB005",
+        message: "Quantity '~1~' refers to '~2~', which is not a primitive instance. ",
+    };
+
+    pub const NO_CONVERGENCE: &Error = &Error{
+        code: "SIM007",
+        message: "Newton-Raphson iteration does not converge, instance '~1~' doesn't settle (~2~). ",
+    };
+
+    pub const TIME_STEP_TOO_SMALL: &Error = &Error{
+        code: "SIM008",
+        message: "Time step is too small at time ~1~, the transient analysis doesn't converge. ",
+    };
+
+    pub const AMBIGUOUS_INPUT: &Error = &Error{
+        code: "SIM009",
+        message: "Circuit '~1~' has ~2~ independent sources, choose the input of the AC analysis with '--input'. ",
+    };
+
+    pub const UNDEFINED_INSTANCE: &Error = &Error{
+        code: "SEM008",
+        message: "Instance '~1~' is not defined in this scope. ",
+    };
+
+    pub const UNDEFINED_MEMBER: &Error = &Error{
+        code: "SEM009",
+        message: "Instance '~1~' of '~2~' has no member '~3~'. ",
+    };
+
+    pub const RECURSIVE_MEMBER: &Error = &Error{
+        code: "SEM010",
+        message: "Parameter of '~1~' depends on itself: ~2~. ",
+    };
+
+    pub const CONTROL_CYCLE: &Error = &Error{
+        code: "ELB006",
+        message: "Controlled sources depend on each other: ~1~. ",
+    };
+
+    pub const FLOATING_NODE: &Error = &Error{
+        code: "ELB007",
+        message: "Node '~1~' is floating: ~2~. ",
+    };
+
+    pub const PACKAGE_NOT_FOUND: &Error = &Error{
+        code: "PKG001",
+        message: "Package '~1~' not found. Searched in: ~2~. ",
+    };
+
+    pub const IMPORT_CYCLE: &Error = &Error{
+        code: "PKG002",
+        message: "Import cycle: ~1~. ",
+    };
+
+    pub const PACKAGE_NAME_MISMATCH: &Error = &Error{
+        code: "PKG003",
+        message: "File declares package '~1~', but it's imported as '~2~'. ",
+    };
+
+    pub const PACKAGE_NOT_IMPORTED: &Error = &Error{
+        code: "PKG004",
+        message: "Name '~1~' belongs to package '~2~', which is not imported. ",
+    };
+
+    pub const FILE_READ_ERROR: &Error = &Error{
+        code: "PKG005",
+        message: "Can't read file '~1~'. ",
+    };
+
+    pub const INVALID_PRIMITIVE: &Error = &Error{
+        code: "SEM011",
+        message: "Primitive '~1~' doesn't match the built-in model: ~2~. ",
+    };
+
+    pub const PRIMITIVE_OUTSIDE_STD: &Error = &Error{
+        code: "SEM012",
+        message: "Primitive '~1~' can be declared only in the standard package. ",
+    };
+
+    pub const UNSUPPORTED_EXPORT: &Error = &Error{
+        code: "SPX001",
+        message: "Parameter of '~1~' can't be exported to SPICE: ~2~. ",
+    };
+
+    pub const UNSUPPORTED_SPICE_CARD: &Error = &Error{
+        code: "SPI001",
+        message: "SPICE card '~1~' isn't supported. ",
+    };
+
+    pub const INVALID_SPICE_CARD: &Error = &Error{
+        code: "SPI002",
+        message: "Invalid SPICE card '~1~': ~2~. ",
+    };
+
+    pub const UNDEFINED_SPICE_MODEL: &Error = &Error{
+        code: "SPI003",
+        message: "Model '~1~' isn't defined. ",
+    };
+
+    pub const UNDEFINED_SPICE_SUBCIRCUIT: &Error = &Error{
+        code: "SPI004",
+        message: "Sub-circuit '~1~' isn't defined. ",
+    };
+
+    pub const UNSUPPORTED_SPICE_OPTION: &Error = &Error{
+        code: "SPI005",
+        message: "Option '~1~' of SPICE card '~2~' isn't supported. ",
+    };
+
+    pub const INVALID_SPICE_EXPRESSION: &Error = &Error{
+        code: "SPI006",
+        message: "Invalid SPICE expression '~1~': ~2~. ",
+    };
+
+    pub const UNSUPPORTED_SPICE_SUBSTRATE: &Error = &Error{
+        code: "SPI007",
+        message: "Substrate node '~1~' of SPICE card '~2~' isn't supported and is left unconnected. ",
+    };
+
+    pub const INCOMPATIBLE_DIMENSIONS: &Error = &Error{
+        code: "DIM001",
+        message: "Operands of '~1~' have incompatible units: ~2~ and ~3~. ",
+    };
+
+    pub const DIMENSIONLESS_EXPECTED: &Error = &Error{
+        code: "DIM002",
+        message: "Argument of '~1~' should be dimensionless, found ~2~. ",
+    };
+
+    pub const DIMENSION_MISMATCH: &Error = &Error{
+        code: "DIM003",
+        message: "Parameter '~1~' of '~2~' expects ~3~, found ~4~. ",
+    };
+}
@@ -4,15 +4,20 @@ use crate::entities::TokenType;
 use std::collections::HashMap; 
 use std::fmt::Formatter;
 use std::fmt::Display; 
+use crate::entities::Error;
 use crate::entities::ErrorEntry;
 use crate::entities::errors;
 use crate::entities::Position;
+use crate::entities::Span;
+use crate::entities::FunctionEnvironment; 
+use crate::entities::Complex; 
+use crate::entities::Dimension; 
 use std::f64;
 
 #[derive(Clone)]
 pub struct Expression {
-    pub tokens: Vec<Token>,
-    root: Option<Node>, 
+    pub span: Span,
+    root: Option<ExpressionNode>, 
 }
 
 impl Display for Expression {
@@ -24,558 +29,1101 @@ impl Display for Expression {
     }
 }
 
+/// Node of the expression tree with the span of its source. Brackets 
+/// around the node are not a part of the span. 
 #[derive(Clone)]
-enum Node {
-    Number(String), 
+pub enum ExpressionNode {
+    Number(String, Option<String>, Span), // number with its unit or variable name
+    Member(String, String, Span), // instance and member names, e.g. 'JK.U'
     Operator(OperatorNode),
 }
 
-impl Display for Node {
+impl ExpressionNode {
+    pub fn span(&self) -> &Span {
+        match self {
+            ExpressionNode::Number(_, _, span) => span, 
+            ExpressionNode::Member(_, _, span) => span, 
+            ExpressionNode::Operator(operator) => &operator.span, 
+        }
+    }
+}
+
+impl Display for ExpressionNode {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        let string = node_to_str(self);
+        let string = node_to_str(self, &|x| x.to_string());
         write!(f, "{}", string)
     }
 }
 
-fn node_to_str(node: &Node) -> String {
+/// Prints the node in fully parenthesised form: `((a - b) - c)`, `(-x)`, `f(a, b)`. 
+/// Names of variables, members and functions are replaced by `rename`. 
+fn node_to_str(node: &ExpressionNode, rename: &dyn Fn(&str) -> String) -> String {
     match node {
-        Node::Number(num) => { format!("{}", num) }
-        Node::Operator(op_node) => {
-            let mut res = op_node.name.clone(); 
-            let mut next = false; 
+        ExpressionNode::Number(num, _, _) if num.parse::<f64>().is_ok() => num.clone(),
+        ExpressionNode::Number(name, _, _) => rename(name), 
+        ExpressionNode::Member(instance, member, _) => rename(&format!("{}.{}", instance, member)), 
+        ExpressionNode::Operator(op_node) => {
+            let args: Vec<String> = op_node.nodes.iter()
+                .map(|x| node_to_str(x, rename))
+                .collect(); 
             
-            res.push_str("["); 
-            for inner_node in &op_node.nodes {
-                let string = node_to_str(&inner_node); 
-                if next {
-                    res.push_str(" "); 
-                }
-                else {
-                    next = true; 
-                }
-
-                res.push_str(format!("{}", string).as_str()); 
+            match (is_operator_name(&op_node.name), args.len()) {
+                (true, 1) => format!("({}{})", op_node.name, args[0]),
+                (true, 2) => format!("({} {} {})", args[0], op_node.name, args[1]),
+                (true, 3) => format!("({} ? {} : {})", args[0], args[1], args[2]),
+                _ => format!("{}({})", rename(&op_node.name), args.join(", ")),
             }
-            res.push_str("]"); 
-
-            res
         },
     }
 }
 
-struct OperatorNode {
-    name: String, 
-    nodes: Vec<Node>,
+/// Operator or function call, `nodes` are its operands 
+#[derive(Clone)]
+pub struct OperatorNode {
+    pub name: String, 
+    pub nodes: Vec<ExpressionNode>,
+    pub name_span: Span, // the operator symbol or the function name
+    pub span: Span, // the whole operation
+}
+
+/// State of the evaluation: variables of the current scope and names 
+/// of user functions being evaluated (to detect recursion). 
+struct EvaluationContext<'a, T: Value> {
+    input: &'a HashMap<String, T>, 
+    functions: &'a FunctionEnvironment, 
+    call_stack: &'a mut Vec<String>, 
+}
+
+/// Number type the expression can be evaluated in: real numbers for 
+/// DC and transient analyses, complex numbers for the AC analysis. 
+trait Value: Copy + 'static {
+    fn from_real(value: f64) -> Self; 
+
+    /// The value if it is real, `None` otherwise 
+    fn to_real(&self) -> Option<f64>; 
+
+    fn is_true(&self) -> bool; 
+
+    fn is_finite(&self) -> bool; 
+
+    /// Table of standard functions for this type, sorted by 
+    /// name and amount of arguments 
+    fn functions() -> &'static [&'static Function<'static, Self>]; 
 }
 
-impl Clone for OperatorNode {
-    fn clone(&self) -> Self {
-        Self {
-            name: self.name.clone(),
-            nodes: self.nodes.to_vec(), 
+impl Value for f64 {
+    fn from_real(value: f64) -> f64 {
+        value
+    }
+
+    fn to_real(&self) -> Option<f64> {
+        Option::Some(*self)
+    }
+
+    fn is_true(&self) -> bool {
+        *self != 0.0
+    }
+
+    fn is_finite(&self) -> bool {
+        f64::is_finite(*self)
+    }
+
+    fn functions() -> &'static [&'static Function<'static, f64>] {
+        STANDART_FUNCTIONS
+    }
+}
+
+impl Value for Complex {
+    fn from_real(value: f64) -> Complex {
+        Complex::from(value)
+    }
+
+    fn to_real(&self) -> Option<f64> {
+        match self.im == 0.0 {
+            true => Option::Some(self.re), 
+            false => Option::None, 
         }
     }
+
+    fn is_true(&self) -> bool {
+        self.re != 0.0 || self.im != 0.0
+    }
+
+    fn is_finite(&self) -> bool {
+        Complex::is_finite(self)
+    }
+
+    fn functions() -> &'static [&'static Function<'static, Complex>] {
+        COMPLEX_FUNCTIONS
+    }
 }
 
 impl Expression {
-    #[allow(dead_code)]
-    pub fn evaluate(&self, input: &HashMap<String, f64>) -> Option<f64> {
-        match self.root.clone() {
+    /// Root of the expression tree 
+    pub fn root(&self) -> Option<&ExpressionNode> {
+        self.root.as_ref()
+    }
+
+    /// Returns names and positions of all variables used in the expression 
+    pub fn get_variables(&self) -> Vec<(String, Position)> {
+        let mut variables: Vec<(String, Position)> = vec![]; 
+        if let Option::Some(node) = &self.root {
+            collect_variables(node, &mut variables); 
+        }
+
+        variables
+    }
+
+    /// Returns instance names, member names and positions of all member 
+    /// accesses (e.g. 'JK.U') used in the expression 
+    pub fn get_members(&self) -> Vec<(String, String, Position)> {
+        let mut members: Vec<(String, String, Position)> = vec![]; 
+        if let Option::Some(node) = &self.root {
+            collect_members(node, &mut members); 
+        }
+
+        members
+    }
+
+    /// Returns names, amount of arguments and positions of all 
+    /// function calls (operators excluded) used in the expression 
+    pub fn get_calls(&self) -> Vec<(String, usize, Position)> {
+        let mut calls: Vec<(String, usize, Position)> = vec![]; 
+        if let Option::Some(node) = &self.root {
+            collect_calls(node, &mut calls); 
+        }
+
+        calls
+    }
+
+    /// Infers the physical dimension of the expression from units of 
+    /// literals, `None` if it's unknown. Numbers without units may have 
+    /// any dimension. `lookup` gives dimensions of variables and members 
+    /// (e.g. 'time' or 'JK.U'). Mixed dimensions are reported to `warns_acc`. 
+    pub fn infer_dimension(&self, lookup: &dyn Fn(&str) -> Option<Dimension>, warns_acc: &mut Vec<ErrorEntry>) -> Option<Dimension> {
+        self.root.as_ref().and_then(|x| infer_node(x, lookup, warns_acc))
+    }
+
+    /// Prints the expression like `to_string`, but names of variables, 
+    /// members and called functions are replaced by `rename` 
+    pub fn to_string_renamed(&self, rename: &dyn Fn(&str) -> String) -> String {
+        match &self.root {
+            Option::None => "[NULL EXPRESSION]".to_string(), 
+            Option::Some(node) => node_to_str(node, rename), 
+        }
+    }
+
+    /// Renames called functions, e.g. 'F9' to 'std.F9'. Names missing 
+    /// in `names` are left as is. 
+    pub fn rename_calls(&mut self, names: &HashMap<String, String>) {
+        if let Option::Some(node) = &mut self.root {
+            rename_calls(node, names); 
+        }
+    }
+
+    /// Evaluates the expression. Variables are taken from `input`, 
+    /// function calls are resolved in `functions` and then in 
+    /// `STANDART_FUNCTIONS`. 
+    pub fn evaluate(&self, input: &HashMap<String, f64>, functions: &FunctionEnvironment) -> Result<f64, Box<ErrorEntry>> {
+        self.evaluate_in(input, functions, &mut vec![])
+    }
+
+    /// Evaluates the expression in complex numbers. Functions which are 
+    /// defined only for real numbers (comparisons, 'floor', etc.) accept 
+    /// complex arguments with zero imaginary part. 
+    pub fn evaluate_complex(&self, input: &HashMap<String, Complex>, functions: &FunctionEnvironment) -> Result<Complex, Box<ErrorEntry>> {
+        self.evaluate_in(input, functions, &mut vec![])
+    }
+
+    fn evaluate_in<T: Value>(&self, input: &HashMap<String, T>, functions: &FunctionEnvironment, call_stack: &mut Vec<String>) -> Result<T, Box<ErrorEntry>> {
+        match &self.root {
             Option::Some(node) => {
-                evaluate_node(&node, input)
+                let mut context = EvaluationContext {
+                    input, 
+                    functions, 
+                    call_stack, 
+                }; 
+
+                evaluate_node(node, &mut context)
+            },
+            Option::None => {
+                Result::Err(Box::new(ErrorEntry::new(errors::EMPTY_EXPRESSION, &self.span.position, &vec![])))
             },
-            Option::None => Option::None,
         }
     }
 }
 
-#[allow(dead_code)]
-fn evaluate_node(node: &Node, input: &HashMap<String, f64>) -> Option<f64> {
+fn collect_variables(node: &ExpressionNode, acc: &mut Vec<(String, Position)>) {
+    match node {
+        ExpressionNode::Number(value, _, span) => {
+            if value.parse::<f64>().is_err() {
+                acc.push((value.clone(), span.position.clone())); 
+            }
+        },
+        ExpressionNode::Operator(operator) => {
+            for inner_node in &operator.nodes {
+                collect_variables(inner_node, acc); 
+            }
+        },
+        ExpressionNode::Member(_, _, _) => { }, 
+    }
+}
+
+fn rename_calls(node: &mut ExpressionNode, names: &HashMap<String, String>) {
+    if let ExpressionNode::Operator(operator) = node {
+        if let Option::Some(name) = names.get(&operator.name) {
+            operator.name = name.clone(); 
+        }
+
+        for inner_node in &mut operator.nodes {
+            rename_calls(inner_node, names); 
+        }
+    }
+}
+
+fn collect_members(node: &ExpressionNode, acc: &mut Vec<(String, String, Position)>) {
     match node {
-        Node::Number(num_str) => {
+        ExpressionNode::Member(instance, member, span) => {
+            acc.push((instance.clone(), member.clone(), span.position.clone())); 
+        },
+        ExpressionNode::Operator(operator) => {
+            for inner_node in &operator.nodes {
+                collect_members(inner_node, acc); 
+            }
+        },
+        ExpressionNode::Number(_, _, _) => { }, 
+    }
+}
+
+fn collect_calls(node: &ExpressionNode, acc: &mut Vec<(String, usize, Position)>) {
+    if let ExpressionNode::Operator(operator) = node {
+        if !is_operator_name(&operator.name) {
+            acc.push((operator.name.clone(), operator.nodes.len(), operator.name_span.position.clone())); 
+        }
+
+        for inner_node in &operator.nodes {
+            collect_calls(inner_node, acc); 
+        }
+    }
+}
+
+fn infer_node(node: &ExpressionNode, lookup: &dyn Fn(&str) -> Option<Dimension>, warns_acc: &mut Vec<ErrorEntry>) -> Option<Dimension> {
+    let operator = match node {
+        ExpressionNode::Number(value, unit, _) if value.parse::<f64>().is_ok() => return unit.as_deref().and_then(Dimension::from_unit), 
+        ExpressionNode::Number(name, _, _) => return lookup(name), 
+        ExpressionNode::Member(instance, member, _) => return lookup(&format!("{}.{}", instance, member)), 
+        ExpressionNode::Operator(operator) => operator, 
+    }; 
+
+    let args: Vec<Option<Dimension>> = operator.nodes.iter()
+        .map(|x| infer_node(x, lookup, warns_acc))
+        .collect(); 
+
+    // Both operands of sums and comparisons have the same dimension 
+    let name = operator.name.as_str(); 
+    let unify = |x: Option<Dimension>, y: Option<Dimension>, warns_acc: &mut Vec<ErrorEntry>| match (x, y) {
+        (Option::Some(x), Option::Some(y)) if x != y => {
+            warns_acc.push(ErrorEntry::warning(
+                errors::INCOMPATIBLE_DIMENSIONS, 
+                &operator.name_span.position, 
+                &vec![name.to_string(), x.to_string(), y.to_string()])); 
+            Option::None
+        }, 
+        _ => x.or(y), 
+    }; 
+
+    match (name, args.len()) {
+        ("+" | "-" | "abs" | "re" | "im" | "conj" | "floor" | "ceil", 1) => args[0], 
+        ("+" | "-" | "%" | "min" | "max", 2) => unify(args[0], args[1], warns_acc), 
+        ("==" | "!=" | "<" | "<=" | ">" | ">=" | "atan2", 2) => {
+            unify(args[0], args[1], warns_acc); 
+            Option::None
+        }, 
+        ("*", 2) => Option::Some(args[0]? * args[1]?), 
+        ("/", 2) => Option::Some(args[0]? / args[1]?), 
+        (TERNARY_OPERATOR, 3) => unify(args[1], args[2], warns_acc), 
+        ("^" | "pow", 2) => {
+            check_dimensionless(name, &args[1..], &operator.name_span.position, warns_acc); 
+            match &operator.nodes[1] {
+                ExpressionNode::Number(power, _, _) => Option::Some(args[0]?.powi(power.parse::<i32>().ok()?)), 
+                _ => Option::None, 
+            }
+        }, 
+        ("sqrt", 1) => args[0]?.sqrt(), 
+        ("!" | "arg", 1) | ("&&" | "||", 2) => Option::None, 
+        _ if is_standart_function(name) => {
+            check_dimensionless(name, &args, &operator.name_span.position, warns_acc); 
+            Option::Some(Dimension::dimensionless())
+        }, 
+        _ => Option::None, // user functions
+    }
+}
+
+/// Transcendental functions (e.g. `exp`, `sin`) accept only dimensionless arguments 
+fn check_dimensionless(name: &str, args: &[Option<Dimension>], position: &Position, warns_acc: &mut Vec<ErrorEntry>) {
+    for dimension in args.iter().flatten().filter(|x| !x.is_dimensionless()) {
+        warns_acc.push(ErrorEntry::warning(
+            errors::DIMENSIONLESS_EXPECTED, 
+            position, 
+            &vec![name.to_string(), dimension.to_string()])); 
+    }
+}
+
+fn evaluate_node<T: Value>(node: &ExpressionNode, context: &mut EvaluationContext<T>) -> Result<T, Box<ErrorEntry>> {
+    match node {
+        ExpressionNode::Number(num_str, _, span) => {
             let is_num = num_str.parse::<f64>();
             match is_num {
-                Ok(num) => Option::Some(num),
-                Err(_) => {
-                    let get = input.get(num_str)?;
-                    Option::Some(*get)
+                Ok(num) => Result::Ok(T::from_real(num)),
+                Err(_) => match context.input.get(num_str) {
+                    Option::Some(value) => Result::Ok(*value), 
+                    Option::None => Result::Err(Box::new(ErrorEntry::new(
+                        errors::UNDEFINED_VARIABLE, 
+                        &span.position, 
+                        &vec![num_str.clone()]))), 
                 }
             }
         },
-        Node::Operator(operator) => {
-            let nums: Vec<Option<f64>> = operator.nodes.iter()
-                .map(|x| evaluate_node(x, input))
-                .collect(); 
-            
-            if nums.contains(&Option::None) {
-                Option::None 
+        ExpressionNode::Member(instance, member, span) => {
+            let name = format!("{}.{}", instance, member); 
+            match context.input.get(&name) {
+                Option::Some(value) => Result::Ok(*value), 
+                Option::None => Result::Err(Box::new(ErrorEntry::new(
+                    errors::UNDEFINED_VARIABLE, 
+                    &span.position, 
+                    &vec![name]))), 
             }
-            else {
-                let func = get_func(&operator.name)?; 
-                let nums: Vec<f64> = nums.iter() 
-                    .map(|x| x.expect(""))
-                    .collect(); 
-
-                if nums.len() != func.args_count {
-                    Option::None
-                }   
-                else {
-                    let f = func.lambda;
-                    f(&nums)
-                }
-            }   
+        },
+        ExpressionNode::Operator(operator) if operator.name == TERNARY_OPERATOR => {
+            // Only the chosen branch is evaluated
+            match evaluate_node(&operator.nodes[0], context)?.is_true() {
+                true => evaluate_node(&operator.nodes[1], context), 
+                false => evaluate_node(&operator.nodes[2], context), 
+            }
+        },
+        ExpressionNode::Operator(operator) if operator.name == "&&" || operator.name == "||" => {
+            // Short-circuit evaluation, the right operand may be invalid 
+            // when the left one already defines the result 
+            let left = evaluate_node(&operator.nodes[0], context)?.is_true(); 
+            if left == (operator.name == "||") {
+                return Result::Ok(T::from_real(bool_to_num(left))); 
+            }
+
+            let right = evaluate_node(&operator.nodes[1], context)?.is_true(); 
+            Result::Ok(T::from_real(bool_to_num(right)))
+        },
+        ExpressionNode::Operator(operator) => {
+            let mut nums: Vec<T> = vec![]; 
+            for inner_node in &operator.nodes {
+                nums.push(evaluate_node(inner_node, context)?); 
+            }
+
+            if context.functions.get(&operator.name).is_some() {
+                return evaluate_user_function(operator, &nums, context); 
+            }
+
+            evaluate_standart_function(operator, &nums)
         },
     }
 }  
 
-/// Returns the lambda and amount of arguments 
-fn get_func(name: &String) -> Option<Function> {
-    let search_result = STANDART_FUNCTIONS
-        .binary_search_by(|&f| name.cmp(&String::from(f.name)));
-    
-    match search_result {
-        Result::Ok(index) => {
-            let func = STANDART_FUNCTIONS[index];
+/// Applies the function of the type's table. If the type has no such 
+/// function, the real one is applied to real arguments. 
+fn evaluate_standart_function<T: Value>(operator: &OperatorNode, args: &Vec<T>) -> Result<T, Box<ErrorEntry>> {
+    let result = match find_func(T::functions(), &operator.name, args.len()) {
+        Option::Some(func) => (func.lambda)(args), 
+        Option::None => {
+            let func = get_func(STANDART_FUNCTIONS, &operator.name, args.len(), &operator.name_span.position)?; 
+            let real_args: Option<Vec<f64>> = args.iter()
+                .map(|x| x.to_real())
+                .collect(); 
 
-            Option::Some(func.clone())
+            real_args
+                .and_then(|x| (func.lambda)(&x))
+                .map(T::from_real)
         },
-        Result::Err(_) => Option::None,
+    }; 
+
+    match result {
+        Option::Some(x) if x.is_finite() => Result::Ok(x), 
+        _ => Result::Err(Box::new(ErrorEntry::new(
+            errors::INVALID_FUNCTION_ARGS, 
+            &operator.name_span.position, 
+            &vec![operator.name.clone()]))), 
     }
 }
 
-#[derive(Clone)]
-struct Function<'a> {
+/// Binds the arguments to parameter names and evaluates the function body. 
+/// The body sees only its own parameters, not the variables of the caller. 
+fn evaluate_user_function<T: Value>(operator: &OperatorNode, args: &[T], context: &mut EvaluationContext<T>) -> Result<T, Box<ErrorEntry>> {
+    let functions = context.functions; 
+    let function = functions.get(&operator.name).unwrap(); 
+    if function.param_names.len() != args.len() {
+        return Result::Err(Box::new(ErrorEntry::new(
+            errors::WRONG_ARGS_COUNT, 
+            &operator.name_span.position, 
+            &vec![operator.name.clone(), function.param_names.len().to_string(), args.len().to_string()]))); 
+    }
+
+    if context.call_stack.contains(&operator.name) {
+        let mut chain = context.call_stack.clone(); 
+        chain.push(operator.name.clone()); 
+        return Result::Err(Box::new(ErrorEntry::new(
+            errors::RECURSIVE_FUNCTION, 
+            &operator.name_span.position, 
+            &vec![operator.name.clone(), chain.join(" -> ")]))); 
+    }
+
+    let input: HashMap<String, T> = function.param_names.iter()
+        .cloned()
+        .zip(args.iter().cloned())
+        .collect(); 
+
+    context.call_stack.push(operator.name.clone()); 
+    let result = function.expression.evaluate_in(&input, functions, context.call_stack); 
+    context.call_stack.pop(); 
+
+    // Errors of the body point to the file which declares the function 
+    result.map_err(|err| Box::new(err.in_file(&function.file_name)))
+}
+
+/// Operators are overloaded by amount of arguments (unary and 
+/// binary '-'), so both are compared. 
+fn find_func<T>(table: &'static [&'static Function<'static, T>], name: &str, args_count: usize) -> Option<&'static Function<'static, T>> {
+    table.binary_search_by(|&f| (f.name, f.args_count).cmp(&(name, args_count)))
+        .ok()
+        .map(|index| table[index])
+}
+
+/// Returns the function of the table or the error describing why 
+/// it can't be called 
+fn get_func<T>(table: &'static [&'static Function<'static, T>], name: &String, args_count: usize, position: &Position) -> Result<&'static Function<'static, T>, Box<ErrorEntry>> {
+    match find_func(table, name, args_count) {
+        Option::Some(func) => Result::Ok(func),
+        Option::None => {
+            let expected: Vec<String> = table.iter()
+                .filter(|f| f.name == name)
+                .map(|f| f.args_count.to_string())
+                .collect(); 
+
+            match expected.len() {
+                0 => Result::Err(Box::new(ErrorEntry::new(
+                    errors::UNDEFINED_FUNCTION, 
+                    position, 
+                    &vec![name.clone()]))),
+                _ => Result::Err(Box::new(ErrorEntry::new(
+                    errors::WRONG_ARGS_COUNT, 
+                    position, 
+                    &vec![name.clone(), expected.join(" or "), args_count.to_string()]))),
+            }
+        },
+    }
+}
+
+struct Function<'a, T: 'a> {
     name: &'a str,
     args_count: usize,
-    lambda: &'a dyn Fn(&Vec<f64>) -> Option<f64>
+    lambda: &'a dyn Fn(&Vec<T>) -> Option<T>
 }
 
-const STANDART_FUNCTIONS: &'static [&'static Function] = &[
-    
+/// Checks whether the function is one of `STANDART_FUNCTIONS` 
+/// (operators included) 
+pub fn is_standart_function(name: &str) -> bool {
+    STANDART_FUNCTIONS.iter().any(|f| f.name == name)
+}
+
+fn bool_to_num(value: bool) -> f64 {
+    match value {
+        true => 1.0, 
+        false => 0.0, 
+    }
+}
+
+// Sorted by name and then by amount of arguments, because 
+// get_func uses binary search. 
+const STANDART_FUNCTIONS: &[&Function<f64>] = &[
+    &Function{ name: "!", args_count: 1, lambda: &|x| Some(bool_to_num(x[0] == 0.0)) },
+    &Function{ name: "!=", args_count: 2, lambda: &|x| Some(bool_to_num(x[0] != x[1])) },
+    &Function{ name: "%", args_count: 2, lambda: &|x| if x[1] == 0.0 { None } else { Some(x[0] % x[1]) } },
+    &Function{ name: "*", args_count: 2, lambda: &|x| Some(x[0] * x[1]) },
+    &Function{ name: "+", args_count: 1, lambda: &|x| Some(x[0]) },
+    &Function{ name: "+", args_count: 2, lambda: &|x| Some(x[0] + x[1]) },
+    &Function{ name: "-", args_count: 1, lambda: &|x| Some(-x[0]) },
+    &Function{ name: "-", args_count: 2, lambda: &|x| Some(x[0] - x[1]) },
+    &Function{ name: "/", args_count: 2, lambda: &|x| if x[1] == 0.0 { None } else { Some(x[0] / x[1]) } },
+    &Function{ name: "<", args_count: 2, lambda: &|x| Some(bool_to_num(x[0] < x[1])) },
+    &Function{ name: "<=", args_count: 2, lambda: &|x| Some(bool_to_num(x[0] <= x[1])) },
+    &Function{ name: "==", args_count: 2, lambda: &|x| Some(bool_to_num(x[0] == x[1])) },
+    &Function{ name: ">", args_count: 2, lambda: &|x| Some(bool_to_num(x[0] > x[1])) },
+    &Function{ name: ">=", args_count: 2, lambda: &|x| Some(bool_to_num(x[0] >= x[1])) },
+    &Function{ name: "^", args_count: 2, lambda: &|x| Some(x[0].powf(x[1])) },
+    &Function{ name: "abs", args_count: 1, lambda: &|x| Some(x[0].abs()) },
+    &Function{ name: "arg", args_count: 1, lambda: &|x| Some(if x[0] < 0.0 { f64::consts::PI } else { 0.0 }) },
+    &Function{ name: "atan2", args_count: 2, lambda: &|x| Some(x[0].atan2(x[1])) },
+    &Function{ name: "ceil", args_count: 1, lambda: &|x| Some(x[0].ceil()) },
+    &Function{ name: "conj", args_count: 1, lambda: &|x| Some(x[0]) },
+    &Function{ name: "cos", args_count: 1, lambda: &|x| Some(x[0].cos()) },
+    &Function{ name: "cosh", args_count: 1, lambda: &|x| Some(x[0].cosh()) },
+    &Function{ name: "exp", args_count: 1, lambda: &|x| Some(x[0].exp()) },
+    &Function{ name: "floor", args_count: 1, lambda: &|x| Some(x[0].floor()) },
+    &Function{ name: "im", args_count: 1, lambda: &|_| Some(0.0) },
+    &Function{ name: "ln", args_count: 1, lambda: &|x| if x[0] <= 0.0 { None } else { Some(x[0].ln()) } },
+    &Function{ name: "log10", args_count: 1, lambda: &|x| if x[0] <= 0.0 { None } else { Some(x[0].log10()) } },
+    &Function{ name: "max", args_count: 2, lambda: &|x| Some(x[0].max(x[1])) },
+    &Function{ name: "min", args_count: 2, lambda: &|x| Some(x[0].min(x[1])) },
+    &Function{ name: "pow", args_count: 2, lambda: &|x| Some(x[0].powf(x[1])) },
+    &Function{ name: "re", args_count: 1, lambda: &|x| Some(x[0]) },
+    &Function{ name: "sin", args_count: 1, lambda: &|x| Some(x[0].sin()) },
+    &Function{ name: "sinh", args_count: 1, lambda: &|x| Some(x[0].sinh()) },
+    &Function{ name: "sqrt", args_count: 1, lambda: &|x| if x[0] < 0.0 { None } else { Some(x[0].sqrt()) } },
+    &Function{ name: "tan", args_count: 1, lambda: &|x| Some(x[0].tan()) },
+    &Function{ name: "tanh", args_count: 1, lambda: &|x| Some(x[0].tanh()) },
 ];
 
-#[derive(Clone)]
-enum ExpressionElement {
-    Postfix(Vec<Token>, Option<Token>),
-    Common(Token), 
+// Complex versions of the standard functions, sorted in the same way. 
+// Functions missing here are evaluated by `STANDART_FUNCTIONS` if all 
+// arguments are real. 
+const COMPLEX_FUNCTIONS: &[&Function<Complex>] = &[
+    &Function{ name: "*", args_count: 2, lambda: &|x| Some(x[0] * x[1]) },
+    &Function{ name: "+", args_count: 1, lambda: &|x| Some(x[0]) },
+    &Function{ name: "+", args_count: 2, lambda: &|x| Some(x[0] + x[1]) },
+    &Function{ name: "-", args_count: 1, lambda: &|x| Some(-x[0]) },
+    &Function{ name: "-", args_count: 2, lambda: &|x| Some(x[0] - x[1]) },
+    &Function{ name: "/", args_count: 2, lambda: &|x| if x[1] == Complex::default() { None } else { Some(x[0] / x[1]) } },
+    &Function{ name: "^", args_count: 2, lambda: &|x| Some(x[0].pow(x[1])) },
+    &Function{ name: "abs", args_count: 1, lambda: &|x| Some(Complex::from(x[0].abs())) },
+    &Function{ name: "arg", args_count: 1, lambda: &|x| Some(Complex::from(x[0].arg())) },
+    &Function{ name: "conj", args_count: 1, lambda: &|x| Some(x[0].conj()) },
+    &Function{ name: "cos", args_count: 1, lambda: &|x| Some(x[0].cos()) },
+    &Function{ name: "cosh", args_count: 1, lambda: &|x| Some(x[0].cosh()) },
+    &Function{ name: "exp", args_count: 1, lambda: &|x| Some(x[0].exp()) },
+    &Function{ name: "im", args_count: 1, lambda: &|x| Some(Complex::from(x[0].im)) },
+    &Function{ name: "ln", args_count: 1, lambda: &|x| if x[0] == Complex::default() { None } else { Some(x[0].ln()) } },
+    &Function{ name: "pow", args_count: 2, lambda: &|x| Some(x[0].pow(x[1])) },
+    &Function{ name: "re", args_count: 1, lambda: &|x| Some(Complex::from(x[0].re)) },
+    &Function{ name: "sin", args_count: 1, lambda: &|x| Some(x[0].sin()) },
+    &Function{ name: "sinh", args_count: 1, lambda: &|x| Some(x[0].sinh()) },
+    &Function{ name: "sqrt", args_count: 1, lambda: &|x| Some(x[0].sqrt()) },
+    &Function{ name: "tanh", args_count: 1, lambda: &|x| Some(x[0].sinh() / x[0].cosh()) },
+];
+
+
+struct Operator<'a> {
+    text: &'a str,
+    right_assoc: bool, 
 }
 
-impl ExpressionElement {
-    fn get_first_token(&self) -> Option<Token> {
-        match self {
-            ExpressionElement::Common(token) => Option::Some(token.clone()), 
-            ExpressionElement::Postfix(tokens, ftoken) => {
-                match ftoken {
-                    Option::None => {
-                        match tokens.first() {
-                            Option::Some(token) => Option::Some(token.clone()),
-                            Option::None => Option::None,
-                        }
-                    },
-                    Option::Some(token) => Option::Some(token.clone()),
-                }        
-            }
-        }
+/// Binary operators grouped by priority, from the lowest to the highest. 
+/// Binary operators are left-associative (`a - b - c` is `(a - b) - c`) 
+/// except `^`: `a ^ b ^ c` is `a ^ (b ^ c)`. Unary operators bind tighter 
+/// than any binary operator except `^`, so `-x * y` is `(-x) * y`, 
+/// `2 * -3` is `2 * (-3)` and `-x ^ 2` is `-(x ^ 2)`. 
+///
+/// | Priority | Operators                          |
+/// |----------|------------------------------------|
+/// | 0        | `c ? a : b` (right-associative)    |
+/// | 1        | `\|\|`                             |
+/// | 2        | `&&`                               |
+/// | 3        | `==`, `!=`                         |
+/// | 4        | `<=`, `<`, `>=`, `>`               |
+/// | 5        | `+`, `-`                           |
+/// | 6        | `*`, `/`, `%`                      |
+/// | 6.5      | unary `+`, unary `-`, `!`          |
+/// | 7        | `^` (right-associative)            |
+static BIN_OPERATORS: &[&[&Operator]; 7] = & [
+    &[&Operator{ text: "||", right_assoc: false }],
+    &[&Operator{ text: "&&", right_assoc: false }],
+    &[&Operator{ text: "==", right_assoc: false }, &Operator{ text: "!=", right_assoc: false }],
+    &[&Operator{ text: "<=", right_assoc: false }, &Operator{ text: "<", right_assoc: false },
+      &Operator{ text: ">=", right_assoc: false }, &Operator{ text: ">", right_assoc: false }],
+    &[&Operator{ text: "+", right_assoc: false }, &Operator{ text: "-", right_assoc: false }],
+    &[&Operator{ text: "*", right_assoc: false }, &Operator{ text: "/", right_assoc: false }, 
+      &Operator{ text: "%", right_assoc: false }],
+    &[&Operator{ text: "^", right_assoc: true }],
+];
+
+static UN_OPERATORS: &[&Operator] = &[
+    &Operator{ text: "+", right_assoc: true }, 
+    &Operator{ text: "-", right_assoc: true }, 
+    &Operator{ text: "!", right_assoc: true },
+];
+
+/// Operand of an unary operator may contain binary operators 
+/// starting from this priority
+const UN_OPERAND_PRIORITY: usize = 7; 
+
+/// Name of the operator node built from `c ? a : b`
+const TERNARY_OPERATOR: &str = "?:"; 
+
+fn is_operator_name(name: &str) -> bool {
+    BIN_OPERATORS.iter().any(|&ops| ops.iter().any(|op| op.text == name)) || 
+        UN_OPERATORS.iter().any(|op| op.text == name) || 
+        name == TERNARY_OPERATOR
+}
+
+fn is_symbol(token: &Token, value: &str) -> bool {
+    variant_eq(&token.token_type, &TokenType::Symbol) && token.value == value
+}
+
+/// Returns the priority (starting from 1) and the associativity 
+/// of the binary operator, if the token is one. 
+fn get_bin_operator_priority(token: &Token) -> Option<(usize, bool)> {
+    if !variant_eq(&token.token_type, &TokenType::Symbol) {
+        return Option::None; 
     }
+
+    BIN_OPERATORS.iter()
+        .enumerate()
+        .find_map(|(index, &ops)| ops.iter()
+            .find(|op| op.text == token.value)
+            .map(|op| (index + 1, op.right_assoc)))
 }
 
-fn get_expression_elems(tokens: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> Vec<ExpressionElement> {
-    let mut level: i32 = 0; 
-    let mut level_tokens: Vec<Token> = vec![]; 
-    let mut exprs: Vec<ExpressionElement> = vec![]; 
-    let mut fn_call: Option<Token> = Option::None; 
-    let mut i: usize = 0; 
+fn is_un_operator(token: &Token) -> bool {
+    variant_eq(&token.token_type, &TokenType::Symbol) && 
+        UN_OPERATORS.iter().any(|op| op.text == token.value)
+}
 
-    for token in tokens {
-        if level < 0 {
-            errs_acc.push(ErrorEntry::new(
-                errors::OPEN_CLOSED_BRACKETS, 
-                &token.position, 
-                &vec![])); 
-        }
+/// Precedence climbing parser. Every method returns `None` after it 
+/// pushed an error, so the first error stops the parsing. 
+struct Parser<'a> {
+    tokens: &'a Vec<Token>,
+    index: usize, 
+    errs_acc: &'a mut Vec<ErrorEntry>, 
+}
 
-        if variant_eq(&token.token_type, &TokenType::Symbol) && token.value == "(" {
-            if level > 0 {
-                level_tokens.push(token.clone()); 
-            } 
-            else {
-                if level == 0 {
-                    fn_call = match tokens.get(i - 1) { // prev name is word => it's a function call
-                        Option::None => Option::None,
-                        Option::Some(x) if variant_eq(&TokenType::Word, &x.token_type) => {
-                            exprs.pop(); 
-                            Option::Some(x.clone())
-                        },
-                        _ => Option::None,
-                    };
-                }
-            }
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.index); 
+        self.index += 1; 
+        token
+    }
 
-            level += 1; 
+    fn error<T>(&mut self, error: &'static Error<'static>, position: &Position, args: Vec<String>) -> Option<T> {
+        self.errs_acc.push(ErrorEntry::new(error, position, &args)); 
+        Option::None
+    }
+
+    /// Error for the operand which is missing at the end of the expression
+    fn missing_operand(&mut self) -> Option<ExpressionNode> {
+        let position = match self.tokens.last() {
+            Option::Some(token) => token.position.clone(), 
+            Option::None => Position { line: 0, line_position: 0 },
+        }; 
+
+        self.error(errors::EXPRESSION_LAST_SYMBOL, &position, vec![])
+    }
+
+    fn parse_ternary(&mut self) -> Option<ExpressionNode> {
+        let condition = self.parse_binary(1)?; 
+        let question = match self.peek() {
+            Option::Some(token) if is_symbol(token, "?") => token, 
+            _ => return Option::Some(condition), 
+        }; 
+
+        self.next(); 
+        let on_true = self.parse_ternary()?; 
+        match self.next() {
+            Option::Some(token) if is_symbol(token, ":") => { }, 
+            Option::Some(token) => {
+                return self.error(errors::UNEXPECTED_SYMBOL, &token.position, vec![format!(":"), token.value.clone()]); 
+            },
+            Option::None => {
+                return self.error(errors::SYMBOL_NOT_FOUND, &question.position, vec![format!(":")]); 
+            },
         }
-        else if variant_eq(&token.token_type, &TokenType::Symbol) && token.value == ")" {
-            if level > 1 {
-                level_tokens.push(token.clone()); 
-            }   
-
-            level -= 1;
-
-            if level == 0 {
-                exprs.push(ExpressionElement::Postfix(level_tokens.clone(), fn_call.clone())); 
-                fn_call = Option::None; 
-                level_tokens = vec![]; 
-            } 
+
+        let on_false = self.parse_ternary()?; 
+        let span = condition.span().to(on_false.span()); 
+        Option::Some(ExpressionNode::Operator(OperatorNode{
+            name: TERNARY_OPERATOR.to_string(),
+            nodes: vec![condition, on_true, on_false],
+            name_span: question.span.clone(), 
+            span, 
+        }))
+    }
+
+    fn parse_binary(&mut self, min_priority: usize) -> Option<ExpressionNode> {
+        let mut left = self.parse_unary()?; 
+
+        while let Option::Some(token) = self.peek() {
+            let (priority, right_assoc) = match get_bin_operator_priority(token) {
+                Option::Some((priority, right_assoc)) if priority >= min_priority => (priority, right_assoc), 
+                _ => break, 
+            }; 
+
+            self.next(); 
+            // Left associativity: the right operand may only contain 
+            // operators with higher priority, right one allows the same 
+            let right = match right_assoc {
+                true => self.parse_binary(priority)?, 
+                false => self.parse_binary(priority + 1)?, 
+            }; 
+            let span = left.span().to(right.span()); 
+            left = ExpressionNode::Operator(OperatorNode{
+                name: token.value.clone(),
+                nodes: vec![left, right],
+                name_span: token.span.clone(), 
+                span, 
+            }); 
         }
-        else {
-            match level > 0 {
-                true => {
-                    level_tokens.push(token.clone()); 
-                },
-                false => {
-                    exprs.push(ExpressionElement::Common(token.clone())); 
-                }
+
+        Option::Some(left)
+    }
+
+    fn parse_unary(&mut self) -> Option<ExpressionNode> {
+        let token = match self.peek() {
+            Option::Some(token) => token, 
+            Option::None => return self.missing_operand(), 
+        }; 
+
+        if is_un_operator(token) {
+            self.next(); 
+            if self.peek().is_none() {
+                return self.error(errors::NO_VAR_AFTER_UNARY, &token.position, vec![]); 
             }
+
+            let operand = self.parse_binary(UN_OPERAND_PRIORITY)?; 
+            let span = token.span.to(operand.span()); 
+            return Option::Some(ExpressionNode::Operator(OperatorNode{
+                name: token.value.clone(), 
+                nodes: vec![operand], 
+                name_span: token.span.clone(), 
+                span, 
+            })); 
         }
 
-        i += 1; 
+        if get_bin_operator_priority(token).is_some() {
+            return self.error(errors::UNDEFINED_UNARY_OPERATOR, &token.position, vec![token.value.clone()]); 
+        }
+
+        self.parse_primary()
     }
 
-    exprs
-}
+    fn parse_primary(&mut self) -> Option<ExpressionNode> {
+        let token = match self.next() {
+            Option::Some(token) => token, 
+            Option::None => return self.missing_operand(), 
+        }; 
 
-// Checking levels isn't nesessary because we dealing with ExpressionElements
-fn comma_divide(exprs: &Vec<ExpressionElement>) -> Vec<Vec<ExpressionElement>> {
-    let mut res: Vec<Vec<ExpressionElement>> = vec![]; 
-    let mut curr: Vec<ExpressionElement> = vec![]; 
-    for expr in exprs {
-        match expr {
-            ExpressionElement::Common(token) => {
-                if variant_eq(&token.token_type, &TokenType::Symbol) && token.value == "," {
-                    res.push(curr.clone()); 
-                    curr = vec![]; 
-                }
-                else {
-                    curr.push(expr.clone());
+        match token.token_type {
+            TokenType::Number => {
+                Option::Some(ExpressionNode::Number(token.value.clone(), token.unit.clone(), token.span.clone()))
+            },
+            TokenType::Word => {
+                match self.peek() {
+                    Option::Some(next) if is_symbol(next, "(") => {
+                        self.next(); 
+                        let (nodes, closed) = self.parse_call_args()?; 
+                        Option::Some(ExpressionNode::Operator(OperatorNode{
+                            name: token.value.clone(), 
+                            nodes, 
+                            name_span: token.span.clone(), 
+                            span: token.span.to(&closed.span), 
+                        }))
+                    },
+                    _ if token.value.contains('.') => self.parse_member(token), 
+                    _ => Option::Some(ExpressionNode::Number(token.value.clone(), Option::None, token.span.clone())),
                 }
             },
-            ExpressionElement::Postfix(_, _) => {
-                curr.push(expr.clone());
+            TokenType::Symbol if token.value == "(" => {
+                let node = self.parse_ternary()?; 
+                self.expect_closed_bracket(token)?; 
+                Option::Some(node)
             },
+            // The tokenizer has reported undefined tokens 
+            TokenType::Undefined => Option::None, 
+            _ => self.error(errors::EXPECTED_NUM_VAR, &token.position, vec![token.value.clone()]),
         }
     }
 
-    if curr.len() > 0 {
-        res.push(curr.clone()); 
-    }
-    
-    res
-}
-
-fn check_expressions_order(elems: &Vec<ExpressionElement>, errs_acc: &mut Vec<ErrorEntry>) -> Option<usize> {
-    let mut init: usize = 0;
-    let mut i: usize = 0; 
-    let mut first: bool = true;
-    let len = elems.len(); 
-    if len == 0 {
-        return Option::Some(0); 
-    }
-
-    for elem in elems {
-        match elem {
-            ExpressionElement::Common(token) => {
-                if variant_eq(&token.token_type, &TokenType::Symbol) {
-                    if first {
-                        first = false; 
-                        init = 1; 
-                        if !(token.value == "+" || token.value == "-") { // TODO: Fix this
-                            errs_acc.push(ErrorEntry::new(
-                                errors::UNDEFINED_UNARY_OPERATOR,
-                                &token.position,
-                                &vec![token.value.clone()],
-                            ));
-                        }
-                    }
-
-                    if i == elems.len() {
-                        errs_acc.push(ErrorEntry::new(
-                            errors::EXPRESSION_LAST_SYMBOL,
-                            &token.position, 
-                            &vec![], 
-                        ));
-                        return Option::Some(i); 
-                    }
-
-                    if (init + i) % 2 != 1 {
-                        errs_acc.push(ErrorEntry::new(
-                            errors::EXPECTED_BINARY_OPERATOR,
-                            &token.position,
-                            &vec![token.value.clone()], 
-                        ));
-
-                        return Option::Some(i); 
-                    }
-                }
-                else {
-                    if first {
-                        first = false; 
-                    }
-
-                    if (init + i) % 2 != 0 {
-                        errs_acc.push(ErrorEntry::new(
-                            errors::EXPECTED_NUM_VAR,
-                            &token.position,
-                            &vec![token.value.clone()], 
-                        ));
-
-                        return Option::Some(i); 
-                    }
-                }
+    /// Member access is a word 'instance.member', both parts are 
+    /// identifiers without dots 
+    fn parse_member(&mut self, token: &Token) -> Option<ExpressionNode> {
+        match token.value.split_once('.') {
+            Option::Some((instance, member)) if !member.is_empty() && !member.contains('.') => {
+                Option::Some(ExpressionNode::Member(instance.to_string(), member.to_string(), token.span.clone()))
             },
-            ExpressionElement::Postfix(_, _) => {
-                if first {
-                    first = false; 
-                }
+            _ => self.error(errors::INVALID_MEMBER_ACCESS, &token.position, vec![token.value.clone()]),
+        }
+    }
 
-                if (init + i) % 2 != 0 {
-                    return Option::Some(i); 
-                }
-            },
+    /// Parses arguments of the function call and the closing bracket, 
+    /// the opened bracket is already consumed
+    fn parse_call_args(&mut self) -> Option<(Vec<ExpressionNode>, &'a Token)> {
+        let opened = &self.tokens[self.index - 1]; 
+        let mut nodes: Vec<ExpressionNode> = vec![]; 
+        if let Option::Some(next) = self.peek() {
+            if is_symbol(next, ")") {
+                self.next(); 
+                return Option::Some((nodes, next)); 
+            }
+        }
+
+        loop {
+            nodes.push(self.parse_ternary()?); 
+            match self.peek() {
+                Option::Some(next) if is_symbol(next, ",") => { 
+                    self.next(); 
+                },
+                _ => break, 
+            }
         }
 
-        i += 1; 
+        let closed = self.expect_closed_bracket(opened)?; 
+        Option::Some((nodes, closed))
     }
 
-    let last = elems[len - 1].clone(); 
-    match last {
-        ExpressionElement::Common(token) => {
-            if variant_eq(&token.token_type, &TokenType::Symbol) {
-                errs_acc.push(ErrorEntry::new(
-                    errors::EXPRESSION_LAST_SYMBOL,
-                    &token.position.clone(),
-                    &vec![], 
-                ));
+    fn expect_closed_bracket(&mut self, opened: &Token) -> Option<&'a Token> {
+        match self.next() {
+            Option::Some(token) if is_symbol(token, ")") => Option::Some(token), 
+            Option::Some(token) if matches!(token.token_type, TokenType::Undefined) => Option::None, 
+            Option::Some(token) => {
+                self.error(errors::EXPECTED_BINARY_OPERATOR, &token.position, vec![token.value.clone()])
+            },
+            Option::None => {
+                let position = self.tokens.last().map(|x| x.position.clone()).unwrap_or(opened.position.clone()); 
+                self.errs_acc.push(ErrorEntry::new(errors::OPEN_CLOSED_BRACKETS, &position, &vec![])
+                    .with_label(&opened.position, "unclosed bracket opened here")
+                    .with_help("add ')' at the end of the expression")); 
+                Option::None
             }
-        },
-        _ => { },
+        }
     }
 
-    Option::None
+    /// Parses the whole token list, rest tokens are reported as errors
+    fn parse(&mut self) -> Option<ExpressionNode> {
+        let root = self.parse_ternary()?; 
+        match self.peek() {
+            Option::None => Option::Some(root), 
+            Option::Some(token) if is_symbol(token, ",") => {
+                self.error(errors::EXPRESSION_SHOULD_RET_NUMBER, &token.position, vec![])
+            },
+            Option::Some(token) if is_symbol(token, ")") => {
+                self.error(errors::OPEN_CLOSED_BRACKETS, &token.position, vec![])
+            },
+            Option::Some(token) if matches!(token.token_type, TokenType::Undefined) => Option::None, 
+            Option::Some(token) => {
+                self.error(errors::EXPECTED_BINARY_OPERATOR, &token.position, vec![token.value.clone()])
+            }
+        }
+    }
 }
 
-struct Operator<'a> {
-    text: &'a str,
+pub fn get_expression(tokens: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> Option<Expression> {
+    if tokens.is_empty() {
+        return Option::None; 
+    }
+
+    let mut parser = Parser {
+        tokens, 
+        index: 0, 
+        errs_acc, 
+    }; 
+
+    let root = parser.parse()?; 
+    Option::Some(Expression{
+        span: tokens[0].span.to(&tokens[tokens.len() - 1].span),
+        root: Option::Some(root),
+    })
 }
 
-static BIN_OPERATORS: &'static [&'static [&'static Operator]; 4] = & [
-    &[&Operator{ text: "==" }, &Operator{ text: "!=" }],
-    &[&Operator{ text: "<=" }, &Operator{ text: "<" },
-      &Operator{ text: ">=" }, &Operator{ text: ">" }],
-    &[&Operator{ text: "+" }, &Operator{ text: "-" }],
-    &[&Operator{ text: "*" }, &Operator{ text: "/" }],
-];
 
-static UN_OPERATORS: &'static [&'static Operator] = &[
-    &Operator{ text: "+" }, &Operator{ text: "-" }
-];
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::commands::get_function_environment; 
+    use crate::packages::get_token_recognizers; 
+    use crate::packages::parse_source; 
+    use crate::tokenizer::tokenize; 
 
-fn process_postfix(exprs: &Vec<ExpressionElement>, func_call: Option<Token>, errs_acc: &mut Vec<ErrorEntry>) -> Node {
-    match func_call {
-        Option::None => {
-            match check_expressions_order(exprs, errs_acc) {
-                Option::Some(_) => { return Node::Number(format!("0")); },
-                Option::None => { },
-            };
-        },
-        _ => { },
-    };
-    
-    let div = comma_divide(exprs); 
-    match func_call {
-        Option::None => {
-            let len = div.len(); 
-            match len {
-                0 => {
-                    Node::Number(format!("0"))
-                },
-                1 => {
-                    divide_elems_by_first_priority_operator(exprs, errs_acc)
-                },
-                _ => {
-                    let elem = exprs[0].clone();
-                    errs_acc.push(ErrorEntry::new(
-                        errors::EXPRESSION_SHOULD_RET_NUMBER,
-                        &match elem.get_first_token() {
-                            Option::Some(token) => token.position.clone(),
-                            Option::None => Position { line: 0, line_position: 0 },
-                        },
-                        &vec![],
-                    ));
-
-                    Node::Number(format!("0"))
-                }
-            }
-        },
-        Option::Some(func_call) => {
-            let nodes: Vec<Node> = div.iter()
-                .map(|x| divide_elems_by_first_priority_operator(x, errs_acc))
-                .collect(); 
+    fn parse(code: &str) -> Expression {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let tokens = tokenize(code.to_string(), &get_token_recognizers(), &mut errs); 
+        let expression = get_expression(&tokens, &mut errs); 
+        assert!(errs.is_empty(), "errors in '{}': {:?}", code, errs.iter().map(|x| x.get_message()).collect::<Vec<_>>()); 
+        expression.unwrap()
+    }
 
-            Node::Operator(OperatorNode{
-                name: func_call.value.clone(),
-                nodes: nodes, 
-            })
-        }
+    fn evaluate(code: &str) -> Result<f64, Box<ErrorEntry>> {
+        parse(code).evaluate(&HashMap::new(), &FunctionEnvironment::new())
     }
-}
 
-fn divide_elems_by_first_priority_operator(elems: &Vec<ExpressionElement>, errs_acc: &mut Vec<ErrorEntry>) -> Node {
-    // Check bin operators
-    for &operator_priority_vec in BIN_OPERATORS {
-        for &operator in operator_priority_vec {
-            let op_text = &operator.text.to_string(); 
-            let search_result = elems.iter()
-                .skip(1)
-                .position(|x| match x {
-                    ExpressionElement::Common(Token { value: val, position: _, token_type: TokenType::Symbol }) if val == op_text => true, 
-                    _ => false,
-                }); 
-            
-            match search_result {
-                Option::Some(search_position) => { 
-                    return divide_elems_by_bin_operator(elems, &operator, search_position + 1, errs_acc); 
-                },
-                Option::None => { }
-            }
-        }
+    /// Evaluates the expression with functions declared in `declarations` 
+    fn evaluate_with(declarations: &str, code: &str) -> Result<f64, Box<ErrorEntry>> {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source(declarations.to_string(), 0, &mut errs); 
+        assert!(errs.is_empty()); 
+        let functions = get_function_environment(&commands, "functions.txt"); 
+        parse(code).evaluate(&HashMap::new(), &functions)
     }
-        
-    // Check un operators
-    for &operator in UN_OPERATORS {
-        let op_text = operator.text.to_string(); 
-        let first = elems[0].clone(); 
-        
-        match first {
-            ExpressionElement::Common(token) => {
-                if variant_eq(&TokenType::Symbol, &token.token_type) && token.value == op_text {
-                    return match divide_elems_by_un_operator(elems, &operator, errs_acc) {
-                        Option::Some(x) => x,
-                        Option::None => {
-                            errs_acc.push(ErrorEntry::new(
-                                errors::NO_VAR_AFTER_UNARY,
-                                &token.position.clone(), 
-                                &vec![],
-                            ));
-                            Node::Number(format!("0"))
-                        }
-                    }; 
-                }
-            }, 
-            _ => { },
-        }; 
+
+    fn error_code(result: Result<f64, Box<ErrorEntry>>) -> Option<&'static str> {
+        result.err().map(|x| x.error.code)
     }
 
-    let elems_len = elems.len(); 
-    if elems_len == 0 {
-        // TODO:
+    #[test]
+    fn display_is_fully_parenthesised() {
+        assert_eq!(parse("a - b - c").to_string(), "((a - b) - c)"); 
+        assert_eq!(parse("a / b / c").to_string(), "((a / b) / c)"); 
+        assert_eq!(parse("a + b * c").to_string(), "(a + (b * c))"); 
+        assert_eq!(parse("(a + b) * c").to_string(), "((a + b) * c)"); 
+        assert_eq!(parse("-x * y").to_string(), "((-x) * y)"); 
+        assert_eq!(parse("2 * -3").to_string(), "(2 * (-3))"); 
+        assert_eq!(parse("max(a, b - c)").to_string(), "max(a, (b - c))"); 
     }
 
-    // It may be function call of single value, size of elems should be 1
-    if elems_len > 1 {
-        let elem = elems[0].clone(); 
-        errs_acc.push(ErrorEntry::new(
-            errors::EXPRESSION_SHOULD_RET_NUMBER,
-            &match elem.get_first_token() {
-                Option::Some(token) => token.position.clone(),
-                Option::None => Position { line: 0, line_position: 0 },
-            },
-            &vec![], 
-        ));
+    #[test]
+    fn display_round_trips() {
+        let codes = [
+            "a - b - c", 
+            "a / b * c - d + e", 
+            "x < y == y >= z", 
+            "-(a - b) * max(c, -d / e)", 
+            "sin(2 * x) + cos(x) / 3", 
+        ]; 
+
+        for code in codes {
+            let printed = parse(code).to_string(); 
+            assert_eq!(parse(&printed).to_string(), printed, "round trip of '{}'", code); 
+        }
     }
 
-    let elem = elems[0].clone(); 
-    match elem {
-        ExpressionElement::Common(token) => {
-            match token.token_type {
-                TokenType::Word | TokenType::Number => {
-                    Node::Number(token.value)
-                },
-                _ => {
-                    errs_acc.push(ErrorEntry::new(
-                        errors::EXPECTED_NUM_VAR,
-                        &token.position.clone(), 
-                        &vec![token.value.clone()],
-                    ));
-                    Node::Number(format!("0"))
-                }
-            }
-        },
-        ExpressionElement::Postfix(tokens, fn_call) => {
-            let elems = get_expression_elems(&tokens, errs_acc); 
+    #[test]
+    fn binary_operators_are_left_associative() {
+        assert_eq!(evaluate("10 - 4 - 3").ok(), Option::Some(3.0)); 
+        assert_eq!(evaluate("64 / 4 / 2").ok(), Option::Some(8.0)); 
+        assert_eq!(evaluate("2 * 3 / 6 * 4").ok(), Option::Some(4.0)); 
+        assert_eq!(evaluate("1 - 2 + 3").ok(), Option::Some(2.0)); 
+    }
+
+    #[test]
+    fn operators_follow_the_priority_table() {
+        let cases = [
+            ("a + b * c", "(a + (b * c))"), 
+            ("a * b + c", "((a * b) + c)"), 
+            ("a - b / c", "(a - (b / c))"), 
+            ("a + b < c - d", "((a + b) < (c - d))"), 
+            ("a < b == c > d", "((a < b) == (c > d))"), 
+            ("a <= b != c >= d", "((a <= b) != (c >= d))"), 
+            ("-a + b", "((-a) + b)"), 
+            ("a * -b / c", "((a * (-b)) / c)"), 
+        ]; 
 
-            process_postfix(&elems, fn_call.clone(), errs_acc)
+        for (code, expected) in cases {
+            assert_eq!(parse(code).to_string(), expected, "parsing '{}'", code); 
         }
+
+        assert_eq!(evaluate("2 + 3 * 4").ok(), Option::Some(14.0)); 
+        assert_eq!(evaluate("(2 + 3) * 4").ok(), Option::Some(20.0)); 
+        assert_eq!(evaluate("1 + 1 == 2").ok(), Option::Some(1.0)); 
     }
-}
 
-fn divide_elems_by_un_operator(elems: &Vec<ExpressionElement>, operator: &Operator, errs_acc: &mut Vec<ErrorEntry>) -> Option<Node> {
-    // It's an operator call  
-    let right: Vec<_> = elems.iter()
-        .skip(1)
-        .map(|x| x.clone())
-        .collect(); 
+    #[test]
+    fn exponent_is_right_associative() {
+        assert_eq!(parse("a ^ b ^ c").to_string(), "(a ^ (b ^ c))"); 
+        assert_eq!(evaluate("2 ^ 3 ^ 2").ok(), Option::Some(512.0)); 
+        assert_eq!(parse("a * b ^ c").to_string(), "(a * (b ^ c))"); 
+    }
 
-    if right.len() == 0 {
-        return Option::None; 
+    #[test]
+    fn unary_operators_bind_looser_than_exponent() {
+        assert_eq!(parse("-x ^ 2").to_string(), "(-(x ^ 2))"); 
+        assert_eq!(evaluate("-2 ^ 2").ok(), Option::Some(-4.0)); 
+        assert_eq!(evaluate("2 ^ -1").ok(), Option::Some(0.5)); 
+        assert_eq!(parse("!a && b").to_string(), "((!a) && b)"); 
     }
 
-    let nodes = vec![right].iter()
-        .map(|x| divide_elems_by_first_priority_operator(x, errs_acc))
-        .collect(); 
-    
-    Option::Some(Node::Operator(OperatorNode{
-        name: operator.text.to_string(),
-        nodes: nodes
-    }))
-}
-
-fn divide_elems_by_bin_operator(elems: &Vec<ExpressionElement>, operator: &Operator, operators_index: usize, errs_acc: &mut Vec<ErrorEntry>) -> Node {
-    // It's an operator call
-    let left: Vec<_> = elems.iter()
-        .take(operators_index)
-        .map(|x| x.clone())
-        .collect(); 
-    
-    let right: Vec<_> = elems.iter()
-        .skip(operators_index + 1)
-        .map(|x| x.clone())
-        .collect(); 
+    #[test]
+    fn ternary_operator_evaluates_the_chosen_branch() {
+        assert_eq!(parse("a || b ? c : d").to_string(), "((a || b) ? c : d)"); 
+        assert_eq!(parse("a ? b : c ? d : e").to_string(), "(a ? b : (c ? d : e))"); 
+        assert_eq!(evaluate("1 > 0 ? 2 : 3").ok(), Option::Some(2.0)); 
+        assert_eq!(evaluate("0 ? 2 : 0 ? 3 : 4").ok(), Option::Some(4.0)); 
+        assert_eq!(evaluate("1 ? 2 : 1 / 0").ok(), Option::Some(2.0)); 
+        assert!(evaluate("0 ? 2 : 1 / 0").is_err()); 
+    }
 
-    let nodes = vec![left, right].iter()
-        .map(|x| divide_elems_by_first_priority_operator(x, errs_acc))
-        .collect(); 
-    
-    Node::Operator(OperatorNode{
-        name: operator.text.to_string(),
-        nodes: nodes
-    })
-}
+    #[test]
+    fn logical_operators_short_circuit() {
+        assert_eq!(parse("a || b && c").to_string(), "(a || (b && c))"); 
+        assert_eq!(parse("a && b == c").to_string(), "(a && (b == c))"); 
+        assert_eq!(evaluate("0 && 1 / 0").ok(), Option::Some(0.0)); 
+        assert_eq!(evaluate("2 || undefined").ok(), Option::Some(1.0)); 
+        assert_eq!(evaluate("1 && 2").ok(), Option::Some(1.0)); 
+        assert_eq!(evaluate("0 || 0").ok(), Option::Some(0.0)); 
+        assert_eq!(evaluate("1 && undefined").err().map(|x| x.error.code), Option::Some("EVL002")); 
+    }
 
-pub fn get_expression(tokens: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> Option<Expression> {
-    if tokens.len() == 0 {
-        return Option::None; 
+    #[test]
+    fn not_and_modulo() {
+        assert_eq!(evaluate("!0").ok(), Option::Some(1.0)); 
+        assert_eq!(evaluate("!5").ok(), Option::Some(0.0)); 
+        assert_eq!(evaluate("!!3").ok(), Option::Some(1.0)); 
+        assert_eq!(evaluate("7 % 3").ok(), Option::Some(1.0)); 
+        assert_eq!(evaluate("10 % 4 % 3").ok(), Option::Some(2.0)); 
+        assert_eq!(parse("a + b % c").to_string(), "(a + (b % c))"); 
+        assert_eq!(evaluate("1 % 0").err().map(|x| x.error.code), Option::Some("EVL005")); 
     }
 
-    let elems = get_expression_elems(tokens, errs_acc);   
-    match check_expressions_order(&elems, errs_acc) {
-        Option::None => { },
-        Option::Some(_) => { return Option::None; },
+    #[test]
+    fn evaluation_errors_are_reported() {
+        assert_eq!(error_code(evaluate("x + 1")), Option::Some("EVL002")); 
+        assert_eq!(error_code(evaluate("JK.U * 2")), Option::Some("EVL002")); 
+        assert_eq!(error_code(evaluate("foo(1)")), Option::Some("EVL003")); 
+        assert_eq!(error_code(evaluate("sin(1, 2)")), Option::Some("EVL004")); 
+        assert_eq!(error_code(evaluate("sqrt(-1)")), Option::Some("EVL005")); 
+        assert_eq!(error_code(evaluate("ln(0)")), Option::Some("EVL005")); 
+
+        let err = evaluate("1 +  foo(2)").err().unwrap(); 
+        assert_eq!((err.position.as_ref().unwrap().line, err.position.as_ref().unwrap().line_position), (0, 5)); 
+        assert_eq!(err.get_message(), "Undefined function: 'foo'."); 
     }
-    let root = divide_elems_by_first_priority_operator(&elems, errs_acc); 
 
-    Option::Some(Expression{
-        tokens: tokens.clone(),
-        root: Option::Some(root),
-    })
-}
+    #[test]
+    fn variables_are_taken_from_input() {
+        let input: HashMap<String, f64> = HashMap::from([(format!("x"), 3.0), (format!("JK.U"), 2.0)]); 
+        let result = parse("x * JK.U - 1").evaluate(&input, &FunctionEnvironment::new()); 
+        assert_eq!(result.ok(), Option::Some(5.0)); 
+    }
+
+    #[test]
+    fn user_functions_are_resolved() {
+        let declarations = "function inc(a) => a + 1; function twice(a, b) => inc(a) * inc(b); function PI() => 3; "; 
+        assert_eq!(evaluate_with(declarations, "inc(1)").ok(), Option::Some(2.0)); 
+        assert_eq!(evaluate_with(declarations, "twice(1, 2) + PI()").ok(), Option::Some(9.0)); 
+        assert_eq!(error_code(evaluate_with(declarations, "inc(1, 2)")), Option::Some("EVL004")); 
+    }
 
+    #[test]
+    fn function_body_sees_only_its_parameters() {
+        let declarations = "function f(a) => a + x; "; 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source(declarations.to_string(), 0, &mut errs); 
+        let functions = get_function_environment(&commands, "functions.txt"); 
+        let input: HashMap<String, f64> = HashMap::from([(format!("x"), 1.0)]); 
+
+        let err = parse("f(1)").evaluate(&input, &functions).err().unwrap(); 
+        assert_eq!(err.error.code, "EVL002"); 
+        assert_eq!(err.file_name, "functions.txt"); 
+    }
 
+    #[test]
+    fn recursive_functions_are_detected() {
+        let err = evaluate_with("function f(a) => f(a - 1); ", "f(3)").err().unwrap(); 
+        assert_eq!(err.error.code, "EVL006"); 
+        assert_eq!(err.get_message(), "Recursive call of function 'f': f -> f."); 
+
+        let declarations = "function even(n) => odd(n); function odd(n) => even(n); "; 
+        let err = evaluate_with(declarations, "1 + even(2)").err().unwrap(); 
+        assert_eq!(err.error.code, "EVL006"); 
+        assert_eq!(err.get_message(), "Recursive call of function 'even': even -> odd -> even."); 
+
+        // Repeated calls which are not nested are not recursion 
+        assert_eq!(evaluate_with("function g(a) => a * 2; ", "g(g(1)) + g(1)").ok(), Option::Some(6.0)); 
+    }
+}
@@ -0,0 +1,42 @@
+use crate::entities::Expression; 
+use std::collections::HashMap; 
+
+/// Function declared by `function name(arg1, arg2) => expression;` 
+#[derive(Clone)]
+pub struct UserFunction {
+    pub name: String, 
+    pub param_names: Vec<String>, 
+    pub expression: Expression, 
+    pub file_name: String, // file which declares the function
+}
+
+/// User functions available during expression evaluation. 
+/// Standard functions and operators aren't stored here. 
+#[derive(Clone, Default)]
+pub struct FunctionEnvironment {
+    functions: HashMap<String, UserFunction>, 
+}
+
+impl FunctionEnvironment {
+    pub fn new() -> FunctionEnvironment {
+        FunctionEnvironment {
+            functions: HashMap::new(), 
+        }
+    }
+
+    /// Adds the function, the first declaration with the same name wins 
+    pub fn add(&mut self, function: UserFunction) {
+        self.functions.entry(function.name.clone()).or_insert(function); 
+    }
+
+    /// Adds functions of the other environment, e.g. of an imported package 
+    pub fn extend(&mut self, other: FunctionEnvironment) {
+        for (_, function) in other.functions {
+            self.add(function); 
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&UserFunction> {
+        self.functions.get(name)
+    }
+}
@@ -1,21 +1,33 @@
 mod code_char; 
 mod position;
+mod span; 
 mod token; 
 mod token_type;
 mod statement; 
 mod expression; 
 mod error;
+mod function_environment; 
+mod complex; 
+mod dimension; 
 
 pub use code_char::CodeChar; 
 pub use position::Position; 
+pub use span::Span; 
 pub use token::Token;
 pub use token_type::TokenType; 
 pub use statement::Statement; 
 pub use expression::Expression; 
+pub use expression::ExpressionNode; 
+pub use expression::OperatorNode; 
 pub use error::Error;
 pub use error::ErrorEntry;
+pub use function_environment::FunctionEnvironment; 
+pub use function_environment::UserFunction; 
+pub use complex::Complex; 
+pub use dimension::Dimension; 
 
 pub use expression::get_expression; 
+pub use expression::is_standart_function; 
 pub use error::apply_file_name;
 
 pub use error::errors;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use std::fmt::Formatter;
 use std::fmt::Display; 
 
-#[derive(Clone)]
+#[derive(Clone, Default, PartialEq)]
 pub struct Position {
     pub line: usize,
     pub line_position: usize,
@@ -0,0 +1,100 @@
+use crate::entities::Position; 
+
+/// Part of the source code: byte offsets `start..end` in the file 
+/// `file_id` and positions of the first char and of the char after 
+/// the last one. Files of the program are numbered in order of 
+/// parsing (see `SourceFile::file_id`), the main file is 0. 
+#[derive(Clone, Default, PartialEq)]
+pub struct Span {
+    pub file_id: usize, 
+    pub start: usize, 
+    pub end: usize, 
+    pub position: Position, 
+    pub end_position: Position, 
+}
+
+impl Span {
+    /// Span from the start of `self` up to the end of `other`, which 
+    /// doesn't start before `self` 
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            file_id: self.file_id, 
+            start: self.start, 
+            end: other.end, 
+            position: self.position.clone(), 
+            end_position: other.end_position.clone(), 
+        }
+    }
+
+    /// Length in bytes 
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::ErrorEntry; 
+    use crate::entities::Token; 
+    use crate::packages::get_token_recognizers; 
+    use crate::tokenizer::tokenize; 
+    use super::Span; 
+
+    fn get_tokens(code: &str) -> Vec<Token> {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let tokens = tokenize(code.to_string(), &get_token_recognizers(), &mut errs); 
+        assert!(errs.is_empty()); 
+        tokens
+    }
+
+    #[test]
+    fn token_spans_are_byte_offsets() {
+        let code = "R1 = 4.7k;\n  half(x)"; 
+        let tokens = get_tokens(code); 
+        let texts: Vec<&str> = tokens.iter().map(|x| &code[x.span.start..x.span.end]).collect(); 
+        assert_eq!(texts, ["R1", "=", "4.7k", ";", "half", "(", "x", ")"]); 
+
+        // The end position is the char after the last one 
+        let half = &tokens[4].span; 
+        assert_eq!((half.position.line, half.position.line_position), (1, 2)); 
+        assert_eq!((half.end_position.line, half.end_position.line_position), (1, 6)); 
+        assert_eq!(half.len(), 4); 
+        assert!(!half.is_empty()); 
+    }
+
+    #[test]
+    fn offsets_count_bytes_and_columns_count_chars() {
+        let code = "Ω = 1; y"; 
+        let y = get_tokens(code).pop().unwrap(); 
+        assert_eq!(&code[y.span.start..y.span.end], "y"); 
+        assert_eq!((y.span.start, y.span.position.line_position), (8, 7)); 
+    }
+
+    #[test]
+    fn joined_span_goes_from_first_to_last() {
+        let code = "a + b\n  * c"; 
+        let tokens = get_tokens(code); 
+        let span = tokens[0].span.to(&tokens[4].span); 
+        assert_eq!(&code[span.start..span.end], code); 
+        assert_eq!((span.position.line, span.position.line_position), (0, 0)); 
+        assert_eq!((span.end_position.line, span.end_position.line_position), (1, 5)); 
+        assert_eq!(span.len(), code.len()); 
+
+        // The file of the first span is kept 
+        let mut first = tokens[0].span.clone(); 
+        first.file_id = 2; 
+        assert_eq!(first.to(&tokens[2].span).file_id, 2); 
+    }
+
+    #[test]
+    fn default_span_is_empty() {
+        let span = Span::default(); 
+        assert!(span.is_empty()); 
+        assert_eq!(span.len(), 0); 
+        assert!(span == span.to(&Span::default())); 
+    }
+}
@@ -1,3 +1,4 @@
+use crate::entities::Span;
 use crate::entities::Token;
 
 #[derive(Clone)]
@@ -5,4 +6,5 @@ pub struct Statement {
     pub tokens: Vec<Token>,
     pub substatements: Vec<Statement>,
     pub comments: Vec<Token>,
+    pub span: Span, // from the first token up to ';' or the '}' of the block
 }
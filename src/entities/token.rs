@@ -1,9 +1,12 @@
 use crate::entities::Position; 
+use crate::entities::Span; 
 use crate::entities::TokenType; 
 
 #[derive(Clone)]
 pub struct Token {
-    pub position: Position,
+    pub position: Position, // start of the span
     pub value: String,
     pub token_type: TokenType,
+    pub unit: Option<String>, // unit of the number literal, e.g. 'Ohm' in '4.7kOhm'
+    pub span: Span, // set by the tokenizer
 }
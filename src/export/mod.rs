@@ -0,0 +1,4 @@
+mod spice; 
+
+pub use spice::export_spice;
+pub use spice::format_number; 
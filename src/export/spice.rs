@@ -0,0 +1,380 @@
+use std::collections::HashMap; 
+use crate::ast::Circuit; 
+use crate::ast::Instance; 
+use crate::ast::get_names; 
+use crate::elaborator::BoundExpression; 
+use crate::elaborator::Netlist; 
+use crate::elaborator::bind_circuit_entries; 
+use crate::entities::ErrorEntry; 
+use crate::entities::Expression; 
+use crate::entities::FunctionEnvironment; 
+use crate::entities::errors; 
+use crate::keywords::is_quantity; 
+use crate::keywords::primitive; 
+use crate::keywords::variable; 
+use crate::semantic::SymbolTable; 
+
+const GROUND: &str = "0"; 
+
+// Ground of the element is local to the element (see `elaborate`), 
+// but node '0' is global in SPICE
+const LOCAL_GROUND: &str = "_0"; 
+
+/// Parameter value of the card
+struct CardValue {
+    text: String, // number or expression in braces
+    behavior: Option<String>, // expression of the behavioral source if the value depends on 'time'
+}
+
+struct SpiceWriter<'a> {
+    table: &'a SymbolTable, 
+    functions: &'a FunctionEnvironment, 
+    lines: Vec<String>, // subcircuits, dependencies first
+    exported: Vec<String>, // elements which are written or being written
+    calls: Vec<String>, // user functions used by the written expressions
+}
+
+/// Writes the elaborated circuit as a SPICE deck. Every element used by
+/// the circuit becomes a subcircuit, user functions become `.FUNC`
+/// definitions and parameters of the circuit entries are evaluated.
+/// Sources depending on 'time' become behavioral sources.
+pub fn export_spice(netlist: &Netlist, 
+                    table: &SymbolTable, 
+                    circuit: &Circuit, 
+                    errs_acc: &mut Vec<ErrorEntry>) -> String {
+
+    let mut writer = SpiceWriter {
+        table, 
+        functions: &netlist.functions, 
+        lines: vec![], 
+        exported: vec![], 
+        calls: vec![], 
+    }; 
+
+    let file_name = get_file_name(table, &circuit.name); 
+    let mut cards: Vec<String> = vec![]; 
+    for (entry, params) in circuit.instances.iter().zip(bind_circuit_entries(table, circuit)) {
+        let mut values: Vec<CardValue> = vec![]; 
+        for param in &params {
+            match writer.get_circuit_value(entry, param, &netlist.variables) {
+                Result::Ok(value) => values.push(value), 
+                Result::Err(err) => errs_acc.push(err.in_file(&file_name)), 
+            }
+        }
+
+        if values.len() != params.len() {
+            continue; 
+        }
+
+        match writer.get_entry_cards(entry, &values, &get_names(&entry.nodes), errs_acc) {
+            Result::Ok(entry_cards) => {
+                cards.extend(get_comment_lines(&entry.docs)); 
+                cards.extend(entry_cards); 
+            }, 
+            Result::Err(reason) => errs_acc.push(unsupported(entry, reason).in_file(&file_name)), 
+        }
+    }
+
+    let mut deck: Vec<String> = vec![format!("* Circuit {}", circuit.name)]; 
+    deck.extend(get_comment_lines(&circuit.docs)); 
+    deck.extend(writer.get_function_lines()); 
+    deck.extend(writer.lines); 
+    deck.extend(cards); 
+    deck.push(".END".to_string()); 
+
+    deck.join("\n") + "\n"
+}
+
+impl<'a> SpiceWriter<'a> {
+    /// Values of the circuit entries are evaluated, except the ones
+    /// of behavioral sources
+    fn get_circuit_value(&mut self, 
+                         entry: &Instance, 
+                         param: &BoundExpression, 
+                         variables: &HashMap<String, f64>) -> Result<CardValue, Box<ErrorEntry>> {
+
+        if let Option::Some((name, _)) = param.get_members().first() {
+            return Result::Err(Box::new(unsupported(entry, format!("it depends on '{}'", name)))); 
+        }
+
+        let names: Vec<String> = param.expression.get_variables().into_iter().map(|x| x.0).collect(); 
+        check_variables(&names).map_err(|reason| unsupported(entry, reason))?; 
+        if !names.iter().any(|x| x == variable::TIME) {
+            let value = param.evaluate(self.functions, variables)?; 
+            return Result::Ok(CardValue { text: format_number(value), behavior: Option::None }); 
+        }
+
+        self.add_calls(&param.expression); 
+        let text = param.expression.to_string_renamed(&|name| {
+            if name == variable::TIME {
+                return name.to_string(); 
+            }
+
+            if let Option::Some(function) = self.functions.get(name) {
+                return get_spice_name(&function.name); 
+            }
+
+            match param.scope.get(name) {
+                Option::Some(member) => member.evaluate(self.functions, variables).map(format_number).unwrap_or(name.to_string()), 
+                Option::None => variables.get(name).map(|x| format_number(*x)).unwrap_or(name.to_string()), 
+            }
+        }); 
+
+        Result::Ok(CardValue { text: format!("{{{}}}", text), behavior: Option::Some(text) })
+    }
+
+    /// Values of the element entries are expressions of the element
+    /// parameters, parameters of other entries (e.g. 'R1.R') are replaced
+    /// by their expressions
+    fn get_element_value(&mut self, expression: &Expression, entries: &Vec<Instance>) -> Result<CardValue, String> {
+        let mut names: Vec<String> = vec![]; 
+        self.collect_names(expression, entries, &mut names); 
+        if let Option::Some(name) = names.iter().find(|x| x.contains('.')) {
+            return Result::Err(format!("it depends on '{}'", name)); 
+        }
+
+        check_variables(&names)?; 
+        self.add_calls(expression); 
+        let text = self.render(expression, entries); 
+        match names.iter().any(|x| x == variable::TIME) {
+            true => Result::Ok(CardValue { text: format!("{{{}}}", text), behavior: Option::Some(text) }), 
+            false if text.parse::<f64>().is_ok() => Result::Ok(CardValue { text, behavior: Option::None }), 
+            false => Result::Ok(CardValue { text: format!("{{{}}}", text), behavior: Option::None }), 
+        }
+    }
+
+    /// Variables and quantities (e.g. 'JK.U') the expression depends on
+    fn collect_names(&self, expression: &Expression, entries: &Vec<Instance>, acc: &mut Vec<String>) {
+        acc.extend(expression.get_variables().into_iter().map(|x| x.0)); 
+        for (instance, member, _) in expression.get_members() {
+            match self.find_member(&instance, &member, entries) {
+                Option::Some(inner) => self.collect_names(inner, entries, acc), 
+                Option::None => acc.push(format!("{}.{}", instance, member)), 
+            }
+        }
+    }
+
+    /// Expression of the parameter `member` of the entry `instance`
+    fn find_member<'b>(&self, instance: &str, member: &str, entries: &'b [Instance]) -> Option<&'b Expression> {
+        if is_quantity(member) {
+            return Option::None; 
+        }
+
+        let entry = entries.iter().find(|x| x.entry_name == instance)?; 
+        let index = self.table.get_param_names(&entry.element_name)?.iter().position(|x| x == member)?; 
+        entry.param_expressions.get(index)
+    }
+
+    fn render(&self, expression: &Expression, entries: &Vec<Instance>) -> String {
+        expression.to_string_renamed(&|name| {
+            if let Option::Some(function) = self.functions.get(name) {
+                return get_spice_name(&function.name); 
+            }
+
+            match name.split_once('.').and_then(|(instance, member)| self.find_member(instance, member, entries)) {
+                Option::Some(inner) => format!("({})", self.render(inner, entries)), 
+                Option::None => name.to_string(), 
+            }
+        })
+    }
+
+    fn add_calls(&mut self, expression: &Expression) {
+        for (name, _, _) in expression.get_calls() {
+            if self.functions.get(&name).is_some() && !self.calls.contains(&name) {
+                self.calls.push(name); 
+            }
+        }
+    }
+
+    /// Cards of the element entry, subcircuits of elements are written
+    /// when they are used for the first time
+    fn get_entry_cards(&mut self, 
+                       entry: &Instance, 
+                       values: &Vec<CardValue>, 
+                       nodes: &[String], 
+                       errs_acc: &mut Vec<ErrorEntry>) -> Result<Vec<String>, String> {
+
+        let name = &entry.entry_name; 
+        let nodes = nodes.join(" "); 
+        let is_source = entry.element_name == primitive::VOLTAGE_SOURCE || entry.element_name == primitive::CURRENT_SOURCE; 
+        if !is_source && values.iter().any(|x| x.behavior.is_some()) {
+            return Result::Err(format!("only sources can depend on '{}'", variable::TIME)); 
+        }
+
+        let cards = match entry.element_name.as_str() {
+            primitive::RESISTOR => vec![format!("{} {} {}", get_card_name("R", name), nodes, values[0].text)], 
+            primitive::CAPACITOR => vec![format!("{} {} {}", get_card_name("C", name), nodes, values[0].text)], 
+            primitive::INDUCTOR => vec![format!("{} {} {}", get_card_name("L", name), nodes, values[0].text)], 
+            primitive::VOLTAGE_SOURCE | primitive::CURRENT_SOURCE => {
+                let letter = match entry.element_name == primitive::VOLTAGE_SOURCE {
+                    true => "V", 
+                    false => "I", 
+                }; 
+
+                match &values[0].behavior {
+                    Option::Some(behavior) => vec![format!("{} {} {}={}", get_card_name("B", name), nodes, letter, behavior)], 
+                    Option::None => vec![format!("{} {} {}", get_card_name(letter, name), nodes, values[0].text)], 
+                }
+            }, 
+            primitive::DIODE => {
+                let model = format!("D_{}", name); 
+                vec![
+                    format!("{} {} {}", get_card_name("D", name), nodes, model), 
+                    format!(".MODEL {} D(IS={} N={})", model, values[0].text, values[1].text), 
+                ]
+            }, 
+            element_name => {
+                self.write_subcircuit(element_name, errs_acc); 
+                let param_names = self.table.get_param_names(element_name).unwrap_or_default(); 
+                let params: Vec<String> = param_names.iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{}={}", name, value.text))
+                    .collect(); 
+
+                match params.is_empty() {
+                    true => vec![format!("{} {} {}", get_card_name("X", name), nodes, get_spice_name(element_name))], 
+                    false => vec![format!("{} {} {} PARAMS: {}", get_card_name("X", name), nodes, get_spice_name(element_name), params.join(" "))], 
+                }
+            }, 
+        }; 
+
+        Result::Ok(cards)
+    }
+
+    fn write_subcircuit(&mut self, name: &str, errs_acc: &mut Vec<ErrorEntry>) {
+        if self.exported.iter().any(|x| x == name) {
+            return; 
+        }
+
+        self.exported.push(name.to_string()); 
+        let table = self.table; 
+        let element = match table.elements.get(name) {
+            Option::Some(element) => element, 
+            Option::None => return, // reported by the semantic analysis
+        }; 
+
+        let file_name = get_file_name(self.table, name); 
+        let mut body: Vec<String> = vec![]; 
+        for entry in &element.instances {
+            let mut values: Vec<CardValue> = vec![]; 
+            for expression in &entry.param_expressions {
+                match self.get_element_value(expression, &element.instances) {
+                    Result::Ok(value) => values.push(value), 
+                    Result::Err(reason) => errs_acc.push(unsupported(entry, reason).in_file(&file_name)), 
+                }
+            }
+
+            if values.len() != entry.param_expressions.len() {
+                continue; 
+            }
+
+            let nodes: Vec<String> = entry.nodes.iter()
+                .map(|x| match x.name == GROUND {
+                    true => LOCAL_GROUND.to_string(), 
+                    false => x.name.clone(), 
+                })
+                .collect(); 
+
+            match self.get_entry_cards(entry, &values, &nodes, errs_acc) {
+                Result::Ok(cards) => {
+                    body.extend(get_comment_lines(&entry.docs)); 
+                    body.extend(cards); 
+                }, 
+                Result::Err(reason) => errs_acc.push(unsupported(entry, reason).in_file(&file_name)), 
+            }
+        }
+
+        let mut header = format!(".SUBCKT {} {}", get_spice_name(name), get_names(&element.nodes).join(" ")); 
+        if !element.params.is_empty() {
+            let params: Vec<String> = element.params.iter().map(|x| format!("{}=0", x.name)).collect(); 
+            header = format!("{} PARAMS: {}", header, params.join(" ")); 
+        }
+
+        self.lines.extend(get_comment_lines(&element.docs)); 
+        self.lines.push(header); 
+        self.lines.extend(body); 
+        self.lines.push(format!(".ENDS {}", get_spice_name(name))); 
+    }
+
+    /// `.FUNC` definitions of the used functions, the functions
+    /// they call are defined first
+    fn get_function_lines(&self) -> Vec<String> {
+        let mut names = self.calls.clone(); 
+        names.sort(); 
+
+        let mut written: Vec<String> = vec![]; 
+        let mut lines: Vec<String> = vec![]; 
+        for name in &names {
+            self.write_function(name, &mut written, &mut lines); 
+        }
+
+        lines
+    }
+
+    fn write_function(&self, name: &str, written: &mut Vec<String>, lines: &mut Vec<String>) {
+        if written.iter().any(|x| x == name) {
+            return; 
+        }
+
+        written.push(name.to_string()); 
+        let function = match self.functions.get(name) {
+            Option::Some(function) => function, 
+            Option::None => return, 
+        }; 
+
+        let mut calls: Vec<String> = function.expression.get_calls().into_iter().map(|x| x.0).collect(); 
+        calls.sort(); 
+        for call in &calls {
+            self.write_function(call, written, lines); 
+        }
+
+        let body = self.render(&function.expression, &vec![]); 
+        lines.push(format!(".FUNC {}({}) {{{}}}", get_spice_name(name), function.param_names.join(", "), body)); 
+    }
+}
+
+/// Values of the AC analysis have no SPICE equivalent
+fn check_variables(names: &[String]) -> Result<(), String> {
+    match names.iter().find(|x| *x == variable::FREQUENCY || *x == variable::LAPLACE) {
+        Option::Some(name) => Result::Err(format!("it depends on '{}'", name)), 
+        Option::None => Result::Ok(()), 
+    }
+}
+
+fn unsupported(entry: &Instance, reason: String) -> ErrorEntry {
+    ErrorEntry::new(
+        errors::UNSUPPORTED_EXPORT, 
+        &entry.span.position, 
+        &vec![entry.entry_name.clone(), reason])
+        .with_span(&entry.span)
+}
+
+fn get_file_name(table: &SymbolTable, name: &str) -> String {
+    table.file_names.get(name).cloned().unwrap_or_default()
+}
+
+/// Qualified names (e.g. 'std.F9') aren't valid SPICE names
+fn get_spice_name(name: &str) -> String {
+    name.replace('.', "_")
+}
+
+/// The first letter of the card name is the type of the device
+fn get_card_name(letter: &str, name: &str) -> String {
+    match name.to_uppercase().starts_with(letter) {
+        true => name.to_string(), 
+        false => format!("{}{}", letter, name), 
+    }
+}
+
+pub fn format_number(value: f64) -> String {
+    let value = value + 0.0; // no negative zero
+    match value != 0.0 && (value.abs() < 1e-3 || value.abs() >= 1e9) {
+        true => format!("{:e}", value), 
+        false => value.to_string(), 
+    }
+}
+
+/// Doc comments of the node as SPICE comment lines
+fn get_comment_lines(docs: &[String]) -> Vec<String> {
+    docs.iter().map(|x| format!("* {}", x)).collect()
+}
@@ -0,0 +1,4 @@
+mod reader; 
+mod spice; 
+
+pub use spice::import_spice; 
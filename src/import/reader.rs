@@ -0,0 +1,177 @@
+use crate::entities::Position; 
+
+/// Word of the SPICE card: name, node, value, '=', '(' or ')'.
+/// Expressions in braces or quotes are one field.
+#[derive(Clone)]
+pub struct Field {
+    pub text: String, 
+    pub position: Position, 
+}
+
+/// Card of the deck with continuation lines joined
+pub struct Card {
+    pub fields: Vec<Field>, 
+    pub comments: Vec<String>, // comment lines before the card
+    pub source: String, // text of the card without comments
+}
+
+impl Card {
+    pub fn position(&self) -> Position {
+        match self.fields.first() {
+            Option::Some(field) => field.position.clone(), 
+            Option::None => Position { line: 0, line_position: 0 }, 
+        }
+    }
+
+    /// Name of the card in upper case, e.g. 'R1' or '.SUBCKT'
+    pub fn name(&self) -> String {
+        self.fields.first().map(|x| x.text.to_uppercase()).unwrap_or_default()
+    }
+
+}
+
+/// Splits the deck into cards. The first line is the title, lines starting
+/// with '*' are comments, lines starting with '+' continue the previous card.
+/// Returns the title and the cards.
+pub fn read_cards(code: &str) -> (String, Vec<Card>) {
+    let mut lines = code.lines().enumerate(); 
+    let title = lines.next().map(|x| x.1.trim().to_string()).unwrap_or_default(); 
+
+    let mut cards: Vec<Card> = vec![]; 
+    let mut comments: Vec<String> = vec![]; 
+    for (index, line) in lines {
+        let trimmed = line.trim(); 
+        if let Option::Some(comment) = trimmed.strip_prefix('*') {
+            comments.push(comment.trim().to_string()); 
+            continue; 
+        }
+
+        let code_part = strip_inline_comment(line); 
+        if let Option::Some(rest) = code_part.trim_start().strip_prefix('+') {
+            let offset = code_part.len() - rest.len(); 
+            if let Option::Some(card) = cards.last_mut() {
+                card.fields.extend(split_fields(rest, index, offset)); 
+                card.source = format!("{} {}", card.source, rest.trim()); 
+            }
+
+            continue; 
+        }
+
+        let fields = split_fields(code_part, index, 0); 
+        if !fields.is_empty() {
+            let source = code_part.trim().to_string(); 
+            cards.push(Card { fields, comments: comments.clone(), source }); 
+            comments.clear(); 
+        }
+    }
+
+    (title, cards)
+}
+
+/// Text after ';' or '$ ' is a comment
+fn strip_inline_comment(line: &str) -> &str {
+    let mut end = line.len(); 
+    if let Option::Some(index) = line.find(';') {
+        end = end.min(index); 
+    }
+
+    if let Option::Some(index) = line.find("$ ") {
+        end = end.min(index); 
+    }
+
+    &line[..end]
+}
+
+fn split_fields(text: &str, line: usize, offset: usize) -> Vec<Field> {
+    let chars: Vec<char> = text.chars().collect(); 
+    let mut fields: Vec<Field> = vec![]; 
+    let mut i = 0; 
+    while i < chars.len() {
+        let ch = chars[i]; 
+        if ch.is_whitespace() || ch == ',' {
+            i += 1; 
+            continue; 
+        }
+
+        let start = i; 
+        match ch {
+            '{' | '\'' => {
+                let closing = if ch == '{' { '}' } else { '\'' }; 
+                i += 1; 
+                while i < chars.len() && chars[i] != closing {
+                    i += 1; 
+                }
+
+                i = (i + 1).min(chars.len()); 
+            }, 
+            '=' | '(' | ')' => i += 1, 
+            _ => {
+                while i < chars.len() && !chars[i].is_whitespace() && !"=(){}',".contains(chars[i]) {
+                    i += 1; 
+                }
+            }, 
+        }
+
+        fields.push(Field {
+            text: chars[start..i].iter().collect(), 
+            position: Position { line, line_position: offset + start }, 
+        }); 
+    }
+
+    fields
+}
+
+/// Parses the number with an engineering suffix, e.g. '4.7k', '10u'
+/// or '1MEG'. Letters after the suffix are units and are ignored.
+/// The case doesn't matter, so '1F' is 1e-15 here, not one farad.
+pub fn parse_number(text: &str) -> Option<f64> {
+    let chars: Vec<char> = text.chars().collect(); 
+    let mut end = 0; 
+    while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.' ||
+        (end == 0 && (chars[end] == '-' || chars[end] == '+'))) {
+        end += 1; 
+    }
+
+    // Exponent: 'e' followed by a digit or a signed digit
+    if end < chars.len() && (chars[end] == 'e' || chars[end] == 'E') {
+        let mut exponent_end = end + 1; 
+        if exponent_end < chars.len() && (chars[exponent_end] == '-' || chars[exponent_end] == '+') {
+            exponent_end += 1; 
+        }
+
+        if exponent_end < chars.len() && chars[exponent_end].is_ascii_digit() {
+            while exponent_end < chars.len() && chars[exponent_end].is_ascii_digit() {
+                exponent_end += 1; 
+            }
+
+            end = exponent_end; 
+        }
+    }
+
+    let mantissa: String = chars[..end].iter().collect(); 
+    let value = mantissa.parse::<f64>().ok()?; 
+    let suffix: String = chars[end..].iter().collect::<String>().to_uppercase(); 
+    if !suffix.chars().all(|x| x.is_alphabetic()) {
+        return Option::None; 
+    }
+
+    let exponent = match suffix.as_str() {
+        x if x.starts_with("MEG") => 6, 
+        x if x.starts_with("MIL") => return Option::Some(value * 25.4e-6), 
+        x if x.starts_with('T') => 12, 
+        x if x.starts_with('G') => 9, 
+        x if x.starts_with('K') => 3, 
+        x if x.starts_with('M') => -3, 
+        x if x.starts_with('U') => -6, 
+        x if x.starts_with('N') => -9, 
+        x if x.starts_with('P') => -12, 
+        x if x.starts_with('F') => -15, 
+        _ => 0, 
+    }; 
+
+    // Scaling by the decimal exponent keeps 10u equal to 1e-5
+    match mantissa.contains(['e', 'E']) {
+        true => Option::Some(value * 10f64.powi(exponent)), 
+        false => format!("{}e{}", mantissa, exponent).parse::<f64>().ok(), 
+    }
+}
@@ -0,0 +1,826 @@
+use std::collections::HashMap; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::entities::Position; 
+use crate::export::format_number; 
+use crate::import::reader::Card; 
+use crate::import::reader::Field; 
+use crate::import::reader::read_cards; 
+use crate::import::reader::parse_number; 
+use crate::keywords::is_keyword; 
+use crate::keywords::is_builtin_variable; 
+use crate::keywords::primitive; 
+
+/// Name of the circuit made of the top-level cards
+const MAIN_CIRCUIT: &str = "Main"; 
+
+/// Pin added to sub-circuits which use the global ground
+const GROUND_PIN: &str = "GND"; 
+
+const PARAMS_MARKER: &str = "PARAMS:"; 
+
+struct Model {
+    name: String, 
+    kind: String, // D, NPN or PNP
+    params: Vec<(String, Field)>, 
+}
+
+struct Subcircuit {
+    name: String, 
+    pins: Vec<Field>, 
+    params: Vec<(Field, Field)>, // names with default values
+    cards: Vec<Card>, 
+    comments: Vec<String>, 
+    uses_ground: bool, 
+    position: Position, 
+}
+
+/// Names of the expressions in the current scope: upper case SPICE name
+/// and its edesigner text
+type Names = HashMap<String, String>; 
+
+/// Converts the SPICE deck to the edesigner source. Unsupported cards
+/// are reported and kept in the output as comments.
+pub fn import_spice(code: &str, errs_acc: &mut Vec<ErrorEntry>) -> String {
+    let (title, cards) = read_cards(code); 
+    let mut importer = Importer {
+        models: vec![], 
+        subcircuits: vec![], 
+        top_cards: vec![], 
+        global_params: vec![], 
+        global_names: HashMap::new(), 
+    }; 
+
+    importer.collect(cards, errs_acc); 
+    importer.find_ground_users(); 
+
+    let mut result = String::new(); 
+    if !title.is_empty() {
+        result += &format!("// {}\n", title); 
+    }
+
+    result += &importer.write_params(errs_acc); 
+    for model in &importer.models {
+        if model.kind != "D" {
+            result += &importer.write_transistor(model, errs_acc); 
+        }
+    }
+
+    for subcircuit in &importer.subcircuits {
+        result += &importer.write_subcircuit(subcircuit, errs_acc); 
+    }
+
+    if !importer.top_cards.is_empty() {
+        let names = importer.global_names.clone(); 
+        result += &format!("\ncircuit {}()\n{{\n", MAIN_CIRCUIT); 
+        for card in &importer.top_cards {
+            result += &importer.convert_card(card, &names, false, errs_acc); 
+        }
+
+        result += "}\n"; 
+    }
+
+    result
+}
+
+struct Importer {
+    models: Vec<Model>, 
+    subcircuits: Vec<Subcircuit>, 
+    top_cards: Vec<Card>, 
+    global_params: Vec<(Field, Field)>, 
+    global_names: Names, // '.PARAM' names, which become functions
+}
+
+impl Importer {
+    /// Splits the cards into models, parameters, sub-circuits and
+    /// the top-level circuit
+    fn collect(&mut self, cards: Vec<Card>, errs_acc: &mut Vec<ErrorEntry>) {
+        let mut current: Option<Subcircuit> = Option::None; 
+        for card in cards {
+            let name = card.name(); 
+            match name.as_str() {
+                ".END" => break, 
+                ".SUBCKT" if current.is_some() => unsupported(&card, errs_acc), 
+                ".SUBCKT" => current = self.read_subcircuit(card, errs_acc), 
+                ".ENDS" => match current.take() {
+                    Option::Some(subcircuit) => self.subcircuits.push(subcircuit), 
+                    Option::None => invalid(&card, "'.ENDS' without '.SUBCKT'", errs_acc), 
+                }, 
+                ".MODEL" => self.read_model(&card, errs_acc), 
+                ".PARAM" if current.is_some() => unsupported(&card, errs_acc), 
+                ".PARAM" => {
+                    let (positional, assignments) = split_assignments(&card.fields[1..]); 
+                    if !positional.is_empty() {
+                        invalid(&card, "expected 'name=value'", errs_acc); 
+                    }
+
+                    for (name, value) in assignments {
+                        let text = format!("{}()", sanitize(&name.text)); 
+                        self.global_names.insert(name.text.to_uppercase(), text); 
+                        self.global_params.push((name, value)); 
+                    }
+                }, 
+                _ => match current.as_mut() {
+                    Option::Some(subcircuit) => subcircuit.cards.push(card), 
+                    Option::None => self.top_cards.push(card), 
+                }, 
+            }
+        }
+
+        if let Option::Some(subcircuit) = current {
+            errs_acc.push(ErrorEntry::new(
+                errors::INVALID_SPICE_CARD, 
+                &subcircuit.position, 
+                &vec![format!(".SUBCKT {}", subcircuit.name), "missing '.ENDS'".to_string()])); 
+            self.subcircuits.push(subcircuit); 
+        }
+    }
+
+    /// `.SUBCKT name pins... [PARAMS:] name=value ...`
+    fn read_subcircuit(&self, card: Card, errs_acc: &mut Vec<ErrorEntry>) -> Option<Subcircuit> {
+        let (positional, params) = split_assignments(&card.fields[1..]); 
+        if positional.is_empty() {
+            invalid(&card, "missing name", errs_acc); 
+            return Option::None; 
+        }
+
+        Option::Some(Subcircuit {
+            name: sanitize(&positional[0].text), 
+            pins: positional[1..].to_vec(), 
+            params, 
+            cards: vec![], 
+            uses_ground: false, 
+            position: card.position(), 
+            comments: card.comments, 
+        })
+    }
+
+    /// `.MODEL name type (name=value ...)`
+    fn read_model(&mut self, card: &Card, errs_acc: &mut Vec<ErrorEntry>) {
+        let fields: Vec<Field> = card.fields[1..].iter()
+            .filter(|x| x.text != "(" && x.text != ")")
+            .cloned()
+            .collect(); 
+        let (positional, params) = split_assignments(&fields); 
+        if positional.len() != 2 {
+            invalid(card, "expected '.MODEL name type (params)'", errs_acc); 
+            return; 
+        }
+
+        let kind = positional[1].text.to_uppercase(); 
+        if !matches!(kind.as_str(), "D" | "NPN" | "PNP") {
+            unsupported(card, errs_acc); 
+            return; 
+        }
+
+        self.models.push(Model {
+            name: positional[0].text.clone(), 
+            kind, 
+            params: params.into_iter().map(|(name, value)| (name.text.to_uppercase(), value)).collect(), 
+        }); 
+    }
+
+    fn find_model(&self, name: &str) -> Option<&Model> {
+        self.models.iter().find(|x| x.name.eq_ignore_ascii_case(name))
+    }
+
+    fn find_subcircuit(&self, name: &str) -> Option<&Subcircuit> {
+        self.subcircuits.iter().find(|x| x.name.eq_ignore_ascii_case(&sanitize(name)))
+    }
+
+    /// Ground is global in SPICE, but local in edesigner elements, so
+    /// sub-circuits which use it (directly or through instances) get the ground pin
+    fn find_ground_users(&mut self) {
+        loop {
+            let mut changed = false; 
+            for i in 0..self.subcircuits.len() {
+                if self.subcircuits[i].uses_ground {
+                    continue; 
+                }
+
+                let uses_ground = self.subcircuits[i].cards.iter().any(|card| {
+                    let nodes = self.get_nodes(card); 
+                    nodes.iter().any(|x| is_ground(&x.text)) || (card.name().starts_with('X') &&
+                        self.get_instance_subcircuit(card).is_some_and(|x| x.uses_ground))
+                }); 
+
+                if uses_ground {
+                    self.subcircuits[i].uses_ground = true; 
+                    changed = true; 
+                }
+            }
+
+            if !changed {
+                break; 
+            }
+        }
+    }
+
+    fn get_instance_subcircuit(&self, card: &Card) -> Option<&Subcircuit> {
+        let (positional, _) = split_assignments(&card.fields[1..]); 
+        positional.last().and_then(|x| self.find_subcircuit(&x.text))
+    }
+
+    /// Node fields of the device card
+    fn get_nodes(&self, card: &Card) -> Vec<Field> {
+        let (positional, _) = split_assignments(&card.fields[1..]); 
+        let count = match card.name().chars().next() {
+            Option::Some('R' | 'C' | 'L' | 'V' | 'I' | 'D') => 2, 
+            Option::Some('Q') => 3, 
+            Option::Some('X') => positional.len().saturating_sub(1), 
+            _ => 0, 
+        }; 
+
+        positional.into_iter().take(count).collect()
+    }
+
+    /// `.PARAM` values become functions without parameters
+    fn write_params(&self, errs_acc: &mut Vec<ErrorEntry>) -> String {
+        let mut result = String::new(); 
+        for (name, value) in &self.global_params {
+            if let Option::Some(text) = translate_value(value, &self.global_names, errs_acc) {
+                result += &format!("function {}() => {};\n", sanitize(&name.text), text); 
+            }
+        }
+
+        match result.is_empty() {
+            true => result, 
+            false => format!("\n{}", result), 
+        }
+    }
+
+    /// Bipolar transistor model as the Ebers-Moll transport model
+    /// made of two diodes and two controlled current sources
+    fn write_transistor(&self, model: &Model, errs_acc: &mut Vec<ErrorEntry>) -> String {
+        let is = self.get_model_param(model, "IS", "1e-14", errs_acc); 
+        let nf = self.get_model_param(model, "NF", "1", errs_acc); 
+        let nr = self.get_model_param(model, "NR", "1", errs_acc); 
+        let bf = self.get_model_param(model, "BF", "100", errs_acc); 
+        let br = self.get_model_param(model, "BR", "1", errs_acc); 
+        let alpha = |beta: &str| match parse_number(beta) {
+            Option::Some(value) => format_number(value / (1.0 + value)), 
+            Option::None => format!("({0} / (1 + {0}))", beta), 
+        }; 
+
+        let (af, ar) = (alpha(&bf), alpha(&br)); 
+        let (emitter, collector, forward, reverse) = match model.kind.as_str() {
+            "NPN" => ("[B, E]", "[B, C]", "[C, B]", "[E, B]"), 
+            _ => ("[E, B]", "[C, B]", "[B, C]", "[B, E]"), 
+        }; 
+
+        let mut result = format!("\nelement [C, B, E] {}(AREA)\n{{\n", sanitize(&model.name)); 
+        result += &format!("    DE{} = {}({}, {});\n", emitter, primitive::DIODE, combine(&combine(&is, "/", &af), "*", "AREA"), nf); 
+        result += &format!("    DC{} = {}({}, {});\n", collector, primitive::DIODE, combine(&combine(&is, "/", &ar), "*", "AREA"), nr); 
+        result += &format!("    JF{} = {}({});\n", forward, primitive::CURRENT_SOURCE, combine(&af, "*", "DE.I")); 
+        result += &format!("    JR{} = {}({});\n", reverse, primitive::CURRENT_SOURCE, combine(&ar, "*", "DC.I")); 
+        result + "}\n"
+    }
+
+    fn get_model_param(&self, model: &Model, name: &str, default: &str, errs_acc: &mut Vec<ErrorEntry>) -> String {
+        model.params.iter()
+            .find(|x| x.0 == name)
+            .and_then(|x| translate_value(&x.1, &self.global_names, errs_acc))
+            .unwrap_or(default.to_string())
+    }
+
+    /// `.SUBCKT` as the element, its parameters shadow global ones
+    fn write_subcircuit(&self, subcircuit: &Subcircuit, errs_acc: &mut Vec<ErrorEntry>) -> String {
+        let mut names = self.global_names.clone(); 
+        for (name, _) in &subcircuit.params {
+            names.insert(name.text.to_uppercase(), sanitize(&name.text)); 
+        }
+
+        let mut pins: Vec<String> = subcircuit.pins.iter().map(|x| get_node_name(&x.text, true)).collect(); 
+        if subcircuit.uses_ground {
+            pins.push(GROUND_PIN.to_string()); 
+        }
+
+        let params: Vec<String> = subcircuit.params.iter().map(|x| sanitize(&x.0.text)).collect(); 
+        let mut result = String::from("\n"); 
+        result += &write_comments(&subcircuit.comments, ""); 
+        result += &format!("element [{}] {}({})\n{{\n", pins.join(", "), subcircuit.name, params.join(", ")); 
+        for card in &subcircuit.cards {
+            result += &self.convert_card(card, &names, true, errs_acc); 
+        }
+
+        result + "}\n"
+    }
+
+    /// Element entry of the device card with its comments
+    fn convert_card(&self, card: &Card, names: &Names, in_subcircuit: bool, errs_acc: &mut Vec<ErrorEntry>) -> String {
+        let mut result = write_comments(&card.comments, "    "); 
+        let errors_count = errs_acc.len(); 
+        let entry = match card.name().chars().next() {
+            Option::Some('R') => self.convert_passive(card, primitive::RESISTOR, names, errs_acc), 
+            Option::Some('C') => self.convert_passive(card, primitive::CAPACITOR, names, errs_acc), 
+            Option::Some('L') => self.convert_passive(card, primitive::INDUCTOR, names, errs_acc), 
+            Option::Some('V') => self.convert_source(card, primitive::VOLTAGE_SOURCE, names, errs_acc), 
+            Option::Some('I') => self.convert_source(card, primitive::CURRENT_SOURCE, names, errs_acc), 
+            Option::Some('D') => self.convert_diode(card, names, errs_acc), 
+            Option::Some('Q') => self.convert_transistor(card, names, errs_acc), 
+            Option::Some('X') => self.convert_instance(card, names, errs_acc), 
+            _ => {
+                unsupported(card, errs_acc); 
+                Option::None
+            }, 
+        }; 
+
+        match entry {
+            Option::Some((element, nodes, params)) => {
+                let nodes: Vec<String> = nodes.iter().map(|x| get_node_name(x, in_subcircuit)).collect(); 
+                result += &format!("    {}[{}] = {}({});\n", 
+                    sanitize(&card.fields[0].text), nodes.join(", "), element, params.join(", ")); 
+            }, 
+            Option::None if errs_acc.len() > errors_count => {
+                result += &format!("    // unsupported: {}\n", card.source); 
+            }, 
+            Option::None => { }, 
+        }
+
+        result
+    }
+
+    /// `R1 n1 n2 value`
+    fn convert_passive(&self, card: &Card, element: &str, names: &Names, errs_acc: &mut Vec<ErrorEntry>) -> Option<Entry> {
+        let (positional, assignments) = split_assignments(&card.fields[1..]); 
+        if positional.len() < 3 {
+            invalid(card, "expected 'name node1 node2 value'", errs_acc); 
+            return Option::None; 
+        }
+
+        for extra in positional[3..].iter().chain(assignments.iter().map(|x| &x.0)) {
+            unsupported_option(card, extra, errs_acc); 
+        }
+
+        let value = translate_value(&positional[2], names, errs_acc)?; 
+        Option::Some((element.to_string(), get_texts(&positional[..2]), vec![value]))
+    }
+
+    /// `V1 n1 n2 [DC] value [AC mag [phase]]` or `V1 n1 n2 SIN(VO VA FREQ [TD])`
+    fn convert_source(&self, card: &Card, element: &str, names: &Names, errs_acc: &mut Vec<ErrorEntry>) -> Option<Entry> {
+        let fields = &card.fields; 
+        if fields.len() < 3 {
+            invalid(card, "expected 'name node1 node2 value'", errs_acc); 
+            return Option::None; 
+        }
+
+        let mut value = "0".to_string(); 
+        let mut i = 3; 
+        while i < fields.len() {
+            let field = &fields[i]; 
+            match field.text.to_uppercase().as_str() {
+                "DC" if i + 1 < fields.len() => {
+                    value = translate_value(&fields[i + 1], names, errs_acc)?; 
+                    i += 2; 
+                }, 
+                "AC" => {
+                    // Small-signal magnitude and phase are dropped: the AC 
+                    // analysis drives the source chosen by '--input' 
+                    unsupported_option(card, field, errs_acc); 
+                    i += 1; 
+                    let end = (i + 2).min(fields.len()); 
+                    while i < end && parse_number(&fields[i].text).is_some() {
+                        i += 1; 
+                    }
+                }, 
+                "SIN" => {
+                    let (args, next) = get_group(fields, i + 1); 
+                    let args: Vec<String> = args.iter()
+                        .map(|x| translate_value(x, names, errs_acc))
+                        .collect::<Option<Vec<String>>>()?; 
+                    if args.len() < 3 {
+                        invalid(card, "expected 'SIN(VO VA FREQ [TD])'", errs_acc); 
+                        return Option::None; 
+                    }
+
+                    if args.len() > 4 {
+                        // damping and phase
+                        unsupported_option(card, field, errs_acc); 
+                        return Option::None; 
+                    }
+
+                    value = get_sine(&args); 
+                    i = next; 
+                }, 
+                "PULSE" | "PWL" | "EXP" | "SFFM" => {
+                    unsupported_option(card, field, errs_acc); 
+                    return Option::None; 
+                }, 
+                _ if i == 3 => {
+                    value = translate_value(field, names, errs_acc)?; 
+                    i += 1; 
+                }, 
+                _ => {
+                    unsupported_option(card, field, errs_acc); 
+                    i += 1; 
+                }, 
+            }
+        }
+
+        Option::Some((element.to_string(), get_texts(&fields[1..3]), vec![value]))
+    }
+
+    /// `D1 anode cathode model [area]`
+    fn convert_diode(&self, card: &Card, names: &Names, errs_acc: &mut Vec<ErrorEntry>) -> Option<Entry> {
+        let (positional, assignments) = split_assignments(&card.fields[1..]); 
+        if positional.len() < 3 {
+            invalid(card, "expected 'name anode cathode model'", errs_acc); 
+            return Option::None; 
+        }
+
+        for extra in positional.iter().skip(4).chain(assignments.iter().map(|x| &x.0)) {
+            unsupported_option(card, extra, errs_acc); 
+        }
+
+        let model = self.get_model(card, &positional[2], &["D"], errs_acc)?; 
+        let area = match positional.get(3) {
+            Option::Some(field) => translate_value(field, names, errs_acc)?, 
+            Option::None => "1".to_string(), 
+        }; 
+
+        let is = self.get_model_param(model, "IS", "1e-14", errs_acc); 
+        let n = self.get_model_param(model, "N", "1", errs_acc); 
+        let params = vec![combine(&is, "*", &area), n]; 
+        Option::Some((primitive::DIODE.to_string(), get_texts(&positional[..2]), params))
+    }
+
+    /// `Q1 collector base emitter [substrate] model [area]`
+    fn convert_transistor(&self, card: &Card, names: &Names, errs_acc: &mut Vec<ErrorEntry>) -> Option<Entry> {
+        let (positional, assignments) = split_assignments(&card.fields[1..]); 
+        if positional.len() < 4 {
+            invalid(card, "expected 'name collector base emitter model'", errs_acc); 
+            return Option::None; 
+        }
+
+        // The substrate node is optional and isn't modelled
+        let model_index = match positional.len() > 4 && self.find_model(&positional[3].text).is_none() {
+            true => 4, 
+            false => 3, 
+        }; 
+
+        if model_index == 4 {
+            unsupported_substrate(card, &positional[3], errs_acc); 
+        }
+
+        for extra in positional.iter().skip(model_index + 2).chain(assignments.iter().map(|x| &x.0)) {
+            unsupported_option(card, extra, errs_acc); 
+        }
+
+        let model = self.get_model(card, &positional[model_index], &["NPN", "PNP"], errs_acc)?; 
+        let area = match positional.get(model_index + 1) {
+            Option::Some(field) => translate_value(field, names, errs_acc)?, 
+            Option::None => "1".to_string(), 
+        }; 
+
+        Option::Some((sanitize(&model.name), get_texts(&positional[..3]), vec![area]))
+    }
+
+    fn get_model(&self, card: &Card, name: &Field, kinds: &[&str], errs_acc: &mut Vec<ErrorEntry>) -> Option<&Model> {
+        match self.find_model(&name.text) {
+            Option::Some(model) if kinds.contains(&model.kind.as_str()) => Option::Some(model), 
+            Option::Some(model) => {
+                invalid(card, &format!("model '{}' has type {}", model.name, model.kind), errs_acc); 
+                Option::None
+            }, 
+            Option::None => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::UNDEFINED_SPICE_MODEL, 
+                    &name.position, 
+                    &vec![name.text.clone()])); 
+                Option::None
+            }, 
+        }
+    }
+
+    /// `X1 nodes... name [PARAMS:] name=value ...`. Missing parameters
+    /// get default values, which may depend on other parameters.
+    fn convert_instance(&self, card: &Card, names: &Names, errs_acc: &mut Vec<ErrorEntry>) -> Option<Entry> {
+        let (positional, assignments) = split_assignments(&card.fields[1..]); 
+        let name = match positional.last() {
+            Option::Some(name) => name, 
+            Option::None => {
+                invalid(card, "missing sub-circuit name", errs_acc); 
+                return Option::None; 
+            }, 
+        }; 
+
+        let subcircuit = match self.find_subcircuit(&name.text) {
+            Option::Some(subcircuit) => subcircuit, 
+            Option::None => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::UNDEFINED_SPICE_SUBCIRCUIT, 
+                    &name.position, 
+                    &vec![name.text.clone()])); 
+                return Option::None; 
+            }, 
+        }; 
+
+        let mut nodes = get_texts(&positional[..positional.len() - 1]); 
+        if nodes.len() != subcircuit.pins.len() {
+            invalid(card, &format!("sub-circuit '{}' has {} pins", subcircuit.name, subcircuit.pins.len()), errs_acc); 
+            return Option::None; 
+        }
+
+        for (param, _) in &assignments {
+            if !subcircuit.params.iter().any(|x| x.0.text.eq_ignore_ascii_case(&param.text)) {
+                invalid(card, &format!("sub-circuit '{}' has no parameter '{}'", subcircuit.name, param.text), errs_acc); 
+                return Option::None; 
+            }
+        }
+
+        // Defaults are evaluated in the scope of the sub-circuit
+        let mut default_names = self.global_names.clone(); 
+        let mut values: Vec<String> = vec![]; 
+        for (param, default) in &subcircuit.params {
+            let value = match assignments.iter().find(|x| x.0.text.eq_ignore_ascii_case(&param.text)) {
+                Option::Some((_, value)) => translate_value(value, names, errs_acc)?, 
+                Option::None => translate_value(default, &default_names, errs_acc)?, 
+            }; 
+
+            default_names.insert(param.text.to_uppercase(), wrap(&value)); 
+            values.push(value); 
+        }
+
+        if subcircuit.uses_ground {
+            nodes.push("0".to_string()); 
+        }
+
+        Option::Some((subcircuit.name.clone(), nodes, values))
+    }
+}
+
+/// Element name, nodes and parameter expressions of the entry
+type Entry = (String, Vec<String>, Vec<String>); 
+
+/// The card is kept as a comment, so it's only a warning 
+fn unsupported(card: &Card, errs_acc: &mut Vec<ErrorEntry>) {
+    errs_acc.push(ErrorEntry::warning(
+        errors::UNSUPPORTED_SPICE_CARD, 
+        &card.position(), 
+        &vec![card.fields[0].text.clone()])); 
+}
+
+fn unsupported_option(card: &Card, option: &Field, errs_acc: &mut Vec<ErrorEntry>) {
+    errs_acc.push(ErrorEntry::warning(
+        errors::UNSUPPORTED_SPICE_OPTION, 
+        &option.position, 
+        &vec![option.text.clone(), card.fields[0].text.clone()])); 
+}
+
+fn unsupported_substrate(card: &Card, node: &Field, errs_acc: &mut Vec<ErrorEntry>) {
+    errs_acc.push(ErrorEntry::warning(
+        errors::UNSUPPORTED_SPICE_SUBSTRATE, 
+        &node.position, 
+        &vec![node.text.clone(), card.fields[0].text.clone()])); 
+}
+
+fn invalid(card: &Card, message: &str, errs_acc: &mut Vec<ErrorEntry>) {
+    errs_acc.push(ErrorEntry::new(
+        errors::INVALID_SPICE_CARD, 
+        &card.position(), 
+        &vec![card.fields[0].text.clone(), message.to_string()])); 
+}
+
+/// Splits fields into positional ones and `name=value` pairs, 
+/// skipping the `PARAMS:` marker
+fn split_assignments(fields: &[Field]) -> (Vec<Field>, Vec<(Field, Field)>) {
+    let mut positional: Vec<Field> = vec![]; 
+    let mut assignments: Vec<(Field, Field)> = vec![]; 
+    let mut i = 0; 
+    while i < fields.len() {
+        if fields[i].text.eq_ignore_ascii_case(PARAMS_MARKER) {
+            i += 1; 
+        }
+        else if i + 2 < fields.len() && fields[i + 1].text == "=" {
+            assignments.push((fields[i].clone(), fields[i + 2].clone())); 
+            i += 3; 
+        }
+        else {
+            positional.push(fields[i].clone()); 
+            i += 1; 
+        }
+    }
+
+    (positional, assignments)
+}
+
+/// Fields between parentheses starting at the index and the index after them
+fn get_group(fields: &[Field], start: usize) -> (Vec<Field>, usize) {
+    if fields.get(start).is_none_or(|x| x.text != "(") {
+        return (vec![], start); 
+    }
+
+    let end = fields.iter().skip(start).position(|x| x.text == ")")
+        .map(|x| x + start)
+        .unwrap_or(fields.len()); 
+    (fields[start + 1..end].to_vec(), end + 1)
+}
+
+fn get_texts(fields: &[Field]) -> Vec<String> {
+    fields.iter().map(|x| x.text.clone()).collect()
+}
+
+/// `SIN(VO VA FREQ [TD])` as the expression of time
+fn get_sine(args: &[String]) -> String {
+    let sine = |time: &str| {
+        let wave = format!("{} * sin(2 * 3.141592653589793 * {} * {})", wrap(&args[1]), wrap(&args[2]), time); 
+        match parse_number(&args[0]) == Option::Some(0.0) {
+            true => wave, 
+            false => format!("{} + {}", args[0], wave), 
+        }
+    }; 
+    match args.get(3) {
+        Option::Some(delay) => format!("time < {0} ? {1} : {2}", wrap(delay), args[0], sine(&format!("(time - {})", delay))), 
+        Option::None => sine("time"), 
+    }
+}
+
+fn write_comments(comments: &[String], indent: &str) -> String {
+    comments.iter().map(|x| format!("{}// {}\n", indent, x)).collect()
+}
+
+/// Global ground of SPICE
+fn is_ground(node: &str) -> bool {
+    node == "0" || node.eq_ignore_ascii_case("GND")
+}
+
+/// Ground inside elements is the ground pin, element pins can't be numbers
+fn get_node_name(node: &str, in_subcircuit: bool) -> String {
+    match is_ground(node) {
+        true if in_subcircuit => GROUND_PIN.to_string(), 
+        true => "0".to_string(), 
+        false if !in_subcircuit && node.chars().all(|x| x.is_ascii_digit()) => node.to_string(), 
+        false => sanitize(node), 
+    }
+}
+
+/// Makes the edesigner name: invalid chars become '_', keywords get a suffix
+fn sanitize(name: &str) -> String {
+    let mut result: String = name.chars()
+        .map(|x| if x.is_alphanumeric() || x == '_' { x } else { '_' })
+        .collect(); 
+    if result.starts_with(|x: char| x.is_ascii_digit()) {
+        result.insert(0, '_'); 
+    }
+
+    if is_keyword(&result) || is_builtin_variable(&result) {
+        result.push('_'); 
+    }
+
+    result
+}
+
+/// Combines two expressions, folding numbers
+fn combine(left: &str, operator: &str, right: &str) -> String {
+    match (parse_number(left), parse_number(right), operator) {
+        (Option::Some(_), Option::Some(1.0), _) => left.to_string(), 
+        (Option::Some(1.0), Option::Some(_), "*") => right.to_string(), 
+        (Option::Some(x), Option::Some(y), "*") => format_number(x * y), 
+        (Option::Some(x), Option::Some(y), "/") => format_number(x / y), 
+        (_, Option::Some(1.0), _) => left.to_string(), 
+        (Option::Some(1.0), _, "*") => right.to_string(), 
+        _ => format!("{} {} {}", wrap(left), operator, wrap(right)), 
+    }
+}
+
+/// Puts the compound expression in parentheses
+fn wrap(expression: &str) -> String {
+    match parse_number(expression).is_some() || !expression.contains(' ') {
+        true => expression.to_string(), 
+        false => format!("({})", expression), 
+    }
+}
+
+/// Value of the card: number with a suffix, `{expression}`, 
+/// `'expression'` or the parameter name
+fn translate_value(field: &Field, names: &Names, errs_acc: &mut Vec<ErrorEntry>) -> Option<String> {
+    let text = field.text.as_str(); 
+    if let Option::Some(value) = parse_number(text) {
+        return Option::Some(format_number(value)); 
+    }
+
+    let expression = match text.chars().next() {
+        Option::Some('{') => text.trim_start_matches('{').trim_end_matches('}'), 
+        Option::Some('\'') => text.trim_matches('\''), 
+        _ => text, 
+    }; 
+
+    match translate_expression(expression, names) {
+        Result::Ok(result) => Option::Some(result), 
+        Result::Err(message) => {
+            errs_acc.push(ErrorEntry::new(
+                errors::INVALID_SPICE_EXPRESSION, 
+                &field.position, 
+                &vec![text.to_string(), message])); 
+            Option::None
+        }, 
+    }
+}
+
+#[derive(PartialEq)]
+enum Kind {
+    Operand, 
+    Function, 
+    Open, 
+    Close, 
+    Comma, 
+    Operator, 
+}
+
+/// Translates the SPICE expression token by token: numbers lose suffixes, 
+/// parameters are renamed and functions get edesigner names
+fn translate_expression(text: &str, names: &Names) -> Result<String, String> {
+    let chars: Vec<char> = text.chars().collect(); 
+    let mut tokens: Vec<(String, Kind)> = vec![]; 
+    let mut i = 0; 
+    while i < chars.len() {
+        let ch = chars[i]; 
+        let start = i; 
+        if ch.is_whitespace() {
+            i += 1; 
+            continue; 
+        }
+
+        if ch.is_ascii_digit() || (ch == '.' && chars.get(i + 1).is_some_and(|x| x.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.' ||
+                ((chars[i] == '-' || chars[i] == '+') && (chars[i - 1] == 'e' || chars[i - 1] == 'E'))) {
+                i += 1; 
+            }
+
+            let number: String = chars[start..i].iter().collect(); 
+            let value = parse_number(&number).ok_or(format!("invalid number '{}'", number))?; 
+            tokens.push((format_number(value), Kind::Operand)); 
+        }
+        else if ch.is_alphabetic() || ch == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1; 
+            }
+
+            let name: String = chars[start..i].iter().collect(); 
+            let is_call = chars[i..].iter().find(|x| !x.is_whitespace()) == Option::Some(&'('); 
+            match is_call {
+                true => tokens.push((get_function_name(&name), Kind::Function)), 
+                false => {
+                    let text = names.get(&name.to_uppercase()).cloned().unwrap_or(sanitize(&name)); 
+                    tokens.push((text, Kind::Operand)); 
+                }, 
+            }
+        }
+        else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect(); 
+            let (symbol, kind) = match pair.as_str() {
+                "**" => ("^".to_string(), Kind::Operator), 
+                "==" | "!=" | "<=" | ">=" | "&&" | "||" => (pair.clone(), Kind::Operator), 
+                _ => match ch {
+                    '(' => (ch.to_string(), Kind::Open), 
+                    ')' => (ch.to_string(), Kind::Close), 
+                    ',' => (ch.to_string(), Kind::Comma), 
+                    '+' | '-' | '*' | '/' | '^' | '%' | '<' | '>' | '!' | '?' | ':' => (ch.to_string(), Kind::Operator), 
+                    _ => return Result::Err(format!("unexpected symbol '{}'", ch)), 
+                }, 
+            }; 
+
+            i += match kind {
+                Kind::Operator if pair == "**" || symbol == pair => 2, 
+                _ => 1, 
+            }; 
+            tokens.push((symbol, kind)); 
+        }
+    }
+
+    if tokens.is_empty() {
+        return Result::Err("empty expression".to_string()); 
+    }
+
+    let mut result = String::new(); 
+    let mut previous: Option<&Kind> = Option::None; 
+    let mut after_unary = false; 
+    for (text, kind) in &tokens {
+        let space = match previous {
+            Option::None | Option::Some(Kind::Open | Kind::Function) => false, 
+            _ => !after_unary && !matches!(kind, Kind::Close | Kind::Comma), 
+        }; 
+
+        if space {
+            result.push(' '); 
+        }
+
+        // '+', '-' and '!' are unary at the start and after operators
+        after_unary = *kind == Kind::Operator && matches!(text.as_str(), "+" | "-" | "!") &&
+            matches!(previous, Option::None | Option::Some(Kind::Operator | Kind::Open | Kind::Comma)); 
+        previous = Option::Some(kind); 
+        result += text; 
+    }
+
+    Result::Ok(result)
+}
+
+/// SPICE functions which have other names in edesigner
+fn get_function_name(name: &str) -> String {
+    let name = name.to_lowercase(); 
+    match name.as_str() {
+        "log" => "ln".to_string(), 
+        "pwr" => "pow".to_string(), 
+        _ => name, 
+    }
+}
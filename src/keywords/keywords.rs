@@ -1,32 +1,119 @@
 
 pub mod keyword {
-    pub const IMPORT: &'static str = "import"; 
-    pub const PACKAGE: &'static str = "package"; 
-    pub const CIRCLE: &'static str = "circuit"; 
-    pub const ELEMENT: &'static str = "element"; 
-    pub const FUNCTION: &'static str = "function"; 
+    pub const IMPORT: &str = "import"; 
+    pub const PACKAGE: &str = "package"; 
+    pub const CIRCLE: &str = "circuit"; 
+    pub const ELEMENT: &str = "element"; 
+    pub const FUNCTION: &str = "function"; 
+    pub const PRIMITIVE: &str = "primitive"; 
+}
+
+/// Variables available in every expression of an element entry 
+pub mod variable {
+    pub const TIME: &str = "time"; 
+    pub const FREQUENCY: &str = "freq"; 
+    pub const LAPLACE: &str = "s"; // complex frequency, j * 2 * pi * freq
+}
 
-    // pub const RESISTOR: &'static str = "Resistor";
-    // pub const CAPACITOR: &'static str = "Capacitor"; 
-    // pub const INDUCTOR: &'static str = "Inductor"; 
-    // pub const VOLTAGE_SOURCE: &'static str = "VoltageSource"; 
-    // pub const CURRENT_SOURCE: &'static str = "CurrentSource"; 
+/// Quantities of primitive instances available in expressions, 
+/// e.g. 'R1.U' 
+pub mod member {
+    pub const VOLTAGE: &str = "U"; 
+    pub const CURRENT: &str = "I"; 
+    pub const POWER: &str = "P"; 
 }
 
-const ALL_KEYWORDS: [&'static str; 5] = [
+pub mod primitive {
+    pub const RESISTOR: &str = "Resistor";
+    pub const CAPACITOR: &str = "Capacitor"; 
+    pub const INDUCTOR: &str = "Inductor"; 
+    pub const VOLTAGE_SOURCE: &str = "VoltageSource"; 
+    pub const CURRENT_SOURCE: &str = "CurrentSource"; 
+    pub const DIODE: &str = "Diode"; 
+}
+
+const ALL_KEYWORDS: [&str; 6] = [
     keyword::CIRCLE,
     keyword::ELEMENT,
     keyword::IMPORT,
     keyword::PACKAGE,
     keyword::FUNCTION,
-    // keyword::CURRENT_SOURCE,
-    // keyword::INDUCTOR,
-    // keyword::RESISTOR,
-    // keyword::VOLTAGE_SOURCE,
-    // keyword::CAPACITOR,
+    keyword::PRIMITIVE,
+]; 
+
+pub const ALL_VARIABLES: [&str; 3] = [
+    variable::TIME, 
+    variable::FREQUENCY, 
+    variable::LAPLACE, 
+]; 
+
+/// Scale suffixes of number literals (SPICE style, case-insensitive) 
+/// with decimal exponents, e.g. '4.7k' is 4700. Longer suffixes go first. 
+/// Units are case-sensitive and win over scales, so '1F' is one farad and 
+/// '1f' is 1e-15, while the SPICE importer reads both as femto. 
+pub const SCALE_SUFFIXES: [(&str, i32); 9] = [
+    ("meg", 6), 
+    ("t", 12), 
+    ("g", 9), 
+    ("k", 3), 
+    ("m", -3), 
+    ("u", -6), 
+    ("n", -9), 
+    ("p", -12), 
+    ("f", -15), 
+]; 
+
+/// Units allowed after the number and its scale suffix, e.g. '100nF' 
+pub const ALL_UNITS: [&str; 8] = [
+    "Ohm", 
+    "F", 
+    "H", 
+    "V", 
+    "A", 
+    "W", 
+    "s", 
+    "Hz", 
+]; 
+
+pub fn is_unit(word: &str) -> bool {
+    ALL_UNITS.contains(&word)
+}
+
+/// Pins and parameters of a model built into the simulator 
+pub struct PrimitiveSignature {
+    pub name: &'static str, 
+    pub nodes: &'static [&'static str], 
+    pub param_names: &'static [&'static str], 
+    pub param_units: &'static [&'static str], // expected units of parameters, empty for dimensionless
+}
+
+// Primitives aren't keywords: they are declared in the 'std' package 
+// and resolved as element names. The declarations must match these models. 
+const ALL_PRIMITIVES: [PrimitiveSignature; 6] = [
+    PrimitiveSignature { name: primitive::RESISTOR, nodes: &["P", "N"], param_names: &["R"], param_units: &["Ohm"] },
+    PrimitiveSignature { name: primitive::CAPACITOR, nodes: &["P", "N"], param_names: &["C"], param_units: &["F"] },
+    PrimitiveSignature { name: primitive::INDUCTOR, nodes: &["P", "N"], param_names: &["L"], param_units: &["H"] },
+    PrimitiveSignature { name: primitive::VOLTAGE_SOURCE, nodes: &["P", "N"], param_names: &["U"], param_units: &["V"] },
+    PrimitiveSignature { name: primitive::CURRENT_SOURCE, nodes: &["P", "N"], param_names: &["I"], param_units: &["A"] },
+    PrimitiveSignature { name: primitive::DIODE, nodes: &["A", "K"], param_names: &["IS", "N"], param_units: &["A", ""] },
 ]; 
 
 pub fn is_keyword(word: &str) -> bool {
     ALL_KEYWORDS.contains(&word)
 }
 
+pub fn is_builtin_variable(word: &str) -> bool {
+    ALL_VARIABLES.contains(&word)
+}
+
+pub fn is_quantity(member: &str) -> bool {
+    member == member::VOLTAGE || member == member::CURRENT || member == member::POWER
+}
+
+pub fn is_primitive(word: &str) -> bool {
+    get_primitive_signature(word).is_some()
+}
+
+pub fn get_primitive_signature(word: &str) -> Option<&'static PrimitiveSignature> {
+    ALL_PRIMITIVES.iter().find(|x| x.name == word)
+}
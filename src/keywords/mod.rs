@@ -1,4 +1,14 @@
 mod keywords; 
 
 pub use keywords::keyword; 
-pub use keywords::is_keyword; 
\ No newline at end of file
+pub use keywords::is_keyword;
+pub use keywords::primitive; 
+pub use keywords::is_primitive;
+pub use keywords::get_primitive_signature; 
+pub use keywords::variable; 
+pub use keywords::is_builtin_variable; 
+pub use keywords::ALL_VARIABLES; 
+pub use keywords::member; 
+pub use keywords::is_quantity; 
+pub use keywords::SCALE_SUFFIXES; 
+pub use keywords::is_unit; 
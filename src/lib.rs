@@ -0,0 +1,59 @@
+//! Circuit description language: the source is split into tokens, 
+//! statements and commands, which carry nodes of the typed AST with 
+//! their spans (see `ast`), analyzed, elaborated into a netlist and 
+//! simulated. `compile` runs the whole front end at once. 
+//! The stages and analyses are re-exported here, other modules except 
+//! `ast` and `diagnostics` are internal. 
+
+mod entities; 
+mod tokenizer; 
+mod tokens_recognizers; 
+mod interfaces; 
+mod statement_divider; 
+mod commands; 
+pub mod ast; 
+mod keywords; 
+mod semantic; 
+mod elaborator; 
+mod simulation; 
+mod packages; 
+mod export; 
+mod import; 
+pub mod diagnostics; 
+mod compiler; 
+
+// Stages of the front end 
+pub use tokenizer::tokenize; 
+pub use statement_divider::get_statements; 
+pub use commands::get_commands; 
+pub use packages::get_token_recognizers; 
+pub use packages::get_command_recognizers; 
+pub use packages::get_builtin_source; 
+pub use entities::Token; 
+pub use entities::Statement; 
+pub use commands::Command; 
+pub use entities::Expression; 
+pub use entities::Complex; 
+pub use diagnostics::Diagnostic; 
+pub use compiler::CompileOptions; 
+pub use compiler::Program; 
+pub use compiler::Design; 
+pub use compiler::check; 
+pub use compiler::compile; 
+
+// Elaboration and simulation 
+pub use elaborator::Netlist; 
+pub use elaborator::Device; 
+pub use elaborator::elaborate; 
+pub use simulation::Solution; 
+pub use simulation::operating_point; 
+pub use simulation::transient; 
+pub use simulation::TimeSweep; 
+pub use simulation::Method; 
+pub use simulation::ac_analysis; 
+pub use simulation::Sweep; 
+pub use simulation::SweepType; 
+
+// SPICE decks 
+pub use export::export_spice; 
+pub use import::import_spice; 
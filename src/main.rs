@@ -1,84 +1,321 @@
-mod entities;
-mod tokenizer; 
-mod tokens_recognizers;
-mod interfaces; 
-mod statement_divider; 
-mod commands; 
-mod keywords; 
-
-use commands::Command; 
-use entities::Statement; 
-use interfaces::TokenRecognizer;
-use interfaces::CommandRecognizer; 
-use tokens_recognizers::BlockCommentTokenRecognizer;
-use tokens_recognizers::LineCommentTokenRecognizer;
-use tokens_recognizers::NumberTokenRecognizer;
-use tokens_recognizers::SymbolTokenRecognizer;
-use tokens_recognizers::WordTokenRecognizer; 
-use tokenizer::tokenize; 
-use statement_divider::get_statements; 
-use commands::get_commands; 
-use entities::ErrorEntry; 
+use edesigner::diagnostics; 
+use edesigner::Command; 
+use edesigner::CompileOptions; 
+use edesigner::Diagnostic; 
+use edesigner::Statement; 
 use std::env;
 use std::fs;
+use std::process; 
+
+/// What to do with the parsed file 
+enum Mode {
+    Commands, // print recognized commands
+    Netlist, // print the elaborated circuit
+    OperatingPoint, // print the DC operating point
+    Transient(f64, f64), // print the transient analysis with step (the maximal one if adaptive) and stop time
+    Ac(edesigner::Sweep), // print the small-signal frequency response
+    Spice, // print the circuit as a SPICE deck
+    FromSpice, // convert the SPICE deck to the edesigner source
+}
+
+/// How diagnostics are printed 
+enum MessageFormat {
+    Human, // source snippets for the terminal
+    Json, // one JSON object per line
+    Sarif, // SARIF 2.1 log for code-scanning tools
+}
+
+struct Options {
+    compile: CompileOptions, 
+    mode: Mode, 
+    input: Option<String>, // the only source of the AC analysis
+    method: edesigner::Method, // integration of the transient analysis
+    adaptive: bool, // the transient step is chosen by the truncation error
+    message_format: MessageFormat, 
+}
+
+fn parse_number(arg: Option<&String>, option: &str) -> Result<f64, String> {
+    match arg.map(|x| x.parse::<f64>()) {
+        Option::Some(Result::Ok(value)) => Result::Ok(value), 
+        _ => Result::Err(format!("Expected number after '{}'. ", option)), 
+    }
+}
+
+fn parse_options(args: &Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        compile: CompileOptions::default(), 
+        mode: Mode::Commands, 
+        input: Option::None, 
+        method: edesigner::Method::Trapezoidal, 
+        adaptive: false, 
+        message_format: MessageFormat::Human, 
+    }; 
+
+    let mut iter = args.iter().skip(1); 
+    while let Option::Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--netlist" => options.mode = Mode::Netlist, 
+            "--op" => options.mode = Mode::OperatingPoint, 
+            "--spice" => options.mode = Mode::Spice, 
+            "--from-spice" => options.mode = Mode::FromSpice, 
+            "--tran" => {
+                let step = parse_number(iter.next(), "--tran")?; 
+                let stop = parse_number(iter.next(), "--tran")?; 
+                if step <= 0.0 || stop < step {
+                    return Result::Err("Time step should be positive and not greater than stop time. ".to_string()); 
+                }
+
+                options.mode = Mode::Transient(step, stop); 
+            },
+            "--ac" => {
+                let sweep_type = match iter.next().map(|x| x.as_str()) {
+                    Option::Some("lin") => edesigner::SweepType::Linear, 
+                    Option::Some("dec") => edesigner::SweepType::Decade, 
+                    _ => return Result::Err("Expected 'lin' or 'dec' after '--ac'. ".to_string()), 
+                }; 
+
+                let points = parse_number(iter.next(), "--ac")?; 
+                let start = parse_number(iter.next(), "--ac")?; 
+                let stop = parse_number(iter.next(), "--ac")?; 
+                if points < 1.0 || points.fract() != 0.0 || start <= 0.0 || stop < start {
+                    return Result::Err("Expected positive amount of points and frequencies 0 < start <= stop. ".to_string()); 
+                }
+
+                options.mode = Mode::Ac(edesigner::Sweep { sweep_type, points: points as usize, start, stop }); 
+            },
+            "--method" => {
+                options.method = match iter.next().map(|x| x.as_str()) {
+                    Option::Some("euler") => edesigner::Method::BackwardEuler, 
+                    Option::Some("trap") => edesigner::Method::Trapezoidal, 
+                    _ => return Result::Err("Expected 'euler' or 'trap' after '--method'. ".to_string()), 
+                }; 
+            },
+            "--adaptive" => options.adaptive = true, 
+            "--input" => {
+                let name = iter.next().ok_or("Missing source name after '--input'. ")?; 
+                options.input = Option::Some(name.clone()); 
+            },
+            "--circuit" => {
+                let name = iter.next().ok_or("Missing circuit name after '--circuit'. ")?; 
+                options.compile.circuit_name = Option::Some(name.clone()); 
+            },
+            "--message-format" => {
+                options.message_format = match iter.next().map(|x| x.as_str()) {
+                    Option::Some("human") => MessageFormat::Human, 
+                    Option::Some("json") => MessageFormat::Json, 
+                    Option::Some("sarif") => MessageFormat::Sarif, 
+                    _ => return Result::Err("Expected 'human', 'json' or 'sarif' after '--message-format'. ".to_string()), 
+                }; 
+            },
+            "-I" => {
+                let dir = iter.next().ok_or("Missing directory after '-I'. ")?; 
+                options.compile.include_dirs.push(dir.clone()); 
+            },
+            "-p" => {
+                let param = iter.next().ok_or("Missing 'name=value' after '-p'. ")?; 
+                let (name, value) = param.split_once('=')
+                    .ok_or(format!("Invalid parameter '{}', expected 'name=value'. ", param))?; 
+                let value = value.parse::<f64>()
+                    .map_err(|_| format!("Invalid value of parameter '{}'. ", name))?; 
+                options.compile.params.insert(name.to_string(), value); 
+            },
+            _ if arg.starts_with('-') => return Result::Err(format!("Unknown option '{}'. ", arg)), 
+            _ => options.compile.file_name = arg.clone(), 
+        }
+    }
+
+    if options.compile.file_name.is_empty() {
+        return Result::Err("Please write a file name. ".to_string()); 
+    }
+
+    Result::Ok(options)
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect(); 
-    if args.len() < 2 {
-        println!("Please write a file name. "); 
-        return; 
-    }
+    let options = match parse_options(&args) {
+        Result::Ok(options) => options, 
+        Result::Err(message) => {
+            eprintln!("{}", message); 
+            process::exit(2); 
+        }
+    }; 
 
-    let file_name: &String = &args[1].clone(); 
-    let code: String = match fs::read_to_string(file_name) {
+    let code: String = match fs::read_to_string(&options.compile.file_name) {
         Result::Ok(text) => text,
         Result::Err(_) => {
-            println!("Error reading file. "); 
-            return; 
+            eprintln!("Error reading file. "); 
+            process::exit(2); 
         }
     };
 
-    let token_recognizers: Vec<&dyn TokenRecognizer> = vec!(
-        &LineCommentTokenRecognizer{},
-        &BlockCommentTokenRecognizer{},
-        &SymbolTokenRecognizer{},
-        &WordTokenRecognizer{},
-        &NumberTokenRecognizer{},
-    ); 
-
-    let mut errors: Vec<ErrorEntry> = vec![]; 
-    let tokens = tokenize(code, &token_recognizers, &mut errors); 
-    let stats = get_statements(&tokens, &mut errors); 
+    let mut errors: Vec<Diagnostic> = vec![]; 
+    run(&options, &code, &mut errors); 
+    print_errors(&errors, &options, &code); 
+    if diagnostics::has_errors(&errors) {
+        process::exit(1); 
+    }
+}
 
-    for error in &errors {
-        error.console_print(); 
+/// Runs the mode of the options, diagnostics of all passes are 
+/// collected to `errs_acc` and printed by the caller 
+fn run(options: &Options, code: &String, errs_acc: &mut Vec<Diagnostic>) {
+    if let Mode::FromSpice = options.mode {
+        print!("{}", edesigner::import_spice(code, errs_acc)); 
+        return; 
     }
 
-    if errors.len() > 0 {
+    if let Mode::Commands = options.mode {
+        match edesigner::check(code, &options.compile) {
+            Result::Ok(program) => {
+                print_commands(&program.files[0].commands); 
+                errs_acc.extend(program.warnings); 
+            },
+            Result::Err(diagnostics) => errs_acc.extend(diagnostics), 
+        }
+
         return; 
     }
 
-    //print_statements(&stats);
+    let design = match edesigner::compile(code, &options.compile) {
+        Result::Ok(design) => design, 
+        Result::Err(diagnostics) => {
+            errs_acc.extend(diagnostics); 
+            return; 
+        },
+    }; 
 
-    let command_recognizers: Vec<&dyn CommandRecognizer> = vec![
-        &commands::CircleCommandRecognizer{},
-        &commands::ElementCommandRecognizer{},
-        &commands::PackageCommandRecognizer{},
-        &commands::ImportCommandRecognizer{}, 
-        &commands::FunctionCommandRecognizer{}, 
-    ]; 
+    errs_acc.extend(design.program.warnings.iter().cloned()); 
+    let netlist = &design.netlist; 
+    match &options.mode {
+        Mode::Netlist => print_netlist(netlist), 
+        Mode::OperatingPoint => {
+            if let Option::Some(solution) = edesigner::operating_point(netlist, errs_acc) {
+                print_operating_point(netlist, &solution); 
+            }
+        },
+        Mode::Transient(step, stop) => {
+            let sweep = edesigner::TimeSweep { step: *step, stop: *stop, method: options.method, adaptive: options.adaptive }; 
+            if let Option::Some(points) = edesigner::transient(netlist, &sweep, errs_acc) {
+                print_transient(&points); 
+            }
+        },
+        Mode::Ac(sweep) => {
+            if let Option::Some(points) = edesigner::ac_analysis(netlist, sweep, options.input.as_deref(), errs_acc) {
+                print_ac(&points); 
+            }
+        },
+        Mode::Spice => {
+            let deck = edesigner::export_spice(netlist, &design.program.table, &design.circuit, errs_acc); 
+            if !diagnostics::has_errors(errs_acc) {
+                print!("{}", deck); 
+            }
+        },
+        Mode::Commands | Mode::FromSpice => { }, 
+    }
+}
 
-    let mut errors: Vec<ErrorEntry> = vec![]; 
-    let commands = get_commands(&stats, &command_recognizers, &mut errors);
+/// Diagnostics go to stderr, so they don't mix with the output of the 
+/// mode (e.g. the SPICE deck). Diagnostics without a file name come from 
+/// the main file. The source of other files is read again to show the snippet. 
+fn print_errors(errors: &Vec<Diagnostic>, options: &Options, code: &str) {
+    let file_name = options.compile.file_name.as_str(); 
+    let errors: Vec<Diagnostic> = errors.iter().map(|x| x.clone().in_file(file_name)).collect(); 
+    match options.message_format {
+        MessageFormat::Human => {
+            for error in &errors {
+                let source = match error.file_name.as_str() {
+                    x if x == file_name => Option::Some(code.to_string()), 
+                    x => match edesigner::get_builtin_source(x) {
+                        Option::Some(source) => Option::Some(source.to_string()), 
+                        Option::None => fs::read_to_string(x).ok(), 
+                    },
+                }; 
 
-    for error in &errors {
-        error.console_print(); 
+                eprintln!("{}", diagnostics::render(error, source.as_deref())); 
+            }
+        },
+        MessageFormat::Json => {
+            for error in &errors {
+                eprintln!("{}", diagnostics::to_json(error)); 
+            }
+        },
+        MessageFormat::Sarif => eprintln!("{}", diagnostics::to_sarif(&errors)), 
     }
+}
 
-    if errors.len() > 0 {
-        return; 
+/// Prints one row per time point: time, node voltages and branch currents 
+fn print_transient(points: &Vec<(f64, edesigner::Solution)>) {
+    let (_, first) = &points[0]; 
+    let mut header: Vec<String> = vec![format!("time")]; 
+    header.extend(first.node_voltages.iter().map(|x| format!("V({})", x.0))); 
+    header.extend(first.branch_currents.iter().map(|x| format!("I({})", x.0))); 
+    println!("{}", header.iter().map(|x| format!("{: <14}", x)).collect::<String>().trim_end()); 
+
+    for (time, solution) in points {
+        let mut row: Vec<f64> = vec![*time]; 
+        row.extend(solution.node_voltages.iter().map(|x| x.1)); 
+        row.extend(solution.branch_currents.iter().map(|x| x.1)); 
+        println!("{}", row.iter().map(|x| format!("{: <14.6e}", x + 0.0)).collect::<String>().trim_end()); 
+    }
+}
+
+/// Prints one row per frequency: magnitude (dB) and phase (degrees) 
+/// of every node voltage 
+fn print_ac(points: &Vec<(f64, edesigner::Solution<edesigner::Complex>)>) {
+    let (_, first) = &points[0]; 
+    let mut header: Vec<String> = vec![format!("freq")]; 
+    for (node, _) in &first.node_voltages {
+        header.push(format!("dB(V({}))", node)); 
+        header.push(format!("ph(V({}))", node)); 
+    }
+
+    println!("{}", header.iter().map(|x| format!("{: <14}", x)).collect::<String>().trim_end()); 
+
+    for (freq, solution) in points {
+        let mut row: Vec<f64> = vec![*freq]; 
+        for (_, voltage) in &solution.node_voltages {
+            row.push(20.0 * voltage.abs().log10()); 
+            row.push(voltage.arg().to_degrees()); 
+        }
+
+        println!("{}", row.iter().map(|x| format!("{: <14.6e}", x + 0.0)).collect::<String>().trim_end()); 
+    }
+}
+
+fn print_operating_point(netlist: &edesigner::Netlist, solution: &edesigner::Solution) {
+    println!("Operating point of {}", netlist.circuit_name); 
+    println!("  Node voltages: "); 
+    for (node, voltage) in &solution.node_voltages {
+        println!("    {0: <16} {1}", format!("V({})", node), voltage + 0.0); 
     }
 
+    if !solution.branch_currents.is_empty() {
+        println!("  Branch currents: "); 
+        for (branch, current) in &solution.branch_currents {
+            println!("    {0: <16} {1}", format!("I({})", branch), current + 0.0); 
+        }
+    }
+}
+
+fn print_netlist(netlist: &edesigner::Netlist) {
+    println!("Circuit {}", netlist.circuit_name); 
+    for device in &netlist.devices {
+        // Parameters of controlled devices are known only during the simulation 
+        let params: Vec<String> = device.params.iter()
+            .zip(&device.param_expressions)
+            .map(|(x, expression)| match x.is_nan() {
+                true => expression.expression.to_string(), 
+                false => x.to_string(), 
+            })
+            .collect(); 
+
+        println!("  {0: <12} {1: <14} [{2}] ({3})", 
+            device.name, device.element_name, device.nodes.join(", "), params.join(", ")); 
+    }
+}
+
+fn print_commands(commands: &Vec<Command>) {
     for command in commands {
         match command {
             Command::Package(c) => { 
@@ -90,19 +327,19 @@ fn main() {
             Command::Circle(c) => {
                 println!("Circle"); 
                 println!("  Name: {}", c.name);
-                if c.param_names.len() > 0 {
+                if c.params.len() > 0 {
                     println!("  Parameters: "); 
-                    for param in &c.param_names {
-                        println!("    {}", param); 
+                    for param in &c.params {
+                        println!("    {}", param.name); 
                     }
                 } 
                 else {
                     println!("  No parameters"); 
                 }
 
-                if c.element_entries.len() > 0 {
+                if c.instances.len() > 0 {
                     println!("  Element entries: "); 
-                    for elem in &c.element_entries {
+                    for elem in &c.instances {
                         println!("    {}", elem.element_name); 
                         
                         println!("      Name: {}", elem.entry_name); 
@@ -117,7 +354,7 @@ fn main() {
 
                         println!("      Nodes: "); 
                         for node in &elem.nodes {
-                            println!("        {}", node); 
+                            println!("        {}", node.name); 
                         }
                     }
                 } 
@@ -128,10 +365,10 @@ fn main() {
             Command::Element(c) => {
                 println!("Element"); 
                 println!("  Name: {}", c.name);
-                if c.param_names.len() > 0 {
+                if c.params.len() > 0 {
                     println!("  Parameters: "); 
-                    for param in &c.param_names {
-                        println!("    {}", param); 
+                    for param in &c.params {
+                        println!("    {}", param.name); 
                     }
                 } 
                 else {
@@ -141,16 +378,16 @@ fn main() {
                 if c.nodes.len() > 0 {
                     println!("  Nodes: "); 
                     for node in &c.nodes {
-                        println!("    {}", node); 
+                        println!("    {}", node.name); 
                     }
                 } 
                 else {
                     println!("  No nodes"); 
                 }
 
-                if c.element_entries.len() > 0 {
+                if c.instances.len() > 0 {
                     println!("  Element entries: "); 
-                    for elem in &c.element_entries {
+                    for elem in &c.instances {
                         println!("    {}", elem.element_name); 
                         
                         println!("      Name: {}", elem.entry_name); 
@@ -165,7 +402,7 @@ fn main() {
 
                         println!("      Nodes: "); 
                         for node in &elem.nodes {
-                            println!("        {}", node); 
+                            println!("        {}", node.name); 
                         }
                     }
                 } 
@@ -176,10 +413,10 @@ fn main() {
             Command::Function(c) => {
                 println!("Function"); 
                 println!("  Name: {}", c.name);
-                if c.param_names.len() > 0 {
+                if c.params.len() > 0 {
                     println!("  Parameters: "); 
-                    for param in &c.param_names {
-                        println!("    {}", param); 
+                    for param in &c.params {
+                        println!("    {}", param.name); 
                     }
                 } 
 
@@ -0,0 +1,125 @@
+/// Package which is always loaded: it declares primitives, which are 
+/// available without import, and helper functions, e.g. 'std.F9'. 
+pub const STD_PACKAGE: &str = "std"; 
+
+/// File name of the built-in package in diagnostics 
+pub const STD_FILE_NAME: &str = "<std>"; 
+
+const STD_SOURCE: &str = include_str!("std.ed"); 
+
+/// Returns file name and code of the package built into the binary 
+pub fn get_builtin_package(package_name: &str) -> Option<(&'static str, &'static str)> {
+    match package_name {
+        STD_PACKAGE => Option::Some((STD_FILE_NAME, STD_SOURCE)), 
+        _ => Option::None, 
+    }
+}
+
+/// Code of the built-in package by its file name in diagnostics 
+pub fn get_builtin_source(file_name: &str) -> Option<&'static str> {
+    match file_name {
+        STD_FILE_NAME => Option::Some(STD_SOURCE), 
+        _ => Option::None, 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap; 
+    use crate::commands::Command; 
+    use crate::commands::get_function_environment; 
+    use crate::entities::ErrorEntry; 
+    use crate::entities::FunctionEnvironment; 
+    use crate::entities::get_expression; 
+    use crate::keywords::get_primitive_signature; 
+    use crate::packages::SourceFile; 
+    use crate::packages::get_token_recognizers; 
+    use crate::packages::parse_source; 
+    use crate::semantic::analyze; 
+    use crate::tokenizer::tokenize; 
+    use super::*; 
+
+    fn get_std_file() -> SourceFile {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source(STD_SOURCE.to_string(), 1, &mut errs); 
+        assert!(errs.is_empty()); 
+        SourceFile {
+            file_id: 1, 
+            file_name: STD_FILE_NAME.to_string(), 
+            package_name: STD_PACKAGE.to_string(), 
+            commands, 
+        }
+    }
+
+    fn evaluate(functions: &FunctionEnvironment, code: &str) -> f64 {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let tokens = tokenize(code.to_string(), &get_token_recognizers(), &mut errs); 
+        let expression = get_expression(&tokens, &mut errs).unwrap(); 
+        match expression.evaluate(&HashMap::new(), functions) {
+            Result::Ok(value) => value, 
+            Result::Err(err) => panic!("'{}' failed: {}", code, err.get_message()), 
+        }
+    }
+
+    #[test]
+    fn std_is_found_by_package_and_file_name() {
+        let (file_name, code) = get_builtin_package(STD_PACKAGE).unwrap(); 
+        assert_eq!(file_name, STD_FILE_NAME); 
+        assert!(code.starts_with("package std;")); 
+        assert_eq!(get_builtin_source(STD_FILE_NAME), Option::Some(code)); 
+
+        assert!(get_builtin_package("main").is_none()); 
+        assert!(get_builtin_source("std.ed").is_none()); 
+    }
+
+    #[test]
+    fn std_has_no_diagnostics() {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        analyze(&vec![get_std_file()], &mut errs); 
+        let messages: Vec<String> = errs.iter().map(|x| x.get_message()).collect(); 
+        assert_eq!(messages, Vec::<String>::new()); 
+    }
+
+    #[test]
+    fn std_declares_every_model() {
+        let names: Vec<String> = get_std_file().commands.iter()
+            .filter_map(|x| match x {
+                Command::Primitive(c) => Option::Some(c.name.clone()), 
+                _ => Option::None, 
+            })
+            .collect(); 
+
+        assert_eq!(names, ["Resistor", "Capacitor", "Inductor", "VoltageSource", "CurrentSource", "Diode"]); 
+        assert!(names.iter().all(|x| get_primitive_signature(x).is_some())); 
+    }
+
+    #[test]
+    fn std_functions_are_evaluated() {
+        let functions = get_function_environment(&get_std_file().commands, STD_FILE_NAME); 
+        assert!((evaluate(&functions, "PI()") - std::f64::consts::PI).abs() < 1e-15); 
+
+        // kT/q at 300 K 
+        let vt = evaluate(&functions, "VT()"); 
+        assert!((vt - 0.025852).abs() < 1e-6); 
+        assert_eq!(evaluate(&functions, "VTT(600)"), 2.0 * vt); 
+
+        assert_eq!(evaluate(&functions, "junction(1e-14, 1, 0)"), 0.0); 
+        let current = evaluate(&functions, "junction(1e-14, 2, 0.6)"); 
+        assert!((current - 1e-14 * ((0.6 / (2.0 * vt)).exp() - 1.0)).abs() < 1e-18); 
+    }
+
+    #[test]
+    fn junction_current_is_continued_by_tangent() {
+        let functions = get_function_environment(&get_std_file().commands, STD_FILE_NAME); 
+
+        // Below UMAX it's the exponential with the leakage 
+        let below = evaluate(&functions, "F9(1e-14, 40, 0.8, 0, 0, 1e-9, 0.5, 0.5)"); 
+        assert!((below - (1e-14 * ((40.0f64 * 0.5).exp() - 1.0) + 1e-9 * 0.5)).abs() < 1e-15); 
+
+        // Above UMAX the current grows linearly with the slope at UMAX 
+        let at_max = evaluate(&functions, "F9(1e-14, 40, 0.8, 0, 0, 0, 0.5, 0.8)"); 
+        let above = evaluate(&functions, "F9(1e-14, 40, 0.8, 0, 0, 0, 0.5, 1.8)"); 
+        let slope = 1e-14 * 40.0 * (40.0f64 * 0.8).exp(); 
+        assert!(((above - at_max) / slope - 1.0).abs() < 1e-9); 
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::HashMap; 
+use std::collections::HashSet; 
+use std::fs; 
+use std::path::PathBuf; 
+use crate::commands::Command; 
+use crate::ast::Instance; 
+use crate::entities::ErrorEntry; 
+use crate::entities::Position; 
+use crate::entities::apply_file_name; 
+use crate::entities::errors; 
+use crate::packages::PackageResolver; 
+use crate::packages::SourceFile; 
+use crate::packages::parse_source; 
+use crate::packages::STD_PACKAGE; 
+use crate::packages::get_builtin_package; 
+
+/// Loads the main file and all packages it imports, directly or through
+/// other packages. Every package is parsed once, declarations of imported
+/// packages are qualified with the package name (e.g. 'std.F9'). The main
+/// file is the first one.
+pub fn load_program(main_file_name: &str, 
+                    code: String, 
+                    resolver: &PackageResolver, 
+                    errs_acc: &mut Vec<ErrorEntry>) -> Vec<SourceFile> {
+
+    let mut errs: Vec<ErrorEntry> = vec![]; 
+    let main_file = SourceFile {
+        file_id: 0, 
+        file_name: main_file_name.to_string(), 
+        package_name: String::new(), 
+        commands: parse_source(code, 0, &mut errs), 
+    }; 
+
+    errs_acc.extend(apply_file_name(&errs, &main_file.file_name)); 
+
+    let mut loader = Loader {
+        resolver, 
+        files: vec![], 
+        loaded: HashSet::new(), 
+        next_file_id: 1, 
+    }; 
+
+    // The chain starts with the main package, so importing it back is a cycle
+    let mut chain: Vec<String> = vec![main_file.get_declared_package().unwrap_or(main_file.file_name.clone())]; 
+
+    // Primitives of 'std' are available without import 
+    let position = Position { line: 0, line_position: 0 }; 
+    loader.load_package(&STD_PACKAGE.to_string(), &position, &main_file.file_name, &mut chain, errs_acc); 
+    loader.load_imports(&main_file, &mut chain, errs_acc); 
+    loader.files.insert(0, main_file); 
+
+    for file in &loader.files {
+        check_qualified_names(file, &loader.loaded, errs_acc); 
+    }
+
+    loader.files
+}
+
+struct Loader<'a> {
+    resolver: &'a PackageResolver, 
+    files: Vec<SourceFile>, 
+    loaded: HashSet<String>, // names of packages which are already parsed
+    next_file_id: usize, 
+}
+
+impl<'a> Loader<'a> {
+    /// Loads packages imported by the file, `chain` contains 
+    /// packages which are being loaded 
+    fn load_imports(&mut self, file: &SourceFile, chain: &mut Vec<String>, errs_acc: &mut Vec<ErrorEntry>) {
+        for command in &file.commands {
+            if let Command::Import(c) = command {
+                if !c.package_name.is_empty() {
+                    self.load_package(&c.package_name, &c.name_span.position, &file.file_name, chain, errs_acc); 
+                }
+            }
+        }
+    }
+
+    /// Loads the package imported at `position` of the file `importer` 
+    fn load_package(&mut self, 
+                    name: &String, 
+                    position: &Position, 
+                    importer: &str, 
+                    chain: &mut Vec<String>, 
+                    errs_acc: &mut Vec<ErrorEntry>) {
+
+        if let Option::Some(start) = chain.iter().position(|x| x == name) {
+            let mut cycle: Vec<String> = chain[start..].to_vec(); 
+            cycle.push(name.clone()); 
+            errs_acc.push(ErrorEntry::new(
+                errors::IMPORT_CYCLE, 
+                position, 
+                &vec![cycle.join(" -> ")]).in_file(importer)); 
+            return; 
+        }
+
+        if self.loaded.contains(name) {
+            return; 
+        }
+
+        // Built-in packages can't be replaced by files 
+        let sources: Vec<(String, String)> = match get_builtin_package(name) {
+            Option::Some((file_name, code)) => vec![(file_name.to_string(), code.to_string())], 
+            Option::None => match self.resolver.find(name) {
+                Result::Ok(paths) => read_files(&paths, position, importer, errs_acc), 
+                Result::Err(searched) => {
+                    let dirs: Vec<String> = searched.iter().map(|x| x.display().to_string()).collect(); 
+                    errs_acc.push(ErrorEntry::new(
+                        errors::PACKAGE_NOT_FOUND, 
+                        position, 
+                        &vec![name.clone(), dirs.join(", ")]).in_file(importer)); 
+                    return; 
+                }
+            },
+        }; 
+
+        self.loaded.insert(name.clone()); 
+        chain.push(name.clone()); 
+        for (file_name, code) in sources {
+            let mut errs: Vec<ErrorEntry> = vec![]; 
+            let mut package = SourceFile {
+                file_id: self.next_file_id, 
+                file_name: file_name.clone(), 
+                package_name: name.clone(), 
+                commands: parse_source(code, self.next_file_id, &mut errs), 
+            }; 
+
+            self.next_file_id += 1; 
+
+            check_package_name(&package, &mut errs); 
+            errs_acc.extend(apply_file_name(&errs, &file_name)); 
+
+            qualify_names(&mut package); 
+            self.load_imports(&package, chain, errs_acc); 
+            self.files.push(package); 
+        }
+
+        chain.pop(); 
+    }
+}
+
+/// Reads files of the package, returns their names and code 
+fn read_files(paths: &Vec<PathBuf>, position: &Position, importer: &str, errs_acc: &mut Vec<ErrorEntry>) -> Vec<(String, String)> {
+    let mut sources: Vec<(String, String)> = vec![]; 
+    for path in paths {
+        let file_name = path.display().to_string(); 
+        match fs::read_to_string(path) {
+            Result::Ok(code) => sources.push((file_name, code)), 
+            Result::Err(_) => errs_acc.push(ErrorEntry::new(
+                errors::FILE_READ_ERROR, 
+                position, 
+                &vec![file_name]).in_file(importer)), 
+        }
+    }
+
+    sources
+}
+
+/// `package` command of the imported file should match the imported name
+fn check_package_name(file: &SourceFile, errs_acc: &mut Vec<ErrorEntry>) {
+    for command in &file.commands {
+        if let Command::Package(c) = command {
+            if !c.name.is_empty() && c.name != file.package_name {
+                errs_acc.push(ErrorEntry::new(
+                    errors::PACKAGE_NAME_MISMATCH, 
+                    &c.span.position, 
+                    &vec![c.name.clone(), file.package_name.clone()])); 
+            }
+        }
+    }
+}
+
+/// Prefixes elements and functions of the package with its name and
+/// updates references to them. Primitives keep their names, circuits
+/// are not exported, so they are removed.
+fn qualify_names(file: &mut SourceFile) {
+    let qualify = |name: &String| format!("{}.{}", file.package_name, name); 
+    let mut elements: HashMap<String, String> = HashMap::new(); 
+    let mut functions: HashMap<String, String> = HashMap::new(); 
+    for command in &file.commands {
+        match command {
+            Command::Element(c) => { elements.insert(c.name.clone(), qualify(&c.name)); }, 
+            Command::Function(c) => { functions.insert(c.name.clone(), qualify(&c.name)); }, 
+            _ => { }, 
+        }
+    }
+
+    let rename_entry = |entry: &mut Instance| {
+        if let Option::Some(name) = elements.get(&entry.element_name) {
+            entry.element_name = name.clone(); 
+        }
+
+        for expression in &mut entry.param_expressions {
+            expression.rename_calls(&functions); 
+        }
+    }; 
+
+    file.commands.retain(|x| !matches!(x, Command::Circle(_))); 
+    for command in &mut file.commands {
+        match command {
+            Command::Element(c) => {
+                c.name = elements[&c.name].clone(); 
+                c.instances.iter_mut().for_each(&rename_entry); 
+            }, 
+            Command::Function(c) => {
+                c.name = functions[&c.name].clone(); 
+                c.expression.rename_calls(&functions); 
+            }, 
+            _ => { }, 
+        }
+    }
+}
+
+/// Qualified names can refer only to the packages the file imports
+/// (or to its own package)
+fn check_qualified_names(file: &SourceFile, loaded: &HashSet<String>, errs_acc: &mut Vec<ErrorEntry>) {
+    let imports = file.get_imports(); 
+    let mut names: Vec<(String, Position)> = vec![]; 
+    for command in &file.commands {
+        let entries: &Vec<Instance> = match command {
+            Command::Element(c) => &c.instances, 
+            Command::Circle(c) => &c.instances, 
+            Command::Function(c) => {
+                names.extend(c.expression.get_calls().into_iter().map(|(name, _, position)| (name, position))); 
+                continue; 
+            }, 
+            _ => continue, 
+        }; 
+
+        for entry in entries {
+            names.push((entry.element_name.clone(), entry.span.position.clone())); 
+            for expression in &entry.param_expressions {
+                names.extend(expression.get_calls().into_iter().map(|(name, _, position)| (name, position))); 
+            }
+        }
+    }
+
+    for (name, position) in names {
+        // The longest loaded package which the name belongs to
+        let package = loaded.iter()
+            .filter(|x| name.starts_with(&format!("{}.", x)))
+            .max_by_key(|x| x.len()); 
+
+        if let Option::Some(package) = package {
+            if *package != file.package_name && !imports.contains(package) {
+                errs_acc.push(ErrorEntry::new(
+                    errors::PACKAGE_NOT_IMPORTED, 
+                    &position, 
+                    &vec![name.clone(), package.clone()]).in_file(&file.file_name)); 
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs; 
+    use std::path::PathBuf; 
+    use crate::commands::Command; 
+    use crate::entities::ErrorEntry; 
+    use crate::packages::PackageResolver; 
+    use crate::packages::SourceFile; 
+    use super::load_program; 
+
+    // Writes package files into a fresh directory and loads the main code with it 
+    fn load(test_name: &str, packages: &[(&str, &str)], code: &str) -> (Vec<SourceFile>, Vec<ErrorEntry>) {
+        let dir = std::env::temp_dir().join(format!("edesigner-loader-{}-{}", std::process::id(), test_name)); 
+        let _ = fs::remove_dir_all(&dir); 
+        fs::create_dir_all(&dir).unwrap(); 
+        for (file_name, package_code) in packages {
+            let path = dir.join(file_name); 
+            fs::create_dir_all(path.parent().unwrap()).unwrap(); 
+            fs::write(&path, package_code).unwrap(); 
+        }
+
+        let resolver = PackageResolver { search_path: vec![PathBuf::from(&dir)] }; 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let files = load_program("main.txt", code.to_string(), &resolver, &mut errs); 
+        fs::remove_dir_all(&dir).unwrap(); 
+        (files, errs)
+    }
+
+    fn get_codes(errs: &Vec<ErrorEntry>) -> Vec<&str> {
+        errs.iter().map(|x| x.error.code).collect()
+    }
+
+    fn get_file<'a>(files: &'a Vec<SourceFile>, package_name: &str) -> &'a SourceFile {
+        files.iter().find(|x| x.package_name == package_name).unwrap()
+    }
+
+    #[test]
+    fn import_cycle_of_packages() {
+        let packages = [
+            ("a.ed", "package a; import b;"), 
+            ("b.ed", "package b; import a;"), 
+        ]; 
+
+        let (files, errs) = load("cycle", &packages, "package main; import a;"); 
+        assert_eq!(get_codes(&errs), vec!["PKG002"]); 
+        assert_eq!(errs[0].get_message(), "Import cycle: a -> b -> a."); 
+        assert!(errs[0].file_name.ends_with("b.ed")); 
+        assert_eq!(errs[0].position.as_ref().unwrap().line, 0); 
+
+        // Both packages are still loaded once 
+        assert_eq!(files.iter().filter(|x| x.package_name == "a").count(), 1); 
+        assert_eq!(files.iter().filter(|x| x.package_name == "b").count(), 1); 
+    }
+
+    #[test]
+    fn import_of_main_package_is_cycle() {
+        let packages = [("a.ed", "package a; import main;")]; 
+        let (_, errs) = load("main_cycle", &packages, "package main; import a;"); 
+        assert_eq!(get_codes(&errs), vec!["PKG002"]); 
+        assert_eq!(errs[0].get_message(), "Import cycle: main -> a -> main."); 
+    }
+
+    #[test]
+    fn self_import_is_cycle() {
+        let packages = [("a.ed", "package a; import a;")]; 
+        let (_, errs) = load("self_cycle", &packages, "package main; import a;"); 
+        assert_eq!(get_codes(&errs), vec!["PKG002"]); 
+        assert_eq!(errs[0].get_message(), "Import cycle: a -> a."); 
+    }
+
+    #[test]
+    fn shared_import_is_not_cycle() {
+        // Diamond: both 'a' and 'b' import 'c' 
+        let packages = [
+            ("a.ed", "package a; import c;"), 
+            ("b.ed", "package b; import c;"), 
+            ("c.ed", "package c; function F(x) => x;"), 
+        ]; 
+
+        let (files, errs) = load("diamond", &packages, "package main; import a; import b;"); 
+        assert!(errs.is_empty()); 
+        assert_eq!(files.iter().filter(|x| x.package_name == "c").count(), 1); 
+    }
+
+    #[test]
+    fn names_of_package_are_qualified() {
+        let code = "package a; 
+            function G(x) => 2 * x; 
+            function H(x) => G(x) + 1; 
+            element [P, N] Load(R)
+            {
+                R1[P, N] = Resistor(G(R)); 
+                K1[P, N] = Part(R); 
+            }
+            element [P, N] Part(R)
+            {
+                R1[P, N] = Resistor(R); 
+            }
+            circuit Test()
+            {
+                K1[1, 0] = Load(1); 
+            }"; 
+
+        let (files, errs) = load("qualify", &[("a.ed", code)], "package main; import a;"); 
+        assert!(errs.is_empty()); 
+
+        let package = get_file(&files, "a"); 
+        let mut names: Vec<String> = vec![]; 
+        for command in &package.commands {
+            match command {
+                Command::Element(c) => names.push(c.name.clone()), 
+                Command::Function(c) => names.push(c.name.clone()), 
+                Command::Circle(_) => panic!("circuits of packages are removed"), 
+                _ => { }, 
+            }
+        }
+
+        assert_eq!(names, vec!["a.G", "a.H", "a.Load", "a.Part"]); 
+
+        // References inside the package follow the new names, primitives keep theirs 
+        let load = package.commands.iter().find_map(|x| match x {
+            Command::Element(c) if c.name == "a.Load" => Option::Some(c), 
+            _ => Option::None, 
+        }).unwrap(); 
+
+        let entries: Vec<&str> = load.instances.iter().map(|x| x.element_name.as_str()).collect(); 
+        assert_eq!(entries, vec!["Resistor", "a.Part"]); 
+        let calls: Vec<String> = load.instances[0].param_expressions[0].get_calls().into_iter().map(|x| x.0).collect(); 
+        assert_eq!(calls, vec!["a.G"]); 
+
+        let h = package.commands.iter().find_map(|x| match x {
+            Command::Function(c) if c.name == "a.H" => Option::Some(c), 
+            _ => Option::None, 
+        }).unwrap(); 
+
+        let calls: Vec<String> = h.expression.get_calls().into_iter().map(|x| x.0).collect(); 
+        assert_eq!(calls, vec!["a.G"]); 
+    }
+
+    #[test]
+    fn qualified_names_need_import() {
+        let packages = [
+            ("a.ed", "package a; import b; function F(x) => b.G(x);"), 
+            ("b.ed", "package b; function G(x) => x; element [P, N] Load(R) { R1[P, N] = Resistor(R); }"), 
+        ]; 
+
+        // 'b' is loaded through 'a', but the main file doesn't import it 
+        let code = "package main; 
+            import a; 
+            circuit Main() 
+            {
+                K1[1, 0] = b.Load(a.F(1)); 
+                K2[1, 0] = b.Load(b.G(1)); 
+            }"; 
+
+        let (_, errs) = load("not_imported", &packages, code); 
+        assert_eq!(get_codes(&errs), vec!["PKG004", "PKG004", "PKG004"]); 
+        assert_eq!(errs[0].get_message(), "Name 'b.Load' belongs to package 'b', which is not imported."); 
+        assert_eq!(errs[0].file_name, "main.txt"); 
+        assert_eq!(errs[0].position.as_ref().unwrap().line, 4); 
+        assert_eq!(errs[2].get_message(), "Name 'b.G' belongs to package 'b', which is not imported."); 
+    }
+
+    #[test]
+    fn nested_package_name_is_longest_match() {
+        // 'a.b' is the directory 'a/b' with two files, 'a' is the file 'a.ed', 
+        // which only 'a.b' imports 
+        let packages = [
+            ("a.ed", "package a; function F(x) => x;"), 
+            ("a/b/first.ed", "package a.b; function G(x) => x;"), 
+            ("a/b/second.ed", "package a.b; import a; function H(x) => a.b.G(x) + a.F(x);"), 
+        ]; 
+
+        let code = "package main; 
+            import a.b; 
+            function F(x) => a.b.H(x) + a.F(x); "; 
+
+        let (files, errs) = load("nested", &packages, code); 
+        assert_eq!(get_codes(&errs), vec!["PKG004"]); 
+        assert_eq!(errs[0].get_message(), "Name 'a.F' belongs to package 'a', which is not imported."); 
+        assert_eq!(files.iter().filter(|x| x.package_name == "a.b").count(), 2); 
+    }
+
+    #[test]
+    fn missing_package_lists_search_path() {
+        let (_, errs) = load("missing", &[], "package main; import nothing;"); 
+        assert_eq!(get_codes(&errs), vec!["PKG001"]); 
+        assert!(errs[0].get_message().starts_with("Package 'nothing' not found. Searched in: ")); 
+        assert_eq!(errs[0].position.as_ref().unwrap().line_position, 21); 
+    }
+
+    #[test]
+    fn declared_name_should_match_import() {
+        let (_, errs) = load("mismatch", &[("a.ed", "package b;")], "package main; import a;"); 
+        assert_eq!(get_codes(&errs), vec!["PKG003"]); 
+        assert!(errs[0].file_name.ends_with("a.ed")); 
+    }
+}
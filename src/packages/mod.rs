@@ -0,0 +1,14 @@
+mod source; 
+mod resolver; 
+mod loader; 
+mod builtin; 
+
+pub use source::SourceFile; 
+pub use source::parse_source; 
+pub use source::get_token_recognizers; 
+pub use source::get_command_recognizers; 
+pub use resolver::PackageResolver; 
+pub use loader::load_program; 
+pub use builtin::STD_PACKAGE; 
+pub use builtin::get_builtin_package; 
+pub use builtin::get_builtin_source; 
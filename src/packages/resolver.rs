@@ -0,0 +1,180 @@
+use std::env; 
+use std::ffi::OsString; 
+use std::fs; 
+use std::path::Path; 
+use std::path::PathBuf; 
+
+/// Environment variable with additional package directories, 
+/// separated like `PATH`
+pub const PACKAGE_PATH_VARIABLE: &str = "EDESIGNER_PATH"; 
+
+/// Extension of package files
+pub const PACKAGE_EXTENSION: &str = "ed"; 
+
+/// Maps package names to files. Package 'a.b' is either the file
+/// 'a/b.ed' or all '.ed' files of the directory 'a/b' in one of
+/// the search directories.
+pub struct PackageResolver {
+    pub search_path: Vec<PathBuf>, 
+}
+
+impl PackageResolver {
+    /// Packages are searched next to the main file, then in `include_dirs`
+    /// (the `-I` options) and then in directories of `EDESIGNER_PATH`
+    pub fn new(main_file_name: &str, include_dirs: &[String]) -> PackageResolver {
+        PackageResolver::with_path_variable(main_file_name, include_dirs, env::var_os(PACKAGE_PATH_VARIABLE))
+    }
+
+    /// Like `new`, but the value of `EDESIGNER_PATH` is given
+    pub fn with_path_variable(main_file_name: &str, include_dirs: &[String], path_variable: Option<OsString>) -> PackageResolver {
+        let main_dir = match Path::new(main_file_name).parent() {
+            Option::Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(), 
+            _ => PathBuf::from("."), 
+        }; 
+
+        let mut search_path: Vec<PathBuf> = vec![main_dir]; 
+        search_path.extend(include_dirs.iter().map(PathBuf::from)); 
+        if let Option::Some(paths) = path_variable {
+            search_path.extend(env::split_paths(&paths).filter(|x| !x.as_os_str().is_empty())); 
+        }
+
+        PackageResolver { search_path }
+    }
+
+    /// Returns files of the package from the first directory which
+    /// contains it, or the searched directories if it's not found
+    pub fn find(&self, package_name: &str) -> Result<Vec<PathBuf>, Vec<PathBuf>> {
+        let relative: PathBuf = package_name.split('.').collect(); 
+        for dir in &self.search_path {
+            let file = dir.join(&relative).with_extension(PACKAGE_EXTENSION); 
+            if file.is_file() {
+                return Result::Ok(vec![file]); 
+            }
+
+            let package_dir = dir.join(&relative); 
+            if package_dir.is_dir() {
+                let mut files: Vec<PathBuf> = match fs::read_dir(&package_dir) {
+                    Result::Ok(entries) => entries
+                        .filter_map(|x| x.ok())
+                        .map(|x| x.path())
+                        .filter(|x| x.is_file() && x.extension().is_some_and(|x| x == PACKAGE_EXTENSION))
+                        .collect(), 
+                    Result::Err(_) => vec![], 
+                }; 
+
+                if !files.is_empty() {
+                    files.sort(); 
+                    return Result::Ok(files); 
+                }
+            }
+        }
+
+        Result::Err(self.search_path.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env; 
+    use std::fs; 
+    use std::path::PathBuf; 
+    use super::PackageResolver; 
+
+    // Creates files (empty ones) under a fresh directory, returns the directory 
+    fn create_dir(test_name: &str, files: &[&str]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("edesigner-resolver-{}-{}", std::process::id(), test_name)); 
+        let _ = fs::remove_dir_all(&dir); 
+        fs::create_dir_all(&dir).unwrap(); 
+        for file in files {
+            let path = dir.join(file); 
+            fs::create_dir_all(path.parent().unwrap()).unwrap(); 
+            fs::write(&path, "").unwrap(); 
+        }
+
+        dir
+    }
+
+    #[test]
+    fn search_starts_next_to_main_file() {
+        let resolver = PackageResolver::with_path_variable("examples/divider.txt", &[], Option::None); 
+        assert_eq!(resolver.search_path[0], PathBuf::from("examples")); 
+
+        let resolver = PackageResolver::with_path_variable("divider.txt", &[], Option::None); 
+        assert_eq!(resolver.search_path[0], PathBuf::from(".")); 
+    }
+
+    #[test]
+    fn include_dirs_follow_main_dir() {
+        let include_dirs = vec!["lib".to_string(), "/usr/share/edesigner".to_string()]; 
+        let resolver = PackageResolver::with_path_variable("src/main.txt", &include_dirs, Option::None); 
+        assert_eq!(resolver.search_path, [
+            PathBuf::from("src"), 
+            PathBuf::from("lib"), 
+            PathBuf::from("/usr/share/edesigner"), 
+        ]); 
+    }
+
+    #[test]
+    fn package_path_variable_follows_include_dirs() {
+        let first = create_dir("variable_first", &[]); 
+        let second = create_dir("variable_second", &["a.ed"]); 
+
+        // Empty entries of the variable are skipped 
+        let paths = env::join_paths([first.clone(), PathBuf::new(), second.clone()]).unwrap(); 
+        let resolver = PackageResolver::with_path_variable("main.txt", &["lib".to_string()], Option::Some(paths)); 
+
+        assert_eq!(resolver.search_path, vec![PathBuf::from("."), PathBuf::from("lib"), first.clone(), second.clone()]); 
+        assert_eq!(resolver.find("a").ok().unwrap(), vec![second.join("a.ed")]); 
+
+        fs::remove_dir_all(&first).unwrap(); 
+        fs::remove_dir_all(&second).unwrap(); 
+    }
+
+    #[test]
+    fn nested_name_is_file_or_directory() {
+        let dir = create_dir("nested", &["a/b.ed", "c/d/second.ed", "c/d/first.ed", "c/d/notes.txt"]); 
+        let resolver = PackageResolver { search_path: vec![dir.clone()] }; 
+
+        assert_eq!(resolver.find("a.b").ok().unwrap(), vec![dir.join("a/b.ed")]); 
+
+        // All '.ed' files of the directory, sorted 
+        assert_eq!(resolver.find("c.d").ok().unwrap(), vec![dir.join("c/d/first.ed"), dir.join("c/d/second.ed")]); 
+
+        fs::remove_dir_all(&dir).unwrap(); 
+    }
+
+    #[test]
+    fn file_is_preferred_to_directory() {
+        let dir = create_dir("file_first", &["a.ed", "a/b.ed"]); 
+        let resolver = PackageResolver { search_path: vec![dir.clone()] }; 
+        assert_eq!(resolver.find("a").ok().unwrap(), vec![dir.join("a.ed")]); 
+        fs::remove_dir_all(&dir).unwrap(); 
+    }
+
+    #[test]
+    fn first_directory_wins() {
+        let include = create_dir("first_include", &["a.ed"]); 
+        let other = create_dir("first_other", &["a.ed", "b/x.ed"]); 
+        let resolver = PackageResolver { search_path: vec![include.clone(), other.clone()] }; 
+
+        assert_eq!(resolver.find("a").ok().unwrap(), vec![include.join("a.ed")]); 
+        assert_eq!(resolver.find("b").ok().unwrap(), vec![other.join("b/x.ed")]); 
+
+        fs::remove_dir_all(&include).unwrap(); 
+        fs::remove_dir_all(&other).unwrap(); 
+    }
+
+    #[test]
+    fn missing_package_returns_search_path() {
+        // The directory without '.ed' files isn't a package 
+        let dir = create_dir("missing", &["a/notes.txt"]); 
+        let resolver = PackageResolver { search_path: vec![dir.clone(), dir.join("lib")] }; 
+
+        match resolver.find("a") {
+            Result::Ok(_) => panic!("the package should not be found"), 
+            Result::Err(searched) => assert_eq!(searched, vec![dir.clone(), dir.join("lib")]), 
+        }
+
+        fs::remove_dir_all(&dir).unwrap(); 
+    }
+}
@@ -0,0 +1,79 @@
+use crate::commands; 
+use crate::commands::Command; 
+use crate::commands::get_commands; 
+use crate::entities::ErrorEntry; 
+use crate::interfaces::CommandRecognizer; 
+use crate::interfaces::TokenRecognizer; 
+use crate::statement_divider::get_statements; 
+use crate::tokenizer::tokenize; 
+use crate::tokens_recognizers::BlockCommentTokenRecognizer; 
+use crate::tokens_recognizers::LineCommentTokenRecognizer; 
+use crate::tokens_recognizers::NumberTokenRecognizer; 
+use crate::tokens_recognizers::SymbolTokenRecognizer; 
+use crate::tokens_recognizers::WordTokenRecognizer; 
+
+/// Parsed file of the program
+pub struct SourceFile {
+    pub file_id: usize, // id of spans of the file, the main file is 0
+    pub file_name: String, 
+    pub package_name: String, // name the package is imported with, empty for the main file
+    pub commands: Vec<Command>, 
+}
+
+impl SourceFile {
+    /// Names of packages imported by the file
+    pub fn get_imports(&self) -> Vec<String> {
+        self.commands.iter()
+            .filter_map(|x| match x {
+                Command::Import(c) => Option::Some(c.package_name.clone()), 
+                _ => Option::None, 
+            })
+            .collect()
+    }
+
+    /// Name from the `package` command of the file
+    pub fn get_declared_package(&self) -> Option<String> {
+        self.commands.iter()
+            .find_map(|x| match x {
+                Command::Package(c) => Option::Some(c.name.clone()), 
+                _ => Option::None, 
+            })
+    }
+}
+
+/// Recognizers of all tokens of the language, in order of priority 
+pub fn get_token_recognizers() -> Vec<&'static dyn TokenRecognizer> {
+    vec!(
+        &LineCommentTokenRecognizer{}, 
+        &BlockCommentTokenRecognizer{}, 
+        &SymbolTokenRecognizer{}, 
+        &WordTokenRecognizer{}, 
+        &NumberTokenRecognizer{}, 
+    )
+}
+
+/// Recognizers of top-level commands, element entries are 
+/// recognized inside them 
+pub fn get_command_recognizers() -> Vec<&'static dyn CommandRecognizer> {
+    vec![
+        &commands::CircleCommandRecognizer{}, 
+        &commands::ElementCommandRecognizer{}, 
+        &commands::PackageCommandRecognizer{}, 
+        &commands::ImportCommandRecognizer{}, 
+        &commands::FunctionCommandRecognizer{}, 
+        &commands::PrimitiveCommandRecognizer{}, 
+    ]
+}
+
+/// Splits the code into commands, spans of the nodes point to the file 
+/// `file_id`. Every pass goes on after errors, so all independent errors 
+/// of the file are reported at once. 
+pub fn parse_source(code: String, file_id: usize, errs_acc: &mut Vec<ErrorEntry>) -> Vec<Command> {
+    let mut tokens = tokenize(code, &get_token_recognizers(), errs_acc); 
+    for token in &mut tokens {
+        token.span.file_id = file_id; 
+    }
+
+    let stats = get_statements(&tokens, errs_acc); 
+    get_commands(&stats, &get_command_recognizers(), errs_acc)
+}
@@ -0,0 +1,50 @@
+use crate::ast::Instance; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::semantic::SymbolTable; 
+
+/// Compares amount of nodes and parameters of every element entry with 
+/// the declaration of the element or the primitive. 
+/// Unresolved elements are skipped, `resolve_names` reports them. 
+/// Entries of `own` declarations are checked, elements are looked up 
+/// in the whole `table`. 
+pub fn check_arity(table: &SymbolTable, own: &SymbolTable, errs_acc: &mut Vec<ErrorEntry>) {
+    let mut entries: Vec<&Instance> = own.circuits.values()
+        .flat_map(|x| x.instances.iter())
+        .chain(own.elements.values().flat_map(|x| x.instances.iter()))
+        .collect(); 
+    
+    entries.sort_by_key(|x| x.span.start); 
+
+    for entry in entries {
+        let (nodes_count, params_count) = match get_element_arity(table, &entry.element_name) {
+            Option::Some(x) => x, 
+            Option::None => continue, 
+        }; 
+
+        if entry.nodes.len() != nodes_count {
+            errs_acc.push(ErrorEntry::new(
+                errors::WRONG_NODES_COUNT, 
+                &entry.span.position, 
+                &vec![entry.element_name.clone(), nodes_count.to_string(), entry.nodes.len().to_string()])
+                .with_span(&entry.span)); 
+        }
+
+        if entry.param_expressions.len() != params_count {
+            errs_acc.push(ErrorEntry::new(
+                errors::WRONG_PARAMS_COUNT, 
+                &entry.span.position, 
+                &vec![entry.element_name.clone(), params_count.to_string(), entry.param_expressions.len().to_string()])
+                .with_span(&entry.span)); 
+        }
+    }
+}
+
+/// Returns amount of nodes and parameters of the element 
+fn get_element_arity(table: &SymbolTable, name: &str) -> Option<(usize, usize)> {
+    if let Option::Some(element) = table.elements.get(name) {
+        return Option::Some((element.nodes.len(), element.params.len())); 
+    }
+
+    table.primitives.get(name).map(|x| (x.nodes.len(), x.params.len()))
+}
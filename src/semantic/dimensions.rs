@@ -0,0 +1,140 @@
+use crate::ast::Instance; 
+use crate::entities::Dimension; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::keywords::get_primitive_signature; 
+use crate::keywords::member; 
+use crate::keywords::variable; 
+use crate::semantic::SymbolTable; 
+
+/// Dimensions of built-in variables and quantities of instances, 
+/// parameters of elements and functions are unknown
+fn lookup(name: &str) -> Option<Dimension> {
+    let unit = match name.rsplit_once('.') {
+        Option::Some((_, member::VOLTAGE)) => "V", 
+        Option::Some((_, member::CURRENT)) => "A", 
+        Option::Some((_, member::POWER)) => "W", 
+        Option::Some(_) => return Option::None, 
+        Option::None => match name {
+            variable::TIME => "s", 
+            variable::FREQUENCY | variable::LAPLACE => "Hz", 
+            _ => return Option::None, 
+        }, 
+    }; 
+
+    Dimension::from_unit(unit)
+}
+
+/// Infers dimensions of expressions of `own` declarations from units
+/// of literals. Parameters of primitives are compared with the units
+/// of the built-in models. Mismatches are warnings: the program is
+/// still simulated.
+pub fn check_dimensions(own: &SymbolTable, warns_acc: &mut Vec<ErrorEntry>) {
+    let mut entries: Vec<&Instance> = own.circuits.values()
+        .flat_map(|x| x.instances.iter())
+        .chain(own.elements.values().flat_map(|x| x.instances.iter()))
+        .collect(); 
+
+    entries.sort_by_key(|x| x.span.start); 
+
+    for entry in entries {
+        let signature = get_primitive_signature(&entry.element_name); 
+        for (index, expression) in entry.param_expressions.iter().enumerate() {
+            let inferred = expression.infer_dimension(&lookup, warns_acc); 
+            let expected = signature
+                .and_then(|x| x.param_units.get(index))
+                .and_then(|x| Dimension::from_unit(x)); 
+
+            match (expected, inferred) {
+                (Option::Some(expected), Option::Some(found)) if expected != found => {
+                    let param_name = signature.map(|x| x.param_names[index]).unwrap_or_default(); 
+                    warns_acc.push(ErrorEntry::warning(
+                        errors::DIMENSION_MISMATCH, 
+                        &expression.span.position, 
+                        &vec![param_name.to_string(), entry.element_name.clone(), expected.to_string(), found.to_string()])
+                        .with_span(&expression.span)
+                        .with_help(&format!("units of literals are written after the number, e.g. '10{}'", expected))); 
+                }, 
+                _ => { }, 
+            }
+        }
+    }
+
+    let mut functions: Vec<_> = own.functions.values().collect(); 
+    functions.sort_by_key(|x| x.span.start); 
+
+    for function in functions {
+        function.expression.infer_dimension(&lookup, warns_acc); 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::check; 
+    use crate::compiler::CompileOptions; 
+
+    /// Dimension warnings of the program as "code line:column message" 
+    fn get_warnings(code: &str) -> Vec<String> {
+        let program = match check(code, &CompileOptions::default()) {
+            Result::Ok(program) => program, 
+            Result::Err(errors) => panic!("{:?}", errors.iter().map(|x| x.get_message()).collect::<Vec<String>>()), 
+        }; 
+
+        program.warnings.iter()
+            .filter(|x| x.error.code.starts_with("DIM"))
+            .map(|x| format!("{} {}:{} {}", x.error.code, x.position.as_ref().unwrap().line + 1, x.position.as_ref().unwrap().line_position + 1, x.get_message()))
+            .collect()
+    }
+
+    fn circuit(source: &str) -> String {
+        format!("circuit Main()\n{{\n    V1[1, 0] = VoltageSource({});\n    R1[1, 0] = Resistor(1kOhm);\n}}", source)
+    }
+
+    #[test]
+    fn consistent_units_have_no_warnings() {
+        assert!(get_warnings(&circuit("2V * sin(2 * 1kHz * time) + 1mA * 1kOhm")).is_empty()); 
+        assert!(get_warnings(&circuit("time > 1ms ? 5V : 0V")).is_empty()); 
+
+        // Numbers without units and parameters are unknown 
+        assert!(get_warnings(&circuit("5 + 1V")).is_empty()); 
+    }
+
+    #[test]
+    fn incompatible_operands_are_reported() {
+        assert_eq!(get_warnings(&circuit("1V + 1A")), vec![
+            "DIM001 3:33 Operands of '+' have incompatible units: V and A.", 
+        ]); 
+
+        assert_eq!(get_warnings(&circuit("time > 1V ? 1V : 1A")), vec![
+            "DIM001 3:35 Operands of '>' have incompatible units: s and V.", 
+            "DIM001 3:40 Operands of '?:' have incompatible units: V and A.", 
+        ]); 
+    }
+
+    #[test]
+    fn arguments_of_functions_should_be_dimensionless() {
+        assert_eq!(get_warnings(&circuit("sin(time)")), vec![
+            "DIM002 3:30 Argument of 'sin' should be dimensionless, found s.", 
+            "DIM003 3:30 Parameter 'U' of 'VoltageSource' expects V, found dimensionless.", 
+        ]); 
+
+        assert_eq!(get_warnings("function f(x) => exp(1V) + 2 ^ 1s;"), vec![
+            "DIM002 1:18 Argument of 'exp' should be dimensionless, found V.", 
+            "DIM002 1:30 Argument of '^' should be dimensionless, found s.", 
+        ]); 
+    }
+
+    #[test]
+    fn parameters_of_primitives_are_checked() {
+        let code = "circuit Main()\n{\n    C1[1, 0] = Capacitor(1V / 1A);\n    R1[1, 0] = Resistor(1kOhm * 1mA / 1A);\n}"; 
+        assert_eq!(get_warnings(code), vec![
+            "DIM003 3:26 Parameter 'C' of 'Capacitor' expects F, found Ohm.", 
+        ]); 
+
+        // Derived units are printed by base ones 
+        let code = "circuit Main()\n{\n    L1[1, 0] = Inductor(1H / 1s);\n}"; 
+        assert_eq!(get_warnings(code), vec![
+            "DIM003 3:25 Parameter 'L' of 'Inductor' expects H, found Ohm.", 
+        ]); 
+    }
+}
@@ -0,0 +1,292 @@
+mod symbol_table; 
+mod name_resolution; 
+mod arity; 
+mod dimensions; 
+
+pub use symbol_table::SymbolTable; 
+pub use symbol_table::get_symbol_table; 
+pub use name_resolution::resolve_names; 
+pub use arity::check_arity; 
+pub use dimensions::check_dimensions; 
+
+use crate::entities::ErrorEntry; 
+use crate::packages::SourceFile; 
+use crate::packages::STD_PACKAGE; 
+use crate::commands::Command; 
+use crate::entities::errors; 
+
+/// Semantic analysis of the loaded files: builds the symbol table of 
+/// the whole program, resolves all names used in every file and checks 
+/// element entries against the element declarations. Diagnostics are 
+/// attributed to the files they come from, dimension mismatches are 
+/// warnings. 
+pub fn analyze(files: &Vec<SourceFile>, errs_acc: &mut Vec<ErrorEntry>) -> SymbolTable {
+    let mut table = SymbolTable::default(); 
+    let mut own_tables: Vec<SymbolTable> = vec![]; 
+    for file in files {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let own = get_symbol_table(&file.commands, &mut errs); 
+        check_primitives_package(file, &mut errs); 
+        errs_acc.extend(errs.into_iter().map(|x| x.in_file(&file.file_name))); 
+
+        table.extend(own.clone(), &file.file_name); 
+        own_tables.push(own); 
+    }
+
+    for (file, own) in files.iter().zip(&own_tables) {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        resolve_names(&table, own, &mut errs); 
+        check_arity(&table, own, &mut errs); 
+        check_dimensions(own, &mut errs); 
+        errs_acc.extend(errs.into_iter().map(|x| x.in_file(&file.file_name))); 
+    }
+
+    table
+}
+
+/// Models of primitives are built into the simulator, so only 
+/// the standard package declares them 
+fn check_primitives_package(file: &SourceFile, errs_acc: &mut Vec<ErrorEntry>) {
+    if file.package_name == STD_PACKAGE {
+        return; 
+    }
+
+    for command in &file.commands {
+        if let Command::Primitive(c) = command {
+            errs_acc.push(ErrorEntry::new(
+                errors::PRIMITIVE_OUTSIDE_STD, 
+                &c.name_span.position, 
+                &vec![c.name.clone()])); 
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::check; 
+    use crate::compiler::CompileOptions; 
+    use crate::entities::ErrorEntry; 
+    use crate::packages::parse_source; 
+    use super::get_symbol_table; 
+
+    fn get_diagnostics(code: &str) -> Vec<ErrorEntry> {
+        match check(code, &CompileOptions::default()) {
+            Result::Ok(program) => program.warnings, 
+            Result::Err(diagnostics) => diagnostics, 
+        }
+    }
+
+    /// Codes of the diagnostics with their 1-based lines and columns 
+    fn get_codes(code: &str) -> Vec<String> {
+        get_diagnostics(code).iter()
+            .map(|x| format!("{} {}:{}", x.error.code, x.position.as_ref().unwrap().line + 1, x.position.as_ref().unwrap().line_position + 1))
+            .collect()
+    }
+
+    #[test]
+    fn valid_program_has_no_diagnostics() {
+        let code = "
+circuit Main(U)
+{
+    V1[1, 0] = VoltageSource(U); 
+    K1[1, 0] = Load(2 * half(1000)); 
+}
+
+element [In, Out] Load(R)
+{
+    R1[In, Out] = Resistor(R + time * 0); 
+}
+
+function half(x) => x / 2; 
+"; 
+        assert_eq!(get_codes(code), Vec::<String>::new()); 
+    }
+
+    #[test]
+    fn undefined_names_are_reported() {
+        let code = "
+circuit Main(U)
+{
+    X1[1, 0] = Unknown(1); 
+    R1[1, 0] = Resistor(R); 
+    R2[1, 0] = Resistor(foo(U)); 
+}
+
+function f(a) => a + time; 
+"; 
+        assert_eq!(get_codes(code), vec!["SEM001 4:16", "SEM002 5:25", "SEM003 6:25", "SEM002 9:22"]); 
+
+        let messages: Vec<String> = get_diagnostics(code).iter().map(|x| x.get_message()).collect(); 
+        assert_eq!(messages[0], "Undefined element: 'Unknown'."); 
+        assert_eq!(messages[1], "Undefined parameter: 'R'."); 
+        assert_eq!(messages[2], "Undefined function: 'foo'."); 
+    }
+
+    #[test]
+    fn duplicate_definitions_are_reported() {
+        let code = "
+circuit Main(a, a)
+{
+    R1[1, 0] = Resistor(1); 
+    R1[1, 0] = Resistor(2); 
+}
+
+element [A, A] Load() { R1[A, 0] = Resistor(1); }
+circuit Main() { R1[1, 0] = Resistor(1); }
+function f() => 1; 
+function f() => 2; 
+"; 
+        assert_eq!(get_codes(code), vec!["SEM004 9:9", "SEM004 11:10", "SEM004 2:17", "SEM004 5:5", "SEM004 8:13"]); 
+
+        // The second declaration points to the first one 
+        let diagnostics = get_diagnostics(code); 
+        assert_eq!(diagnostics[0].labels.len(), 1); 
+        assert_eq!(diagnostics[0].labels[0].position.line, 1); 
+        assert_eq!(diagnostics[0].labels[0].message, "first defined here"); 
+    }
+
+    #[test]
+    fn shadowed_names_are_reported() {
+        let code = "
+circuit Main(time)
+{
+    R1[1, 0] = Resistor(time); 
+}
+
+element [A, B] Resistor(R) { R1[A, B] = Resistor(R); }
+function sin(x) => x; 
+"; 
+        let diagnostics = get_diagnostics(code); 
+        let codes: Vec<String> = diagnostics.iter().map(|x| format!("{} {}", x.error.code, x.get_message())).collect(); 
+        assert!(codes.contains(&format!("SEM005 Name 'Resistor' shadows the built-in primitive with the same name."))); 
+        assert!(codes.contains(&format!("SEM005 Name 'sin' shadows the standard function with the same name."))); 
+        assert!(codes.contains(&format!("SEM005 Name 'time' shadows the built-in variable with the same name."))); 
+    }
+
+    #[test]
+    fn node_and_parameter_counts_are_checked() {
+        let code = "
+circuit Main()
+{
+    R1[1] = Resistor(10, 20); 
+    K1[1, 0] = Load(); 
+    K2[1, 0, 2] = Load(5); 
+    X1[1] = Unknown(); 
+}
+
+element [In, Out] Load(R) { R1[In, Out] = Resistor(R); }
+"; 
+        assert_eq!(get_codes(code), vec!["SEM001 7:13", "SEM006 4:5", "SEM007 4:5", "SEM007 5:5", "SEM006 6:5"]); 
+
+        let messages: Vec<String> = get_diagnostics(code).iter().map(|x| x.get_message()).collect(); 
+        assert_eq!(messages[1], "Element 'Resistor' has 2 node(s), but 1 given."); 
+        assert_eq!(messages[2], "Element 'Resistor' takes 1 parameter(s), but 2 given."); 
+        assert_eq!(messages[3], "Element 'Load' takes 1 parameter(s), but 0 given."); 
+        assert_eq!(messages[4], "Element 'Load' has 2 node(s), but 3 given."); 
+    }
+
+    #[test]
+    fn arity_errors_cover_the_whole_entry() {
+        let code = "circuit Main() { R1[1] = Resistor(10); }"; 
+        let diagnostics = get_diagnostics(code); 
+        assert_eq!(diagnostics.len(), 1); 
+
+        let end = diagnostics[0].end_position.clone().unwrap(); 
+        assert_eq!(diagnostics[0].position.as_ref().unwrap().line_position, 17); 
+        assert_eq!(end.line_position, code.find(" }").unwrap()); 
+    }
+
+    #[test]
+    fn member_access_is_resolved() {
+        let code = "
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(1); 
+    R1[1, 2] = Resistor(100); 
+    R2[2, 0] = Resistor(R1.R); 
+    J1[0, 2] = CurrentSource(X9.I); 
+    R3[2, 0] = Resistor(10); 
+    J2[0, 2] = CurrentSource(R3.Q); 
+    K1[2, 0] = Load(1); 
+    J3[0, 2] = CurrentSource(K1.I); 
+}
+
+element [In, Out] Load(R) { R1[In, Out] = Resistor(R); }
+"; 
+        assert_eq!(get_codes(code), vec!["SEM008 7:30", "SEM009 9:30", "SEM009 11:30"]); 
+
+        let messages: Vec<String> = get_diagnostics(code).iter().map(|x| x.get_message()).collect(); 
+        assert_eq!(messages[0], "Instance 'X9' is not defined in this scope."); 
+        assert_eq!(messages[1], "Instance 'R3' of 'Resistor' has no member 'Q'."); 
+        assert_eq!(messages[2], "Instance 'K1' of 'Load' has no member 'I'."); 
+    }
+
+    #[test]
+    fn recursive_members_are_reported() {
+        let code = "
+circuit Main()
+{
+    R5[1, 0] = Resistor(R5.R); 
+    R6[1, 0] = Resistor(R7.R); 
+    R7[1, 0] = Resistor(2 * R6.R); 
+    R8[1, 0] = Resistor(R5.U / R5.I); 
+}
+"; 
+        // Quantities are solved, not bound, so reading 'R5.U' isn't a cycle 
+        assert_eq!(get_codes(code), vec!["SEM010 4:25", "SEM010 6:29"]); 
+
+        let messages: Vec<String> = get_diagnostics(code).iter().map(|x| x.get_message()).collect(); 
+        assert_eq!(messages[0], "Parameter of 'R5' depends on itself: R5 -> R5."); 
+        assert_eq!(messages[1], "Parameter of 'R6' depends on itself: R6 -> R7 -> R6."); 
+    }
+
+    #[test]
+    fn primitives_are_declared_only_in_std() {
+        let code = "
+primitive [P, N] Resistor(R); 
+primitive [A, K] Diode(IS, N); 
+circuit Main() { R1[1, 0] = Resistor(10); }
+"; 
+        assert_eq!(get_codes(code), vec!["SEM012 2:18", "SEM012 3:18"]); 
+        assert_eq!(get_diagnostics(code)[0].get_message(), "Primitive 'Resistor' can be declared only in the standard package."); 
+    }
+
+    #[test]
+    fn primitives_should_match_models() {
+        let code = "
+primitive [P] Resistor(R); 
+primitive [A, K] Diode(IS); 
+primitive [C, B, E] Transistor(BF); 
+primitive [P, N] Capacitor(C); 
+"; 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source(code.to_string(), 0, &mut errs); 
+        let table = get_symbol_table(&commands, &mut errs); 
+
+        let messages: Vec<String> = errs.iter().map(|x| format!("{} {}", x.error.code, x.get_message())).collect(); 
+        assert_eq!(messages, vec![
+            "SEM011 Primitive 'Resistor' doesn't match the built-in model: expected 2 pin(s), found 1.", 
+            "SEM011 Primitive 'Diode' doesn't match the built-in model: expected 2 parameter(s), found 1.", 
+            "SEM011 Primitive 'Transistor' doesn't match the built-in model: there is no such model.", 
+        ]); 
+
+        // Declarations are kept, so entries are still checked against them 
+        assert_eq!(table.primitives.len(), 4); 
+        assert!(table.is_instantiable("Transistor")); 
+    }
+
+    #[test]
+    fn primitive_name_clashes_with_element() {
+        let code = "
+element [P, N] Capacitor(C) { R1[P, N] = Resistor(C); }
+primitive [P, N] Capacitor(C); 
+"; 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let commands = parse_source(code.to_string(), 0, &mut errs); 
+        get_symbol_table(&commands, &mut errs); 
+
+        let codes: Vec<&str> = errs.iter().map(|x| x.error.code).collect(); 
+        assert_eq!(codes, vec!["SEM005", "SEM004"]); 
+        assert_eq!(errs[1].position.as_ref().unwrap().line, 2); 
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashSet; 
+use crate::ast::Instance; 
+use crate::entities::Expression; 
+use crate::ast::Ident; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::semantic::SymbolTable; 
+use crate::keywords::ALL_VARIABLES; 
+use crate::keywords::is_builtin_variable; 
+use crate::keywords::is_quantity; 
+use crate::entities::Position; 
+
+/// Resolves element names of all element entries and identifiers 
+/// used in expressions of `own` declarations, names are looked up in 
+/// the whole `table`. Every element, circuit and function is a separate 
+/// scope containing only its parameters. 
+pub fn resolve_names(table: &SymbolTable, own: &SymbolTable, errs_acc: &mut Vec<ErrorEntry>) {
+    // Symbol table is a hash map, so the diagnostics are sorted by source position
+    let mut circuits: Vec<_> = own.circuits.values().collect(); 
+    circuits.sort_by_key(|c| c.span.start); 
+    for circuit in circuits {
+        let scope = get_scope(&circuit.params, errs_acc); 
+        resolve_entries(table, &circuit.instances, &scope, errs_acc); 
+    }
+
+    let mut elements: Vec<_> = own.elements.values().collect(); 
+    elements.sort_by_key(|c| c.span.start); 
+    for element in elements {
+        get_scope(&element.nodes, errs_acc); 
+        let scope = get_scope(&element.params, errs_acc); 
+        resolve_entries(table, &element.instances, &scope, errs_acc); 
+    }
+
+    let mut functions: Vec<_> = own.functions.values().collect(); 
+    functions.sort_by_key(|c| c.span.start); 
+    for function in functions {
+        let scope = get_scope(&function.params, errs_acc); 
+        resolve_expression(table, &function.expression, &scope, errs_acc); 
+
+        // Function bodies have no instances 
+        resolve_members(table, &function.expression, &[], errs_acc); 
+    }
+}
+
+/// Collects the names into a scope reporting duplicates and 
+/// names of built-in variables 
+fn get_scope(names: &Vec<Ident>, errs_acc: &mut Vec<ErrorEntry>) -> HashSet<String> {
+    let mut scope: HashSet<String> = HashSet::new(); 
+    for ident in names {
+        if is_builtin_variable(&ident.name) {
+            errs_acc.push(ErrorEntry::new(
+                errors::SHADOWED_NAME, 
+                &ident.span.position, 
+                &vec![ident.name.clone(), format!("built-in variable")])); 
+        }
+
+        // Duplicate is reported at its own position, not at the first occurrence 
+        if !scope.insert(ident.name.clone()) {
+            errs_acc.push(ErrorEntry::new(
+                errors::DUPLICATE_DEFINITION, 
+                &ident.span.position, 
+                &vec![ident.name.clone()])); 
+        }
+    }
+
+    scope
+}
+
+/// Built-in variables are visible in entries, but not in function bodies 
+fn resolve_entries(table: &SymbolTable, entries: &Vec<Instance>, scope: &HashSet<String>, errs_acc: &mut Vec<ErrorEntry>) {
+    let mut scope = scope.clone(); 
+    scope.extend(ALL_VARIABLES.iter().map(|x| x.to_string())); 
+
+    let mut entry_names: HashSet<String> = HashSet::new(); 
+    for entry in entries {
+        if !entry_names.insert(entry.entry_name.clone()) {
+            errs_acc.push(ErrorEntry::new(
+                errors::DUPLICATE_DEFINITION, 
+                &entry.name_span.position, 
+                &vec![entry.entry_name.clone()])); 
+        }
+
+        if !table.is_instantiable(&entry.element_name) {
+            errs_acc.push(ErrorEntry::new(
+                errors::UNDEFINED_ELEMENT, 
+                &entry.element_name_span.position, 
+                &vec![entry.element_name.clone()])); 
+        }
+
+        for expression in &entry.param_expressions {
+            resolve_expression(table, expression, &scope, errs_acc); 
+            resolve_members(table, expression, entries, errs_acc); 
+        }
+    }
+
+    check_member_cycles(entries, errs_acc); 
+}
+
+/// Instance of the member access should be an entry of the same element. 
+/// Quantities (U, I, P) are available for primitives, parameters 
+/// for all instances. 
+fn resolve_members(table: &SymbolTable, expression: &Expression, entries: &[Instance], errs_acc: &mut Vec<ErrorEntry>) {
+    for (instance, member, position) in expression.get_members() {
+        let entry = match entries.iter().find(|x| x.entry_name == instance) {
+            Option::Some(entry) => entry, 
+            Option::None => {
+                errs_acc.push(ErrorEntry::new(
+                    errors::UNDEFINED_INSTANCE, 
+                    &position, 
+                    &vec![instance])); 
+                continue; 
+            }
+        }; 
+
+        let is_defined = match is_quantity(&member) {
+            true => table.primitives.contains_key(&entry.element_name), 
+            false => table.get_param_names(&entry.element_name)
+                .map(|x| x.contains(&member))
+                .unwrap_or(true), // undefined element is reported separately
+        }; 
+
+        if !is_defined {
+            errs_acc.push(ErrorEntry::new(
+                errors::UNDEFINED_MEMBER, 
+                &position, 
+                &vec![instance, entry.element_name.clone(), member])); 
+        }
+    }
+}
+
+/// Entries which are read by parameter members (e.g. 'R1.R') 
+fn get_member_dependencies<'a>(entry: &Instance, entries: &'a [Instance]) -> Vec<(&'a Instance, Position)> {
+    let mut dependencies: Vec<(&Instance, Position)> = vec![]; 
+    for (instance, member, position) in entry.param_expressions.iter().flat_map(|x| x.get_members()) {
+        let is_new = dependencies.iter().all(|x| x.0.entry_name != instance); 
+        if let (false, true, Option::Some(dependency)) = (is_quantity(&member), is_new, entries.iter().find(|x| x.entry_name == instance)) {
+            dependencies.push((dependency, position)); 
+        }
+    }
+
+    dependencies
+}
+
+/// Parameter members are bound lazily, so an entry can't 
+/// read its own parameters through other entries 
+fn check_member_cycles(entries: &Vec<Instance>, errs_acc: &mut Vec<ErrorEntry>) {
+    let mut finished: HashSet<String> = HashSet::new(); 
+    for entry in entries {
+        let mut chain: Vec<String> = vec![]; 
+        find_member_cycle(entry, entries, &mut chain, &mut finished, errs_acc); 
+    }
+}
+
+fn find_member_cycle(entry: &Instance, 
+                     entries: &Vec<Instance>, 
+                     chain: &mut Vec<String>, 
+                     finished: &mut HashSet<String>, 
+                     errs_acc: &mut Vec<ErrorEntry>) {
+
+    if finished.contains(&entry.entry_name) {
+        return; 
+    }
+
+    chain.push(entry.entry_name.clone()); 
+    for (dependency, position) in get_member_dependencies(entry, entries) {
+        if let Option::Some(start) = chain.iter().position(|x| *x == dependency.entry_name) {
+            let mut cycle = chain[start..].to_vec(); 
+            cycle.push(dependency.entry_name.clone()); 
+            errs_acc.push(ErrorEntry::new(
+                errors::RECURSIVE_MEMBER, 
+                &position, 
+                &vec![dependency.entry_name.clone(), cycle.join(" -> ")])); 
+            continue; 
+        }
+
+        find_member_cycle(dependency, entries, chain, finished, errs_acc); 
+    }
+
+    chain.pop(); 
+    finished.insert(entry.entry_name.clone()); 
+}
+
+fn resolve_expression(table: &SymbolTable, expression: &Expression, scope: &HashSet<String>, errs_acc: &mut Vec<ErrorEntry>) {
+    for (name, position) in expression.get_variables() {
+        if !scope.contains(&name) {
+            errs_acc.push(ErrorEntry::new(
+                errors::UNDEFINED_PARAMETER, 
+                &position, 
+                &vec![name])); 
+        }
+    }
+
+    for (name, _, position) in expression.get_calls() {
+        if !table.is_function(&name) {
+            errs_acc.push(ErrorEntry::new(
+                errors::UNRESOLVED_FUNCTION, 
+                &position, 
+                &vec![name])); 
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap; 
+use crate::commands::Command; 
+use crate::ast::Circuit; 
+use crate::ast::ElementDef; 
+use crate::ast::FunctionDef; 
+use crate::ast::PrimitiveDef; 
+use crate::entities::Span; 
+use crate::entities::Position; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::entities::is_standart_function; 
+use crate::keywords::is_primitive; 
+use crate::keywords::get_primitive_signature; 
+
+/// Top-level declarations of the file or of the whole program. Elements, 
+/// primitives and circuits share one namespace, functions have their own one. 
+#[derive(Clone, Default)]
+pub struct SymbolTable {
+    pub elements: HashMap<String, ElementDef>, 
+    pub circuits: HashMap<String, Circuit>, 
+    pub functions: HashMap<String, FunctionDef>, 
+    pub primitives: HashMap<String, PrimitiveDef>, 
+    pub file_names: HashMap<String, String>, // files of elements and circuits
+}
+
+pub fn get_symbol_table(commands: &Vec<Command>, errs_acc: &mut Vec<ErrorEntry>) -> SymbolTable {
+    let mut table = SymbolTable::default(); 
+
+    for command in commands {
+        match command {
+            Command::Element(c) => {
+                let position = c.name_span.position.clone(); 
+                if table.is_element_or_circuit(&c.name) {
+                    push_duplicate(&c.name, &position, table.get_declaration(&c.name), errs_acc); 
+                    continue; 
+                }
+
+                check_primitive_shadowing(&c.name, &position, errs_acc); 
+                table.elements.insert(c.name.clone(), c.clone()); 
+            },
+            Command::Circle(c) => {
+                let position = c.name_span.position.clone(); 
+                if table.is_element_or_circuit(&c.name) {
+                    push_duplicate(&c.name, &position, table.get_declaration(&c.name), errs_acc); 
+                    continue; 
+                }
+
+                check_primitive_shadowing(&c.name, &position, errs_acc); 
+                table.circuits.insert(c.name.clone(), c.clone()); 
+            },
+            Command::Function(c) => {
+                let position = c.name_span.position.clone(); 
+                if table.functions.contains_key(&c.name) {
+                    push_duplicate(&c.name, &position, table.functions.get(&c.name).map(|x| &x.name_span), errs_acc); 
+                    continue; 
+                }
+
+                if is_standart_function(&c.name) {
+                    errs_acc.push(ErrorEntry::new(
+                        errors::SHADOWED_NAME, 
+                        &position, 
+                        &vec![c.name.clone(), format!("standard function")])); 
+                }
+
+                table.functions.insert(c.name.clone(), c.clone()); 
+            },
+            Command::Primitive(c) => {
+                let position = c.name_span.position.clone(); 
+                if table.is_element_or_circuit(&c.name) {
+                    push_duplicate(&c.name, &position, table.get_declaration(&c.name), errs_acc); 
+                    continue; 
+                }
+
+                check_primitive_model(c, &position, errs_acc); 
+                table.primitives.insert(c.name.clone(), c.clone()); 
+            },
+            _ => { },
+        }
+    }
+
+    table
+}
+
+impl SymbolTable {
+    /// Adds declarations of the other file. Names of imported packages are 
+    /// qualified, so they don't clash; the first declaration wins otherwise. 
+    pub fn extend(&mut self, other: SymbolTable, file_name: &str) {
+        for (name, element) in other.elements {
+            if !self.is_element_or_circuit(&name) {
+                self.file_names.insert(name.clone(), file_name.to_string()); 
+                self.elements.insert(name, element); 
+            }
+        }
+
+        for (name, circuit) in other.circuits {
+            if !self.is_element_or_circuit(&name) {
+                self.file_names.insert(name.clone(), file_name.to_string()); 
+                self.circuits.insert(name, circuit); 
+            }
+        }
+
+        for (name, function) in other.functions {
+            self.functions.entry(name).or_insert(function); 
+        }
+
+        for (name, primitive) in other.primitives {
+            if !self.is_element_or_circuit(&name) {
+                self.primitives.insert(name, primitive); 
+            }
+        }
+    }
+
+    pub fn is_element_or_circuit(&self, name: &str) -> bool {
+        self.elements.contains_key(name) || self.circuits.contains_key(name) || self.primitives.contains_key(name)
+    }
+
+    /// Span of the name of the element, the circuit or the primitive declaration 
+    pub fn get_declaration(&self, name: &str) -> Option<&Span> {
+        self.elements.get(name).map(|x| &x.name_span)
+            .or_else(|| self.circuits.get(name).map(|x| &x.name_span))
+            .or_else(|| self.primitives.get(name).map(|x| &x.name_span))
+    }
+
+    /// Element can be instantiated if it's declared as an element or a primitive 
+    pub fn is_instantiable(&self, name: &str) -> bool {
+        self.elements.contains_key(name) || self.primitives.contains_key(name)
+    }
+
+    pub fn is_function(&self, name: &str) -> bool {
+        self.functions.contains_key(name) || is_standart_function(name)
+    }
+
+    /// Parameter names of the element or the primitive 
+    pub fn get_param_names(&self, name: &str) -> Option<Vec<String>> {
+        match self.elements.get(name) {
+            Option::Some(element) => Option::Some(element.params.iter().map(|x| x.name.clone()).collect()), 
+            Option::None => self.primitives.get(name).map(|x| x.params.iter().map(|x| x.name.clone()).collect()), 
+        }
+    }
+}
+
+/// Points to the first declaration of the name, if it's known 
+fn push_duplicate(name: &str, position: &Position, first: Option<&Span>, errs_acc: &mut Vec<ErrorEntry>) {
+    let error = ErrorEntry::new(errors::DUPLICATE_DEFINITION, position, &vec![name.to_string()]); 
+    errs_acc.push(match first {
+        Option::Some(span) => error.with_label(&span.position, "first defined here"), 
+        Option::None => error, 
+    }); 
+}
+
+fn check_primitive_shadowing(name: &str, position: &Position, errs_acc: &mut Vec<ErrorEntry>) {
+    if is_primitive(name) {
+        errs_acc.push(ErrorEntry::new(
+            errors::SHADOWED_NAME, 
+            position, 
+            &vec![name.to_string(), format!("built-in primitive")])); 
+    }
+}
+
+/// Declaration of the primitive should match the model of the simulator 
+fn check_primitive_model(primitive: &PrimitiveDef, position: &Position, errs_acc: &mut Vec<ErrorEntry>) {
+    let mismatch = match get_primitive_signature(&primitive.name) {
+        Option::None => Option::Some("there is no such model".to_string()), 
+        Option::Some(x) if x.nodes.len() != primitive.nodes.len() => 
+            Option::Some(format!("expected {} pin(s), found {}", x.nodes.len(), primitive.nodes.len())), 
+        Option::Some(x) if x.param_names.len() != primitive.params.len() => 
+            Option::Some(format!("expected {} parameter(s), found {}", x.param_names.len(), primitive.params.len())), 
+        Option::Some(_) => Option::None, 
+    }; 
+
+    if let Option::Some(mismatch) = mismatch {
+        errs_acc.push(ErrorEntry::new(
+            errors::INVALID_PRIMITIVE, 
+            position, 
+            &vec![primitive.name.clone(), mismatch])); 
+    }
+}
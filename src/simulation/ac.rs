@@ -0,0 +1,246 @@
+use std::collections::HashMap; 
+use crate::elaborator::Device; 
+use crate::elaborator::Netlist; 
+use crate::entities::Complex; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::keywords::primitive; 
+use crate::simulation::MnaSystem; 
+use crate::simulation::Solution; 
+use crate::simulation::operating_point; 
+use crate::simulation::get_dc_branches; 
+use crate::simulation::linearize_controlled; 
+use crate::simulation::PointState; 
+use crate::simulation::get_branch_voltage; 
+use crate::simulation::diode_current; 
+
+/// Distribution of frequency points of the AC analysis 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SweepType {
+    Linear, // `points` points from start to stop
+    Decade, // `points` points per decade
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sweep {
+    pub sweep_type: SweepType, 
+    pub points: usize, 
+    pub start: f64, 
+    pub stop: f64, 
+}
+
+impl Sweep {
+    /// Frequencies of the sweep, both bounds included 
+    pub fn get_frequencies(&self) -> Vec<f64> {
+        match self.sweep_type {
+            SweepType::Linear if self.points < 2 => vec![self.start], 
+            SweepType::Linear => {
+                let step = (self.stop - self.start) / (self.points - 1) as f64; 
+                (0..self.points)
+                    .map(|n| self.start + n as f64 * step)
+                    .collect()
+            },
+            SweepType::Decade => {
+                let decades = (self.stop / self.start).log10(); 
+                let count = (decades * self.points as f64 - 1e-9).ceil() as usize; 
+                (0..=count)
+                    .map(|n| (self.start * 10f64.powf(n as f64 / self.points as f64)).min(self.stop))
+                    .collect()
+            },
+        }
+    }
+}
+
+/// Small-signal stamp of the primitive: resistors are impedances, 
+/// capacitors and inductors have admittance s * C and impedance s * L. 
+/// The input source has unit amplitude, other sources are zero. 
+fn stamp_ac(system: &mut MnaSystem<Complex>, device: &Device, params: &[Complex], s: Complex, input: &str) {
+    let (a, b) = (&device.nodes[0], &device.nodes[1]); 
+    let amplitude = match device.name == input {
+        true => Complex::from(1.0), 
+        false => Complex::from(0.0), 
+    }; 
+
+    match device.element_name.as_str() {
+        primitive::RESISTOR => system.stamp_conductance(a, b, Complex::from(1.0) / params[0]), 
+        primitive::CAPACITOR => system.stamp_conductance(a, b, s * params[0]), 
+        primitive::INDUCTOR => {
+            system.stamp_voltage(&device.name, a, b, Complex::from(0.0)); 
+            system.stamp_branch_resistance(&device.name, s * params[0]); 
+        },
+        primitive::VOLTAGE_SOURCE => system.stamp_voltage(&device.name, a, b, amplitude), 
+        primitive::CURRENT_SOURCE => system.stamp_current(a, b, amplitude), 
+        _ => { }, 
+    }
+}
+
+/// Small-signal stamp of the nonlinear device: the conductance of the 
+/// diode and transconductances of the controlled source at the operating 
+/// point `op` 
+fn stamp_linearized(system: &mut MnaSystem<Complex>, netlist: &Netlist, device: &Device, op: &Solution) -> Result<(), Box<ErrorEntry>> {
+    let (a, b) = (&device.nodes[0], &device.nodes[1]); 
+    if device.element_name == primitive::DIODE {
+        let (_, g) = diode_current(&device.params, get_branch_voltage(op, device)); 
+        system.stamp_conductance(a, b, Complex::from(g)); 
+        return Result::Ok(()); 
+    }
+
+    let variables: HashMap<String, f64> = HashMap::new(); 
+    let state = PointState { variables: &variables, previous: Option::None }; 
+    let (_, gains) = linearize_controlled(netlist, device, state, op)?; 
+    for (index, g) in gains {
+        system.stamp_dependent_current(a, b, index, Complex::from(g)); 
+    }
+
+    Result::Ok(())
+}
+
+/// Name of the input source of the AC analysis: `input` if it's set, 
+/// otherwise the only independent source of the circuit 
+fn get_input(netlist: &Netlist, input: Option<&str>) -> Result<String, Box<ErrorEntry>> {
+    let sources: Vec<&str> = netlist.devices.iter()
+        .filter(|x| x.element_name == primitive::VOLTAGE_SOURCE || x.element_name == primitive::CURRENT_SOURCE)
+        .filter(|x| !x.is_controlled())
+        .map(|x| x.name.as_str())
+        .collect(); 
+
+    match input {
+        Option::Some(name) if sources.contains(&name) => Result::Ok(name.to_string()), 
+        Option::Some(name) => Result::Err(Box::new(ErrorEntry::global(
+            errors::UNDEFINED_INPUT, 
+            &vec![name.to_string()]))), 
+        Option::None if sources.len() == 1 => Result::Ok(sources[0].to_string()), 
+        Option::None => Result::Err(Box::new(ErrorEntry::global(
+            errors::AMBIGUOUS_INPUT, 
+            &vec![netlist.circuit_name.clone(), sources.len().to_string()]))), 
+    }
+}
+
+/// Small-signal analysis over the frequency sweep. The circuit is 
+/// linearised around the DC operating point, parameters of linear 
+/// primitives are evaluated in complex numbers with 'freq' and 's' 
+/// of every frequency. 
+/// The input source (`input` or the only source of the circuit) has 
+/// unit amplitude, other sources are turned off. 
+pub fn ac_analysis(netlist: &Netlist, sweep: &Sweep, input: Option<&str>, errs_acc: &mut Vec<ErrorEntry>) -> Option<Vec<(f64, Solution<Complex>)>> {
+    let input = match get_input(netlist, input) {
+        Result::Ok(input) => input, 
+        Result::Err(err) => {
+            errs_acc.push(*err); 
+            return Option::None; 
+        }
+    }; 
+
+    let op = operating_point(netlist, errs_acc)?; 
+    let branches = get_dc_branches(netlist); 
+
+    let mut points: Vec<(f64, Solution<Complex>)> = vec![]; 
+    for freq in sweep.get_frequencies() {
+        let s = Complex::new(0.0, 2.0 * std::f64::consts::PI * freq); 
+        let mut system: MnaSystem<Complex> = MnaSystem::new(netlist, branches.clone()); 
+        for device in &netlist.devices {
+            if device.element_name == primitive::DIODE || device.is_controlled() {
+                if let Result::Err(err) = stamp_linearized(&mut system, netlist, device, &op) {
+                    errs_acc.push(*err); 
+                    return Option::None; 
+                }
+
+                continue; 
+            }
+
+            let params = match netlist.evaluate_ac_params(device, freq) {
+                Result::Ok(params) => params, 
+                Result::Err(err) => {
+                    errs_acc.push(*err); 
+                    return Option::None; 
+                }
+            }; 
+
+            stamp_ac(&mut system, device, &params, s, &input); 
+        }
+
+        match system.solve() {
+            Option::Some(solution) => points.push((freq, solution)), 
+            Option::None => {
+                errs_acc.push(ErrorEntry::global(
+                    errors::SINGULAR_MATRIX_AT_FREQUENCY, 
+                    &vec![freq.to_string()])); 
+                return Option::None; 
+            }
+        }
+    }
+
+    Option::Some(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::simulation::get_test_netlist; 
+
+    const RC: &str = "
+circuit Main()
+{
+    V1[in, 0] = VoltageSource(time >= 0.001 ? 5 : 0); 
+    R1[in, out] = Resistor(1000); 
+    C1[out, 0] = Capacitor(0.000001); 
+}
+"; 
+
+    fn single_point(freq: f64) -> Sweep {
+        Sweep { sweep_type: SweepType::Linear, points: 1, start: freq, stop: freq }
+    }
+
+    #[test]
+    fn frequencies_of_sweeps() {
+        let linear = Sweep { sweep_type: SweepType::Linear, points: 3, start: 10.0, stop: 20.0 }; 
+        assert_eq!(linear.get_frequencies(), vec![10.0, 15.0, 20.0]); 
+
+        let decade = Sweep { sweep_type: SweepType::Decade, points: 2, start: 1.0, stop: 100.0 }; 
+        let frequencies = decade.get_frequencies(); 
+        assert_eq!(frequencies.len(), 5); 
+        assert!((frequencies[1] - 10f64.sqrt()).abs() < 1e-12); 
+        assert_eq!(frequencies[4], 100.0); 
+    }
+
+    #[test]
+    fn rc_corner_frequency() {
+        // The only source is the input with unit amplitude, whatever its DC value 
+        let netlist = get_test_netlist(RC); 
+        let corner = 1.0 / (2.0 * std::f64::consts::PI * 1000.0 * 1e-6); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let points = ac_analysis(&netlist, &single_point(corner), Option::None, &mut errs).unwrap(); 
+        assert!(errs.is_empty()); 
+
+        let output = points[0].1.get_voltage("out"); 
+        assert!((20.0 * output.abs().log10() + 10.0 * 2f64.log10()).abs() < 1e-9); 
+        assert!((output.arg().to_degrees() + 45.0).abs() < 1e-9); 
+        assert!((points[0].1.get_voltage("in").abs() - 1.0).abs() < 1e-12); 
+    }
+
+    #[test]
+    fn other_sources_are_turned_off() {
+        let netlist = get_test_netlist("
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(5); 
+    R1[1, 2] = Resistor(1000); 
+    R2[2, 0] = Resistor(1000); 
+    J1[0, 2] = CurrentSource(0.001); 
+}
+"); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let points = ac_analysis(&netlist, &single_point(1.0), Option::Some("J1"), &mut errs).unwrap(); 
+        assert!((points[0].1.get_voltage("2").re - 500.0).abs() < 1e-9); 
+        assert!(points[0].1.get_voltage("1").abs() < 1e-12); 
+
+        // Without the input the source is ambiguous 
+        assert!(ac_analysis(&netlist, &single_point(1.0), Option::None, &mut errs).is_none()); 
+        assert!(ac_analysis(&netlist, &single_point(1.0), Option::Some("R1"), &mut errs).is_none()); 
+        let messages: Vec<String> = errs.iter().map(|x| format!("{} {}", x.error.code, x.get_message())).collect(); 
+        assert_eq!(messages, vec![
+            "SIM009 Circuit 'Main' has 2 independent sources, choose the input of the AC analysis with '--input'.", 
+            "SIM006 Input 'R1' is not a source of the circuit.", 
+        ]); 
+    }
+}
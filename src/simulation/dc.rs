@@ -0,0 +1,188 @@
+use std::collections::HashMap; 
+use crate::elaborator::Device; 
+use crate::elaborator::Netlist; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::keywords::primitive; 
+use crate::simulation::MnaSystem; 
+use crate::simulation::Solution; 
+use crate::simulation::GROUND; 
+use crate::simulation::NonlinearProblem; 
+use crate::simulation::PointState; 
+use crate::simulation::evaluate_point_params; 
+use crate::simulation::solve_nonlinear; 
+
+/// Devices whose currents are unknowns of the DC system 
+pub fn get_dc_branches(netlist: &Netlist) -> Vec<String> {
+    netlist.devices.iter()
+        .filter(|x| x.element_name == primitive::VOLTAGE_SOURCE || x.element_name == primitive::INDUCTOR)
+        .map(|x| x.name.clone())
+        .collect()
+}
+
+/// Checks the netlist before the analysis: it should have a ground 
+/// node and valid values of primitive parameters 
+pub fn check_netlist(netlist: &Netlist, errs_acc: &mut Vec<ErrorEntry>) -> bool {
+    let errors_count = errs_acc.len(); 
+    if !netlist.get_nodes().iter().any(|x| x == GROUND) {
+        errs_acc.push(ErrorEntry::global(
+            errors::NO_GROUND, 
+            &vec![netlist.circuit_name.clone()])); 
+    }
+
+    for device in &netlist.devices {
+        let invalid_param = match device.element_name.as_str() {
+            primitive::RESISTOR | primitive::CAPACITOR | primitive::INDUCTOR | primitive::DIODE => device.params.iter().find(|x| **x <= 0.0), 
+            _ => Option::None, 
+        }; 
+
+        if let Option::Some(value) = invalid_param {
+            errs_acc.push(ErrorEntry::new(
+                errors::INVALID_PARAM_VALUE, 
+                &device.position, 
+                &vec![device.name.clone(), value.to_string()]).in_file(&device.file_name)); 
+        }
+    }
+
+    errs_acc.len() == errors_count
+}
+
+/// DC stamp of the linear primitive: capacitors are open circuits, 
+/// inductors are short circuits. Sources are scaled by `factor`. 
+pub(super) fn stamp_dc(system: &mut MnaSystem, device: &Device, params: &[f64], factor: f64) {
+    let (a, b) = (&device.nodes[0], &device.nodes[1]); 
+    match device.element_name.as_str() {
+        primitive::RESISTOR => system.stamp_conductance(a, b, 1.0 / params[0]), 
+        primitive::VOLTAGE_SOURCE => system.stamp_voltage(&device.name, a, b, factor * params[0]), 
+        primitive::CURRENT_SOURCE => system.stamp_current(a, b, factor * params[0]), 
+        primitive::INDUCTOR => system.stamp_voltage(&device.name, a, b, 0.0), 
+        _ => { }, 
+    }
+}
+
+/// Computes the DC operating point: node voltages and currents 
+/// of voltage sources and inductors 
+pub fn operating_point(netlist: &Netlist, errs_acc: &mut Vec<ErrorEntry>) -> Option<Solution> {
+    if !check_netlist(netlist, errs_acc) {
+        return Option::None; 
+    }
+
+    let variables: HashMap<String, f64> = HashMap::new(); 
+    let params = match evaluate_point_params(netlist, &variables) {
+        Result::Ok(params) => params, 
+        Result::Err(err) => {
+            errs_acc.push(*err); 
+            return Option::None; 
+        }
+    }; 
+
+    let problem = NonlinearProblem {
+        netlist, 
+        branches: get_dc_branches(netlist), 
+        params, 
+        state: PointState { variables: &variables, previous: Option::None }, 
+        stamp: &stamp_dc, 
+    }; 
+
+    let initial = MnaSystem::new(netlist, problem.branches.clone()).zero_solution(); 
+    match solve_nonlinear(&problem, &initial) {
+        Result::Ok(solution) => Option::Some(solution), 
+        Result::Err(err) => {
+            let singular = ErrorEntry::global(
+                errors::SINGULAR_MATRIX, 
+                &vec![]); 
+
+            errs_acc.push(err.into_error(netlist, singular)); 
+            Option::None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::simulation::get_test_netlist; 
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= 1e-9 * (1.0 + expected.abs()), "{} != {}", actual, expected); 
+    }
+
+    #[test]
+    fn divider_operating_point() {
+        let netlist = get_test_netlist("
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(12); 
+    R1[1, 2] = Resistor(1000); 
+    R2[2, 0] = Resistor(2000); 
+    L1[2, 3] = Inductor(0.001); 
+    C1[3, 0] = Capacitor(0.000001); 
+}
+"); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let solution = operating_point(&netlist, &mut errs).unwrap(); 
+        assert!(errs.is_empty()); 
+
+        // The capacitor is open and the inductor is shorted 
+        assert_close(solution.get_voltage("1"), 12.0); 
+        assert_close(solution.get_voltage("2"), 8.0); 
+        assert_close(solution.get_voltage("3"), 8.0); 
+        assert_close(solution.get_current("V1").unwrap(), -0.004); 
+        assert_close(solution.get_current("L1").unwrap(), 0.0); 
+    }
+
+    #[test]
+    fn current_source_operating_point() {
+        // 2 mA flow from the ground through J1 into node 1 
+        let netlist = get_test_netlist("
+circuit Main()
+{
+    J1[0, 1] = CurrentSource(0.002); 
+    R1[1, 2] = Resistor(1000); 
+    R2[2, 0] = Resistor(500); 
+    R3[2, 0] = Resistor(500); 
+}
+"); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let solution = operating_point(&netlist, &mut errs).unwrap(); 
+        assert_close(solution.get_voltage("2"), 0.5); 
+        assert_close(solution.get_voltage("1"), 2.5); 
+    }
+
+    #[test]
+    fn controlled_sources_read_quantities() {
+        // J2 turns the voltage of R2 into the current, J1 mirrors the current of R1 into R4 
+        let netlist = get_test_netlist("
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(10); 
+    R1[1, 2] = Resistor(1000); 
+    R2[2, 0] = Resistor(1000); 
+    J2[0, 3] = CurrentSource(0.002 * R2.U); 
+    R3[3, 0] = Resistor(1000); 
+    J1[0, 4] = CurrentSource(R1.I); 
+    R4[4, 0] = Resistor(100); 
+}
+"); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let solution = operating_point(&netlist, &mut errs).unwrap(); 
+        assert!(errs.is_empty()); 
+        assert_close(solution.get_voltage("2"), 5.0); 
+        assert_close(solution.get_voltage("3"), 10.0); 
+        assert_close(solution.get_voltage("4"), 0.5); 
+    }
+
+    #[test]
+    fn invalid_circuits_are_reported() {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let netlist = get_test_netlist("circuit Main() { V1[1, 2] = VoltageSource(1); R1[1, 2] = Resistor(0); }"); 
+        assert!(operating_point(&netlist, &mut errs).is_none()); 
+        let codes: Vec<&str> = errs.iter().map(|x| x.error.code).collect(); 
+        assert_eq!(codes, vec!["SIM001", "SIM002"]); 
+
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let netlist = get_test_netlist("circuit Main() { V1[1, 0] = VoltageSource(1); V2[1, 0] = VoltageSource(2); }"); 
+        assert!(operating_point(&netlist, &mut errs).is_none()); 
+        assert_eq!(errs[0].error.code, "SIM003"); 
+    }
+}
@@ -0,0 +1,101 @@
+use std::ops::Add; 
+use std::ops::Sub; 
+use std::ops::Mul; 
+use std::ops::Div; 
+use std::ops::Neg; 
+use crate::entities::Complex; 
+
+/// Element of the system matrix: real for DC and transient analyses, 
+/// complex for the AC analysis 
+pub trait Scalar: Copy + From<f64> 
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    /// Absolute value used to choose the pivot 
+    fn magnitude(&self) -> f64; 
+}
+
+impl Scalar for f64 {
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+
+impl Scalar for Complex {
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+
+/// Solves the dense linear system `matrix * x = rhs` by Gaussian elimination 
+/// with partial pivoting. Returns `None` if the matrix is singular. 
+pub fn solve_linear<T: Scalar>(matrix: &[Vec<T>], rhs: &[T]) -> Option<Vec<T>> {
+    let size = rhs.len(); 
+    let zero = T::from(0.0); 
+    let mut a = matrix.to_vec(); 
+    let mut b = rhs.to_vec(); 
+
+    for col in 0..size {
+        let pivot = (col..size)
+            .max_by(|&x, &y| a[x][col].magnitude().total_cmp(&a[y][col].magnitude()))?; 
+
+        if a[pivot][col].magnitude() < 1e-300 {
+            return Option::None; 
+        }
+
+        a.swap(col, pivot); 
+        b.swap(col, pivot); 
+
+        for row in (col + 1)..size {
+            let factor = a[row][col] / a[col][col]; 
+            if factor.magnitude() == 0.0 {
+                continue; 
+            }
+
+            let (upper, lower) = a.split_at_mut(row); 
+            for (x, y) in lower[0].iter_mut().zip(&upper[col]).skip(col) {
+                *x = *x - factor * *y; 
+            }
+
+            b[row] = b[row] - factor * b[col]; 
+        }
+    }
+
+    let mut x = vec![zero; size]; 
+    for row in (0..size).rev() {
+        let sum = ((row + 1)..size)
+            .fold(zero, |acc, k| acc + a[row][k] * x[k]); 
+
+        x[row] = (b[row] - sum) / a[row][row]; 
+    }
+
+    Option::Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+
+    #[test]
+    fn system_is_solved_with_pivoting() {
+        // The first pivot is zero, so the rows are swapped 
+        let matrix = vec![vec![0.0, 2.0, 1.0], vec![1.0, 1.0, 0.0], vec![2.0, 0.0, 3.0]]; 
+        let rhs = vec![7.0, 3.0, 11.0]; 
+        let x = solve_linear(&matrix, &rhs).unwrap(); 
+        let expected = [1.0, 2.0, 3.0]; 
+        for (value, expected) in x.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-12, "{:?}", x); 
+        }
+    }
+
+    #[test]
+    fn singular_system_has_no_solution() {
+        let matrix = vec![vec![1.0, 2.0], vec![2.0, 4.0]]; 
+        assert!(solve_linear(&matrix, &vec![1.0, 2.0]).is_none()); 
+    }
+
+    #[test]
+    fn complex_system_is_solved() {
+        // (1 + j) * x = 2 
+        let x = solve_linear(&vec![vec![Complex::new(1.0, 1.0)]], &vec![Complex::from(2.0)]).unwrap(); 
+        assert!((x[0].re - 1.0).abs() < 1e-12 && (x[0].im + 1.0).abs() < 1e-12); 
+    }
+}
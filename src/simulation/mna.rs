@@ -0,0 +1,254 @@
+use crate::elaborator::Netlist; 
+use crate::simulation::solve_linear; 
+use crate::simulation::Scalar; 
+
+/// Name of the ground node of the top-level circuit, node '0' of an 
+/// element is an internal node of its instance 
+pub const GROUND: &str = "0"; 
+
+/// Modified nodal analysis system: one row per node voltage (ground 
+/// excluded) and one row per branch current of voltage-defined devices. 
+#[derive(Clone)]
+pub struct MnaSystem<T: Scalar = f64> {
+    pub nodes: Vec<String>, 
+    pub branches: Vec<String>, 
+    pub matrix: Vec<Vec<T>>, 
+    pub rhs: Vec<T>, 
+}
+
+/// Node voltages and branch currents of the solved system 
+#[derive(Clone)]
+pub struct Solution<T: Scalar = f64> {
+    pub node_voltages: Vec<(String, T)>, 
+    pub branch_currents: Vec<(String, T)>, 
+}
+
+impl<T: Scalar> Solution<T> {
+    /// Voltage of the node, ground and unknown nodes have zero voltage 
+    pub fn get_voltage(&self, node: &str) -> T {
+        self.node_voltages.iter()
+            .find(|x| x.0 == node)
+            .map(|x| x.1)
+            .unwrap_or(T::from(0.0))
+    }
+
+    pub fn get_current(&self, branch: &str) -> Option<T> {
+        self.branch_currents.iter()
+            .find(|x| x.0 == branch)
+            .map(|x| x.1)
+    }
+
+    /// Amount of unknowns of the system 
+    pub fn size(&self) -> usize {
+        self.node_voltages.len() + self.branch_currents.len()
+    }
+
+    /// Unknown of the system by its index: node voltages go first, 
+    /// then branch currents 
+    pub fn get_unknown(&self, index: usize) -> T {
+        match index < self.node_voltages.len() {
+            true => self.node_voltages[index].1, 
+            false => self.branch_currents[index - self.node_voltages.len()].1, 
+        }
+    }
+
+    pub fn set_unknown(&mut self, index: usize, value: T) {
+        match index < self.node_voltages.len() {
+            true => self.node_voltages[index].1 = value, 
+            false => {
+                let index = index - self.node_voltages.len(); 
+                self.branch_currents[index].1 = value; 
+            },
+        }
+    }
+}
+
+impl<T: Scalar> MnaSystem<T> {
+    /// Creates the empty system for the netlist, `branches` are names 
+    /// of devices whose currents are unknowns of the system 
+    pub fn new(netlist: &Netlist, branches: Vec<String>) -> MnaSystem<T> {
+        let nodes: Vec<String> = netlist.get_nodes().into_iter()
+            .filter(|x| x != GROUND)
+            .collect(); 
+
+        let size = nodes.len() + branches.len(); 
+        MnaSystem {
+            nodes, 
+            branches, 
+            matrix: vec![vec![T::from(0.0); size]; size], 
+            rhs: vec![T::from(0.0); size], 
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.rhs.len()
+    }
+
+    /// Row of the node voltage, `None` for the ground 
+    pub fn node_index(&self, node: &str) -> Option<usize> {
+        self.nodes.iter().position(|x| x == node)
+    }
+
+    /// Row of the branch current 
+    pub fn branch_index(&self, branch: &str) -> Option<usize> {
+        self.branches.iter()
+            .position(|x| x == branch)
+            .map(|x| x + self.nodes.len())
+    }
+
+    fn add(&mut self, row: Option<usize>, col: Option<usize>, value: T) {
+        if let (Option::Some(row), Option::Some(col)) = (row, col) {
+            self.matrix[row][col] = self.matrix[row][col] + value; 
+        }
+    }
+
+    fn add_rhs(&mut self, row: Option<usize>, value: T) {
+        if let Option::Some(row) = row {
+            self.rhs[row] = self.rhs[row] + value; 
+        }
+    }
+
+    /// Conductance `g` between nodes `a` and `b` 
+    pub fn stamp_conductance(&mut self, a: &str, b: &str, g: T) {
+        let (a, b) = (self.node_index(a), self.node_index(b)); 
+        self.add(a, a, g); 
+        self.add(b, b, g); 
+        self.add(a, b, -g); 
+        self.add(b, a, -g); 
+    }
+
+    /// Current `i` flowing from node `a` through the device to node `b` 
+    pub fn stamp_current(&mut self, a: &str, b: &str, i: T) {
+        let (a, b) = (self.node_index(a), self.node_index(b)); 
+        self.add_rhs(a, -i); 
+        self.add_rhs(b, i); 
+    }
+
+    /// Current `g * x` flowing from node `a` through the device to node `b`, 
+    /// where `x` is the unknown with index `column` 
+    pub fn stamp_dependent_current(&mut self, a: &str, b: &str, column: usize, g: T) {
+        let (a, b) = (self.node_index(a), self.node_index(b)); 
+        self.add(a, Option::Some(column), g); 
+        self.add(b, Option::Some(column), -g); 
+    }
+
+    /// Voltage `u` between nodes `a` and `b`: V(a) - V(b) = u. The branch 
+    /// current flows from `a` through the device to `b`. 
+    pub fn stamp_voltage(&mut self, branch: &str, a: &str, b: &str, u: T) {
+        let k = self.branch_index(branch); 
+        let (a, b) = (self.node_index(a), self.node_index(b)); 
+        let one = T::from(1.0); 
+        self.add(a, k, one); 
+        self.add(b, k, -one); 
+        self.add(k, a, one); 
+        self.add(k, b, -one); 
+        self.add_rhs(k, u); 
+    }
+
+    /// Resistance `r` in series with the voltage-defined branch: 
+    /// V(a) - V(b) - r * I = u 
+    pub fn stamp_branch_resistance(&mut self, branch: &str, r: T) {
+        let k = self.branch_index(branch); 
+        self.add(k, k, -r); 
+    }
+
+    /// Solution with all unknowns equal to zero 
+    pub fn zero_solution(&self) -> Solution<T> {
+        self.to_solution(&vec![T::from(0.0); self.size()])
+    }
+
+    pub fn solve(&self) -> Option<Solution<T>> {
+        let x = solve_linear(&self.matrix, &self.rhs)?; 
+        Option::Some(self.to_solution(&x))
+    }
+
+    fn to_solution(&self, x: &[T]) -> Solution<T> {
+        let node_voltages = self.nodes.iter()
+            .cloned()
+            .zip(x.iter().cloned())
+            .collect(); 
+
+        let branch_currents = self.branches.iter()
+            .cloned()
+            .zip(x.iter().skip(self.nodes.len()).cloned())
+            .collect(); 
+
+        Solution {
+            node_voltages, 
+            branch_currents, 
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::simulation::get_test_netlist; 
+
+    fn get_system(branches: Vec<String>) -> MnaSystem {
+        let netlist = get_test_netlist("circuit Main() { R1[1, 2] = Resistor(1); R2[2, 0] = Resistor(1); }"); 
+        MnaSystem::new(&netlist, branches)
+    }
+
+    #[test]
+    fn ground_is_not_an_unknown() {
+        let system = get_system(vec![format!("V1")]); 
+        assert_eq!(system.nodes, vec!["1", "2"]); 
+        assert_eq!(system.size(), 3); 
+        assert_eq!(system.node_index(GROUND), Option::None); 
+        assert_eq!(system.branch_index("V1"), Option::Some(2)); 
+    }
+
+    #[test]
+    fn conductance_stamp() {
+        let mut system = get_system(vec![]); 
+        system.stamp_conductance("1", "2", 0.5); 
+        system.stamp_conductance("2", GROUND, 0.25); 
+        assert_eq!(system.matrix, vec![vec![0.5, -0.5], vec![-0.5, 0.75]]); 
+        assert_eq!(system.rhs, vec![0.0, 0.0]); 
+    }
+
+    #[test]
+    fn current_stamp() {
+        let mut system = get_system(vec![]); 
+        system.stamp_current("1", "2", 3.0); 
+        system.stamp_current(GROUND, "2", 1.0); 
+        assert_eq!(system.rhs, vec![-3.0, 4.0]); 
+        assert_eq!(system.matrix, vec![vec![0.0; 2]; 2]); 
+    }
+
+    #[test]
+    fn voltage_and_branch_resistance_stamps() {
+        let mut system = get_system(vec![format!("V1")]); 
+        system.stamp_voltage("V1", "1", GROUND, 5.0); 
+        system.stamp_branch_resistance("V1", 2.0); 
+        assert_eq!(system.matrix, vec![
+            vec![0.0, 0.0, 1.0], 
+            vec![0.0, 0.0, 0.0], 
+            vec![1.0, 0.0, -2.0], 
+        ]); 
+        assert_eq!(system.rhs, vec![0.0, 0.0, 5.0]); 
+    }
+
+    #[test]
+    fn dependent_current_stamp() {
+        let mut system = get_system(vec![]); 
+        system.stamp_dependent_current("1", "2", 1, 0.1); 
+        assert_eq!(system.matrix, vec![vec![0.0, 0.1], vec![0.0, -0.1]]); 
+    }
+
+    #[test]
+    fn divider_is_solved() {
+        // V1 = 10 V drives R1 = 1 kOhm and R2 = 3 kOhm in series 
+        let mut system = get_system(vec![format!("V1")]); 
+        system.stamp_voltage("V1", "1", GROUND, 10.0); 
+        system.stamp_conductance("1", "2", 1e-3); 
+        system.stamp_conductance("2", GROUND, 1.0 / 3000.0); 
+
+        let solution = system.solve().unwrap(); 
+        assert!((solution.get_voltage("1") - 10.0).abs() < 1e-12); 
+        assert!((solution.get_voltage("2") - 7.5).abs() < 1e-12); 
+        assert!((solution.get_current("V1").unwrap() + 2.5e-3).abs() < 1e-15); 
+        assert_eq!(solution.get_voltage(GROUND), 0.0); 
+    }
+}
@@ -0,0 +1,39 @@
+mod linear; 
+mod mna; 
+mod newton; 
+mod dc; 
+mod transient; 
+mod ac; 
+
+pub use linear::solve_linear; 
+pub use linear::Scalar; 
+pub use mna::MnaSystem; 
+pub use mna::Solution; 
+pub use mna::GROUND; 
+pub use newton::NonlinearProblem; 
+pub use newton::PointState; 
+pub use newton::NewtonError; 
+pub use newton::evaluate_point_params; 
+pub use newton::solve_nonlinear; 
+pub use newton::linearize_controlled; 
+pub use newton::get_branch_voltage; 
+pub use newton::diode_current; 
+pub use dc::operating_point;
+pub use dc::get_dc_branches; 
+pub use transient::transient; 
+pub use transient::Companion; 
+pub use transient::Method; 
+pub use transient::TimeSweep; 
+pub use ac::ac_analysis; 
+pub use ac::Sweep; 
+pub use ac::SweepType; 
+
+/// Netlist of the only circuit of the code for unit tests of the analyses 
+#[cfg(test)]
+pub(crate) fn get_test_netlist(code: &str) -> crate::elaborator::Netlist {
+    use crate::compiler::CompileOptions; 
+    match crate::compiler::compile(code, &CompileOptions::default()) {
+        Result::Ok(design) => design.netlist, 
+        Result::Err(errors) => panic!("{:?}", errors.iter().map(|x| x.get_message()).collect::<Vec<String>>()), 
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::HashMap; 
+use crate::elaborator::Device; 
+use crate::elaborator::Netlist; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::keywords::primitive; 
+use crate::keywords::member; 
+use crate::simulation::MnaSystem; 
+use crate::simulation::Solution; 
+use crate::simulation::GROUND; 
+use crate::simulation::Companion; 
+
+/// Thermal voltage kT/q at 300 K 
+pub const THERMAL_VOLTAGE: f64 = 0.025852; 
+
+const MAX_ITERATIONS: usize = 100; 
+const ABS_TOLERANCE: f64 = 1e-9; 
+const REL_TOLERANCE: f64 = 1e-6; 
+
+// Conductance in parallel with every junction, so the matrix 
+// isn't singular when the junction is reverse biased 
+const JUNCTION_GMIN: f64 = 1e-12; 
+
+/// Stamp of the linear device with evaluated parameters. The last 
+/// argument scales independent sources (used by source stepping). 
+pub type LinearStamp<'a> = dyn Fn(&mut MnaSystem, &Device, &[f64], f64) + 'a; 
+
+/// Value of the controlled source and pairs of the unknown index 
+/// and the nonzero derivative by it 
+pub type Linearization = (f64, Vec<(usize, f64)>); 
+
+pub enum NewtonError {
+    Singular, 
+    NoConvergence(usize, f64), // index of the device which doesn't settle and its last voltage change
+    Evaluation(Box<ErrorEntry>), 
+}
+
+impl NewtonError {
+    /// Converts the failure to the diagnostic, `singular` is 
+    /// reported for the singular matrix 
+    pub fn into_error(self, netlist: &Netlist, singular: ErrorEntry) -> ErrorEntry {
+        match self {
+            NewtonError::Singular => singular, 
+            NewtonError::NoConvergence(index, change) => {
+                let device = &netlist.devices[index]; 
+                ErrorEntry::new(
+                    errors::NO_CONVERGENCE, 
+                    &device.position, 
+                    &vec![device.name.clone(), format!("last change {:.3e} V", change)]).in_file(&device.file_name)
+            }, 
+            NewtonError::Evaluation(err) => *err, 
+        }
+    }
+}
+
+/// Values of the analysis point which quantities of instances depend on 
+#[derive(Clone, Copy)]
+pub struct PointState<'a> {
+    pub variables: &'a HashMap<String, f64>, // built-in variables at the point
+    pub previous: Option<Companion<'a>>, // integration from the previous time point, `None` in DC
+}
+
+/// Nonlinear system of one analysis point (e.g. a time point) 
+pub struct NonlinearProblem<'a> {
+    pub netlist: &'a Netlist, 
+    pub branches: Vec<String>, 
+    pub params: Vec<Vec<f64>>, // parameters of uncontrolled devices at the point
+    pub state: PointState<'a>, 
+    pub stamp: &'a LinearStamp<'a>, 
+}
+
+/// Evaluates parameters of all uncontrolled devices; parameters of 
+/// controlled ones depend on the solution and are left empty 
+pub fn evaluate_point_params(netlist: &Netlist, variables: &HashMap<String, f64>) -> Result<Vec<Vec<f64>>, Box<ErrorEntry>> {
+    netlist.devices.iter()
+        .map(|x| match x.is_controlled() {
+            true => Result::Ok(vec![]), 
+            false => netlist.evaluate_params(x, variables), 
+        })
+        .collect()
+}
+
+fn is_nonlinear(device: &Device) -> bool {
+    device.element_name == primitive::DIODE || device.is_controlled()
+}
+
+/// Voltage between the first and the second node of the device 
+pub fn get_branch_voltage(solution: &Solution, device: &Device) -> f64 {
+    solution.get_voltage(&device.nodes[0]) - solution.get_voltage(&device.nodes[1])
+}
+
+/// Current and conductance of the diode with parameters (IS, N) at voltage `u` 
+pub fn diode_current(params: &[f64], u: f64) -> (f64, f64) {
+    let vt = params[1] * THERMAL_VOLTAGE; 
+    let e = (u / vt).exp(); 
+    (params[0] * (e - 1.0), params[0] / vt * e)
+}
+
+/// Limits the change of the junction voltage between iterations, 
+/// so the exponent doesn't overflow (as `pnjlim` of SPICE) 
+fn limit_junction(new: f64, old: f64, params: &[f64]) -> f64 {
+    let vt = params[1] * THERMAL_VOLTAGE; 
+    let critical = vt * (vt / (std::f64::consts::SQRT_2 * params[0])).ln(); 
+    if new <= critical || (new - old).abs() <= 2.0 * vt {
+        return new; 
+    }
+
+    match old > 0.0 {
+        true => {
+            let arg = 1.0 + (new - old) / vt; 
+            match arg > 0.0 {
+                true => old + vt * arg.ln(), 
+                false => critical, 
+            }
+        }, 
+        false => vt * (new / vt).ln(), 
+    }
+}
+
+/// Current flowing from the first node through the primitive 
+/// to the second one 
+fn get_current(netlist: &Netlist, device: &Device, state: PointState, solution: &Solution) -> Result<f64, Box<ErrorEntry>> {
+    if device.is_controlled() {
+        return evaluate_controlled(netlist, device, state, solution); 
+    }
+
+    let params = netlist.evaluate_params(device, state.variables)?; 
+    let u = get_branch_voltage(solution, device); 
+    let current = match device.element_name.as_str() {
+        primitive::RESISTOR => u / params[0], 
+        primitive::CAPACITOR => match state.previous {
+            Option::Some(companion) => {
+                let (g, i0) = companion.capacitor(device, params[0]); 
+                g * u + i0
+            },
+            Option::None => 0.0, 
+        },
+        primitive::CURRENT_SOURCE => params[0], 
+        primitive::DIODE => diode_current(&params, u).0, 
+        _ => solution.get_current(&device.name).unwrap_or(0.0), 
+    }; 
+
+    Result::Ok(current)
+}
+
+/// Value of the quantity by its global name, e.g. 'K1.JK.U' 
+fn get_quantity(netlist: &Netlist, name: &str, state: PointState, solution: &Solution) -> Result<f64, Box<ErrorEntry>> {
+    let (instance, member) = name.rsplit_once('.').unwrap_or(("", name)); 
+    let device = netlist.get_device(instance).unwrap(); 
+    let u = get_branch_voltage(solution, device); 
+    match member {
+        member::VOLTAGE => Result::Ok(u), 
+        member::CURRENT => get_current(netlist, device, state, solution), 
+        _ => Result::Ok(u * get_current(netlist, device, state, solution)?), 
+    }
+}
+
+/// Value of the controlled current source at the solution 
+fn evaluate_controlled(netlist: &Netlist, device: &Device, state: PointState, solution: &Solution) -> Result<f64, Box<ErrorEntry>> {
+    let mut inputs = state.variables.clone(); 
+    for name in device.get_controls() {
+        let value = get_quantity(netlist, &name, state, solution)?; 
+        inputs.insert(name, value); 
+    }
+
+    Result::Ok(netlist.evaluate_params(device, &inputs)?[0])
+}
+
+/// Value of the controlled current source and its numeric derivatives 
+/// by the unknowns of the system at the solution 
+pub fn linearize_controlled(netlist: &Netlist, device: &Device, state: PointState, solution: &Solution) -> Result<Linearization, Box<ErrorEntry>> {
+    let value = evaluate_controlled(netlist, device, state, solution)?; 
+    let mut gains: Vec<(usize, f64)> = vec![]; 
+    for index in 0..solution.size() {
+        let x = solution.get_unknown(index); 
+        let h = 1e-6 * (1.0 + x.abs()); 
+        let mut perturbed = solution.clone(); 
+        for delta in [h, -h] {
+            perturbed.set_unknown(index, x + delta); 
+            if let Result::Ok(perturbed_value) = evaluate_controlled(netlist, device, state, &perturbed) {
+                let gain = (perturbed_value - value) / delta; 
+                if gain != 0.0 {
+                    gains.push((index, gain)); 
+                }
+
+                break; 
+            }
+        }
+    }
+
+    Result::Ok((value, gains))
+}
+
+/// Newton-Raphson iteration from the `initial` solution. `gmin` is 
+/// the conductance from every node to the ground, `factor` scales 
+/// independent sources. 
+fn iterate(problem: &NonlinearProblem, initial: &Solution, gmin: f64, factor: f64) -> Result<Solution, NewtonError> {
+    let netlist = problem.netlist; 
+    let nonlinear = netlist.devices.iter().any(is_nonlinear); 
+    let mut junctions: HashMap<usize, f64> = netlist.devices.iter()
+        .enumerate()
+        .filter(|(_, x)| x.element_name == primitive::DIODE)
+        .map(|(index, x)| (index, get_branch_voltage(initial, x)))
+        .collect(); 
+
+    let mut solution = initial.clone(); 
+    let mut worst: (usize, f64) = (0, 0.0); 
+    for _ in 0..MAX_ITERATIONS {
+        let mut system = MnaSystem::new(netlist, problem.branches.clone()); 
+        let mut is_limited = false; 
+        for (index, device) in netlist.devices.iter().enumerate() {
+            let (a, b) = (&device.nodes[0], &device.nodes[1]); 
+            let params = &problem.params[index]; 
+            if device.element_name == primitive::DIODE {
+                let new = get_branch_voltage(&solution, device); 
+                let u = limit_junction(new, junctions[&index], params); 
+                is_limited |= u != new; 
+                junctions.insert(index, u); 
+
+                let (i, g) = diode_current(params, u); 
+                system.stamp_conductance(a, b, g + JUNCTION_GMIN); 
+                system.stamp_current(a, b, i - g * u); 
+            }
+            else if device.is_controlled() {
+                let (i, gains) = linearize_controlled(netlist, device, problem.state, &solution)
+                    .map_err(NewtonError::Evaluation)?; 
+
+                system.stamp_current(a, b, i); 
+                for (index, g) in gains {
+                    system.stamp_dependent_current(a, b, index, g); 
+                    system.stamp_current(a, b, -g * solution.get_unknown(index)); 
+                }
+            }
+            else {
+                (problem.stamp)(&mut system, device, params, factor); 
+            }
+        }
+
+        if gmin > 0.0 {
+            for node in system.nodes.clone() {
+                system.stamp_conductance(&node, GROUND, gmin); 
+            }
+        }
+
+        let next = system.solve().ok_or(NewtonError::Singular)?; 
+        if !nonlinear {
+            return Result::Ok(next); 
+        }
+
+        let is_settled = |x: f64, y: f64| (x - y).abs() <= ABS_TOLERANCE + REL_TOLERANCE * x.abs().max(y.abs()); 
+        let is_converged = !is_limited
+            && next.node_voltages.iter().zip(&solution.node_voltages).all(|(x, y)| is_settled(x.1, y.1))
+            && next.branch_currents.iter().zip(&solution.branch_currents).all(|(x, y)| is_settled(x.1, y.1)); 
+
+        // Junctions are compared with their limited voltages 
+        worst = (0, 0.0); 
+        for (index, device) in netlist.devices.iter().enumerate().filter(|(_, x)| is_nonlinear(x)) {
+            let prev = match junctions.get(&index) {
+                Option::Some(u) => *u, 
+                Option::None => get_branch_voltage(&solution, device), 
+            }; 
+
+            let change = (get_branch_voltage(&next, device) - prev).abs(); 
+            if change > worst.1 {
+                worst = (index, change); 
+            }
+        }
+
+        solution = next; 
+        if is_converged {
+            return Result::Ok(solution); 
+        }
+    }
+
+    Result::Err(NewtonError::NoConvergence(worst.0, worst.1))
+}
+
+/// Solves the nonlinear system. If the plain iteration doesn't converge 
+/// (or overshoots into the singular matrix), gmin stepping and then 
+/// source stepping are tried. 
+pub fn solve_nonlinear(problem: &NonlinearProblem, initial: &Solution) -> Result<Solution, NewtonError> {
+    let error = match iterate(problem, initial, 0.0, 1.0) {
+        Result::Err(NewtonError::NoConvergence(index, change)) => NewtonError::NoConvergence(index, change), 
+        Result::Err(NewtonError::Singular) => NewtonError::Singular, 
+        result => return result, 
+    }; 
+
+    // gmin stepping: large conductances to the ground make the system 
+    // almost linear, then they are decreased step by step 
+    let mut guess = initial.clone(); 
+    let mut gmin = 1e-2; 
+    while gmin > 1e-13 {
+        match iterate(problem, &guess, gmin, 1.0) {
+            Result::Ok(solution) => guess = solution, 
+            Result::Err(_) => break, 
+        }
+
+        gmin /= 10.0; 
+    }
+
+    if gmin <= 1e-13 {
+        if let Result::Ok(solution) = iterate(problem, &guess, 0.0, 1.0) {
+            return Result::Ok(solution); 
+        }
+    }
+
+    // Source stepping: sources are ramped up from zero 
+    let mut guess = MnaSystem::<f64>::new(problem.netlist, problem.branches.clone()).zero_solution(); 
+    for step in 0..=10 {
+        guess = match iterate(problem, &guess, 0.0, step as f64 / 10.0) {
+            Result::Ok(solution) => solution, 
+            Result::Err(NewtonError::NoConvergence(_, _)) => return Result::Err(error), 
+            Result::Err(err) => return Result::Err(err), 
+        }; 
+    }
+
+    Result::Ok(guess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::simulation::get_test_netlist; 
+    use crate::simulation::get_dc_branches; 
+    use crate::simulation::operating_point; 
+    use crate::simulation::dc::stamp_dc; 
+
+    const DIODE: [f64; 2] = [1e-14, 1.0]; 
+
+    /// Runs `check` on the DC problem of the circuit and the zero solution 
+    fn with_problem(code: &str, check: impl Fn(&NonlinearProblem, &Solution)) {
+        let netlist = get_test_netlist(code); 
+        let variables: HashMap<String, f64> = HashMap::new(); 
+        let params = evaluate_point_params(&netlist, &variables).ok().unwrap(); 
+        let branches = get_dc_branches(&netlist); 
+        let problem = NonlinearProblem {
+            netlist: &netlist, 
+            branches: branches.clone(), 
+            params, 
+            state: PointState { variables: &variables, previous: Option::None }, 
+            stamp: &stamp_dc, 
+        }; 
+
+        check(&problem, &MnaSystem::<f64>::new(&netlist, branches).zero_solution()); 
+    }
+
+    #[test]
+    fn diode_current_and_conductance() {
+        let (i, g) = diode_current(&DIODE.to_vec(), 0.6); 
+        let e = (0.6 / THERMAL_VOLTAGE).exp(); 
+        assert!((i - 1e-14 * (e - 1.0)).abs() < 1e-20); 
+        assert!((g - 1e-14 / THERMAL_VOLTAGE * e).abs() < 1e-18); 
+        assert_eq!(diode_current(&DIODE.to_vec(), 0.0).0, 0.0); 
+    }
+
+    #[test]
+    fn junction_voltage_is_limited() {
+        let params = DIODE.to_vec(); 
+
+        // Small changes and voltages below the critical one are kept 
+        assert_eq!(limit_junction(0.61, 0.6, &params), 0.61); 
+        assert_eq!(limit_junction(0.3, -5.0, &params), 0.3); 
+
+        // Large forward jumps grow logarithmically 
+        let limited = limit_junction(5.0, 0.6, &params); 
+        assert!(limited > 0.6 && limited < 0.6 + 10.0 * THERMAL_VOLTAGE, "{}", limited); 
+        let limited = limit_junction(50.0, 0.0, &params); 
+        assert!((limited - THERMAL_VOLTAGE * (50.0 / THERMAL_VOLTAGE).ln()).abs() < 1e-12); 
+    }
+
+    #[test]
+    fn diode_with_resistor() {
+        let code = "circuit Main() { V1[1, 0] = VoltageSource(5); R1[1, 2] = Resistor(1000); D1[2, 0] = Diode(1e-14, 1); }"; 
+        with_problem(code, |problem, zero| {
+            let solution = solve_nonlinear(problem, zero).ok().unwrap(); 
+            let u = solution.get_voltage("2"); 
+
+            // The currents of the resistor and the diode are equal 
+            let (i, _) = diode_current(&DIODE.to_vec(), u); 
+            assert!(((5.0 - u) / 1000.0 - i).abs() < 1e-9); 
+            assert!((u - 0.693).abs() < 5e-3, "{}", u); 
+        }); 
+    }
+
+    #[test]
+    fn gmin_and_source_factor_of_iteration() {
+        let code = "circuit Main() { V1[1, 0] = VoltageSource(10); R1[1, 2] = Resistor(1000); R2[2, 0] = Resistor(1000); }"; 
+        with_problem(code, |problem, zero| {
+            // 1 mS from every node to the ground: (10 - u) / 1k = u / 1k + u * 1m 
+            let solution = iterate(problem, zero, 1e-3, 1.0).ok().unwrap(); 
+            assert!((solution.get_voltage("2") - 10.0 / 3.0).abs() < 1e-12); 
+
+            // Sources are scaled by the factor 
+            let solution = iterate(problem, zero, 0.0, 0.5).ok().unwrap(); 
+            assert!((solution.get_voltage("1") - 5.0).abs() < 1e-12); 
+            assert!((solution.get_voltage("2") - 2.5).abs() < 1e-12); 
+        }); 
+    }
+
+    #[test]
+    fn stepping_converges_where_newton_fails() {
+        // Newton-Raphson overflows the exponent from the zero solution 
+        let code = "
+circuit Main()
+{
+    V1[1, 0] = VoltageSource(1000); 
+    R1[1, 2] = Resistor(1); 
+    R2[2, 0] = Resistor(1000); 
+    J1[2, 0] = CurrentSource(exp(R2.U)); 
+}
+"; 
+        with_problem(code, |problem, zero| {
+            assert!(iterate(problem, zero, 0.0, 1.0).is_err()); 
+
+            let solution = solve_nonlinear(problem, zero).ok().unwrap(); 
+            let u = solution.get_voltage("2"); 
+            assert!((1000.0 - u - u.exp() - u / 1000.0).abs() < 1e-6, "{}", u); 
+        }); 
+    }
+
+    #[test]
+    fn non_convergence_is_reported() {
+        // The source switches its direction at every iteration 
+        let netlist = get_test_netlist("
+circuit Main()
+{
+    R1[1, 0] = Resistor(1000); 
+    J1[0, 1] = CurrentSource(R1.U > 0 ? -0.001 : 0.001); 
+}
+"); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        assert!(operating_point(&netlist, &mut errs).is_none()); 
+        assert_eq!(errs.len(), 1); 
+        assert_eq!(errs[0].error.code, "SIM007"); 
+        assert_eq!(errs[0].get_message(), "Newton-Raphson iteration does not converge, instance 'J1' doesn't settle (last change 2.000e0 V)."); 
+        assert_eq!((errs[0].position.as_ref().unwrap().line, errs[0].position.as_ref().unwrap().line_position), (4, 4)); 
+    }
+}
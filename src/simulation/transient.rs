@@ -0,0 +1,434 @@
+use std::collections::HashMap; 
+use crate::elaborator::Device; 
+use crate::elaborator::Netlist; 
+use crate::entities::ErrorEntry; 
+use crate::entities::errors; 
+use crate::keywords::primitive; 
+use crate::keywords::variable; 
+use crate::simulation::MnaSystem; 
+use crate::simulation::Solution; 
+use crate::simulation::operating_point; 
+use crate::simulation::get_dc_branches; 
+use crate::simulation::get_branch_voltage; 
+use crate::simulation::NonlinearProblem; 
+use crate::simulation::NewtonError; 
+use crate::simulation::PointState; 
+use crate::simulation::evaluate_point_params; 
+use crate::simulation::solve_nonlinear; 
+
+// Tolerance of the local truncation error of capacitor voltages 
+// and inductor currents 
+const LTE_REL_TOLERANCE: f64 = 1e-3; 
+const LTE_ABS_TOLERANCE: f64 = 1e-6; 
+
+// The adaptive step starts from `step * INITIAL_STEP_RATIO` and 
+// the analysis fails if it gets below `step * MIN_STEP_RATIO` 
+const INITIAL_STEP_RATIO: f64 = 1e-2; 
+const MIN_STEP_RATIO: f64 = 1e-9; 
+
+/// Integration formula of capacitors and inductors 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Method {
+    BackwardEuler, // first order, damps oscillations
+    Trapezoidal, // second order, as in SPICE
+}
+
+impl Method {
+    fn order(&self) -> usize {
+        match self {
+            Method::BackwardEuler => 1, 
+            Method::Trapezoidal => 2, 
+        }
+    }
+
+    /// Local truncation error is `error_constant * h^(order + 1)` 
+    /// times the derivative of the order + 1 
+    fn error_constant(&self) -> f64 {
+        match self {
+            Method::BackwardEuler => 1.0 / 2.0, 
+            Method::Trapezoidal => 1.0 / 12.0, 
+        }
+    }
+}
+
+/// Time points of the transient analysis from 0 to `stop`. With 
+/// `adaptive` the step is chosen by the local truncation error and 
+/// `step` is its upper bound. 
+#[derive(Clone, Copy, Debug)]
+pub struct TimeSweep {
+    pub step: f64, 
+    pub stop: f64, 
+    pub method: Method, 
+    pub adaptive: bool, 
+}
+
+/// Accepted time point. The trapezoidal rule needs currents of 
+/// capacitors besides the solution. 
+struct TimePoint {
+    time: f64, 
+    solution: Solution, 
+    capacitor_currents: HashMap<String, f64>, 
+}
+
+/// Companion models of capacitors and inductors for the step from 
+/// the previous time point 
+#[derive(Clone, Copy)]
+pub struct Companion<'a> {
+    pub step: f64, 
+    pub method: Method, 
+    solution: &'a Solution, 
+    capacitor_currents: &'a HashMap<String, f64>, 
+}
+
+impl Companion<'_> {
+    /// Conductance `g` and current `i0` of the capacitor: i = g * u + i0 
+    pub fn capacitor(&self, device: &Device, capacitance: f64) -> (f64, f64) {
+        let prev_voltage = get_branch_voltage(self.solution, device); 
+        match self.method {
+            Method::BackwardEuler => {
+                let g = capacitance / self.step; 
+                (g, -g * prev_voltage)
+            }, 
+            Method::Trapezoidal => {
+                let g = 2.0 * capacitance / self.step; 
+                let prev_current = self.capacitor_currents.get(&device.name).copied().unwrap_or(0.0); 
+                (g, -g * prev_voltage - prev_current)
+            }, 
+        }
+    }
+
+    /// Resistance `r` and voltage `u0` of the inductor: u = r * i + u0 
+    pub fn inductor(&self, device: &Device, inductance: f64) -> (f64, f64) {
+        let prev_current = self.solution.get_current(&device.name).unwrap_or(0.0); 
+        match self.method {
+            Method::BackwardEuler => {
+                let r = inductance / self.step; 
+                (r, -r * prev_current)
+            }, 
+            Method::Trapezoidal => {
+                let r = 2.0 * inductance / self.step; 
+                (r, -r * prev_current - get_branch_voltage(self.solution, device))
+            }, 
+        }
+    }
+}
+
+/// Stamp of the primitive at the time point. Capacitors and inductors 
+/// are replaced by companion models: a conductance with a parallel 
+/// current source (capacitor) and a resistance with a series voltage 
+/// source (inductor). Sources are scaled by `factor`. 
+fn stamp_transient(system: &mut MnaSystem, device: &Device, params: &[f64], factor: f64, companion: &Companion) {
+    let (a, b) = (&device.nodes[0], &device.nodes[1]); 
+    match device.element_name.as_str() {
+        primitive::RESISTOR => system.stamp_conductance(a, b, 1.0 / params[0]), 
+        primitive::VOLTAGE_SOURCE => system.stamp_voltage(&device.name, a, b, factor * params[0]), 
+        primitive::CURRENT_SOURCE => system.stamp_current(a, b, factor * params[0]), 
+        primitive::CAPACITOR => {
+            let (g, i0) = companion.capacitor(device, params[0]); 
+            system.stamp_conductance(a, b, g); 
+            system.stamp_current(a, b, i0); 
+        }, 
+        primitive::INDUCTOR => {
+            let (r, u0) = companion.inductor(device, params[0]); 
+            system.stamp_voltage(&device.name, a, b, u0); 
+            system.stamp_branch_resistance(&device.name, r); 
+        }, 
+        _ => { }, 
+    }
+}
+
+/// Solves the circuit at `time` by the step from the previous point 
+fn solve_point(netlist: &Netlist, branches: &[String], prev: &TimePoint, time: f64, method: Method) -> Result<TimePoint, NewtonError> {
+    let variables: HashMap<String, f64> = HashMap::from([(variable::TIME.to_string(), time)]); 
+    let params = evaluate_point_params(netlist, &variables).map_err(NewtonError::Evaluation)?; 
+    let companion = Companion {
+        step: time - prev.time, 
+        method, 
+        solution: &prev.solution, 
+        capacitor_currents: &prev.capacitor_currents, 
+    }; 
+
+    let stamp = |system: &mut MnaSystem, device: &Device, params: &[f64], factor: f64| {
+        stamp_transient(system, device, params, factor, &companion)
+    }; 
+
+    let problem = NonlinearProblem {
+        netlist, 
+        branches: branches.to_vec(), 
+        params, 
+        state: PointState { variables: &variables, previous: Option::Some(companion) }, 
+        stamp: &stamp, 
+    }; 
+
+    let solution = solve_nonlinear(&problem, &prev.solution)?; 
+    let capacitor_currents = netlist.devices.iter()
+        .zip(&problem.params)
+        .filter(|(x, _)| x.element_name == primitive::CAPACITOR && !x.is_controlled())
+        .map(|(x, params)| {
+            let (g, i0) = companion.capacitor(x, params[0]); 
+            (x.name.clone(), g * get_branch_voltage(&solution, x) + i0)
+        })
+        .collect(); 
+
+    Result::Ok(TimePoint { time, solution, capacitor_currents })
+}
+
+/// Highest divided difference of the values at the times 
+fn divided_difference(times: &[f64], values: &[f64]) -> f64 {
+    let mut values = values.to_vec(); 
+    for order in 1..times.len() {
+        for i in 0..times.len() - order {
+            values[i] = (values[i + 1] - values[i]) / (times[i + order] - times[i]); 
+        }
+    }
+
+    values[0]
+}
+
+/// Ratio of the local truncation error of the new point to its 
+/// tolerance, the worst of all capacitor voltages and inductor currents. 
+/// The derivative of the error is estimated by the divided difference 
+/// of the last points, `None` if there are not enough of them. 
+fn get_error_ratio(netlist: &Netlist, points: &[TimePoint], new: &TimePoint, method: Method) -> Option<f64> {
+    let order = method.order(); 
+    if points.len() < order + 1 {
+        return Option::None; 
+    }
+
+    let history: Vec<&TimePoint> = points[points.len() - order - 1..].iter().chain([new]).collect(); 
+    let times: Vec<f64> = history.iter().map(|x| x.time).collect(); 
+    let step = new.time - points.last().unwrap().time; 
+    let factorial: f64 = (1..=order + 1).map(|x| x as f64).product(); 
+
+    let mut ratio: f64 = 0.0; 
+    for device in &netlist.devices {
+        let values: Vec<f64> = match device.element_name.as_str() {
+            primitive::CAPACITOR => history.iter().map(|x| get_branch_voltage(&x.solution, device)).collect(), 
+            primitive::INDUCTOR => history.iter().map(|x| x.solution.get_current(&device.name).unwrap_or(0.0)).collect(), 
+            _ => continue, 
+        }; 
+
+        let derivative = factorial * divided_difference(&times, &values); 
+        let error = method.error_constant() * step.powi(order as i32 + 1) * derivative.abs(); 
+        let scale = values[order + 1].abs().max(values[order].abs()); 
+        ratio = ratio.max(error / (LTE_REL_TOLERANCE * scale + LTE_ABS_TOLERANCE)); 
+    }
+
+    Option::Some(ratio)
+}
+
+/// Time-domain analysis from 0 to `stop`. The initial state is the 
+/// DC operating point, the built-in variable 'time' is set for every 
+/// time point, so sources may depend on it. The first step is always 
+/// backward Euler: currents of capacitors at the operating point don't 
+/// account for sources switched at 0+. 
+/// 
+/// The adaptive step is rejected and decreased if the local truncation 
+/// error is out of the tolerance or the Newton iteration fails, 
+/// otherwise the next step is increased up to the error bound. 
+pub fn transient(netlist: &Netlist, sweep: &TimeSweep, errs_acc: &mut Vec<ErrorEntry>) -> Option<Vec<(f64, Solution)>> {
+    let initial = operating_point(netlist, errs_acc)?; 
+    let branches = get_dc_branches(netlist); 
+    let steps_count = (sweep.stop / sweep.step - 1e-9).ceil() as usize; 
+    let min_step = sweep.step * MIN_STEP_RATIO; 
+
+    let mut points: Vec<TimePoint> = vec![TimePoint { time: 0.0, solution: initial, capacitor_currents: HashMap::new() }]; 
+    let mut step = match sweep.adaptive {
+        true => sweep.step * INITIAL_STEP_RATIO, 
+        false => sweep.step, 
+    }; 
+
+    while points.last().unwrap().time < sweep.stop && (sweep.adaptive || points.len() <= steps_count) {
+        let prev = points.last().unwrap(); 
+        let time = match sweep.adaptive {
+            true if sweep.stop - prev.time - step < min_step => sweep.stop, 
+            true => prev.time + step, 
+            false => (points.len() as f64 * sweep.step).min(sweep.stop), 
+        }; 
+
+        let method = match points.len() {
+            1 => Method::BackwardEuler, 
+            _ => sweep.method, 
+        }; 
+
+        let error = match solve_point(netlist, &branches, prev, time, method) {
+            Result::Ok(point) if !sweep.adaptive => {
+                points.push(point); 
+                continue; 
+            }, 
+            Result::Ok(point) => {
+                let ratio = get_error_ratio(netlist, &points, &point, method).unwrap_or(0.0); 
+                let factor = 0.9 * ratio.powf(-1.0 / (method.order() as f64 + 1.0)); 
+                if ratio <= 1.0 {
+                    step = (step * factor.min(2.0)).min(sweep.step); 
+                    points.push(point); 
+                    continue; 
+                }
+
+                step *= factor.max(0.25); 
+                Option::None
+            }, 
+            Result::Err(NewtonError::Evaluation(err)) => {
+                errs_acc.push(*err); 
+                return Option::None; 
+            }, 
+            Result::Err(err) if !sweep.adaptive => Option::Some(err), 
+            Result::Err(err) => {
+                step /= 8.0; 
+                Option::Some(err)
+            }, 
+        }; 
+
+        if sweep.adaptive && step >= min_step {
+            continue; 
+        }
+
+        let err = match error {
+            Option::Some(err) => {
+                let singular = ErrorEntry::global(
+                    errors::SINGULAR_MATRIX_AT_TIME, 
+                    &vec![time.to_string()]); 
+
+                err.into_error(netlist, singular)
+            }, 
+            Option::None => ErrorEntry::global(
+                errors::TIME_STEP_TOO_SMALL, 
+                &vec![time.to_string()]), 
+        }; 
+
+        errs_acc.push(err); 
+        return Option::None; 
+    }
+
+    Option::Some(points.into_iter().map(|x| (x.time, x.solution)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::simulation::get_test_netlist; 
+
+    // Step of 1 V at 0+ into R = 1 kOhm and C = 1 uF, tau = 1 ms 
+    const RC: &str = "
+circuit Main()
+{
+    V1[in, 0] = VoltageSource(time > 0 ? 1 : 0); 
+    R1[in, out] = Resistor(1000); 
+    C1[out, 0] = Capacitor(0.000001); 
+}
+"; 
+
+    // Series RLC driven by a step of 1 V: alpha = R / 2L = 10^4 1/s, 
+    // omega0 = 1 / sqrt(LC) = 31623 rad/s, so it rings at 3 * 10^4 rad/s 
+    const RLC: &str = "
+circuit Main()
+{
+    V1[in, 0] = VoltageSource(time > 0 ? 1 : 0); 
+    R1[in, a] = Resistor(20); 
+    L1[a, out] = Inductor(0.001); 
+    C1[out, 0] = Capacitor(0.000001); 
+}
+"; 
+
+    fn simulate(code: &str, step: f64, stop: f64, method: Method, adaptive: bool) -> Vec<(f64, Solution)> {
+        let netlist = get_test_netlist(code); 
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let sweep = TimeSweep { step, stop, method, adaptive }; 
+        let points = transient(&netlist, &sweep, &mut errs).unwrap(); 
+        assert!(errs.is_empty()); 
+        points
+    }
+
+    /// The worst deviation of the quantity from the analytic solution 
+    fn get_max_error(points: &[(f64, Solution)], quantity: impl Fn(&Solution) -> f64, exact: impl Fn(f64) -> f64) -> f64 {
+        points.iter()
+            .map(|(time, solution)| (quantity(solution) - exact(*time)).abs())
+            .fold(0.0, f64::max)
+    }
+
+    fn rc_voltage(time: f64) -> f64 {
+        1.0 - (-time / 1e-3).exp()
+    }
+
+    fn rlc_voltage(time: f64) -> f64 {
+        let (alpha, omega) = (1e4, 3e4); 
+        1.0 - (-alpha * time).exp() * ((omega * time).cos() + alpha / omega * (omega * time).sin())
+    }
+
+    fn rlc_current(time: f64) -> f64 {
+        let (alpha, omega) = (1e4, 3e4); 
+        (-alpha * time).exp() * (omega * time).sin() / (omega * 0.001)
+    }
+
+    #[test]
+    fn fixed_step_covers_the_interval() {
+        let points = simulate(RC, 1e-4, 1.05e-3, Method::Trapezoidal, false); 
+        let times: Vec<f64> = points.iter().map(|x| x.0).collect(); 
+        assert_eq!(times.len(), 12); 
+        assert!((times[1] - 1e-4).abs() < 1e-15); 
+        assert_eq!(*times.last().unwrap(), 1.05e-3); 
+    }
+
+    #[test]
+    fn rc_step_response() {
+        let voltage = |x: &Solution| x.get_voltage("out"); 
+        let euler = simulate(RC, 1e-5, 5e-3, Method::BackwardEuler, false); 
+        let trapezoidal = simulate(RC, 1e-5, 5e-3, Method::Trapezoidal, false); 
+
+        // Errors of the first and the second order by h / tau = 0.01 
+        let euler_error = get_max_error(&euler, voltage, rc_voltage); 
+        let trapezoidal_error = get_max_error(&trapezoidal, voltage, rc_voltage); 
+        assert!(euler_error < 5e-3 && euler_error > 1e-3, "{}", euler_error); 
+        assert!(trapezoidal_error < 1e-4, "{}", trapezoidal_error); 
+    }
+
+    #[test]
+    fn rlc_step_response() {
+        let points = simulate(RLC, 1e-6, 1e-3, Method::Trapezoidal, false); 
+        let voltage_error = get_max_error(&points, |x| x.get_voltage("out"), rlc_voltage); 
+        let current_error = get_max_error(&points, |x| x.get_current("L1").unwrap(), rlc_current); 
+        assert!(voltage_error < 2e-3, "{}", voltage_error); 
+        assert!(current_error < 1e-4, "{}", current_error); 
+
+        // Backward Euler damps the ringing 
+        let points = simulate(RLC, 1e-6, 1e-3, Method::BackwardEuler, false); 
+        assert!(get_max_error(&points, |x| x.get_voltage("out"), rlc_voltage) > 1e-2); 
+    }
+
+    #[test]
+    fn adaptive_step_follows_the_error() {
+        let points = simulate(RLC, 1e-4, 1e-3, Method::Trapezoidal, true); 
+        let voltage_error = get_max_error(&points, |x| x.get_voltage("out"), rlc_voltage); 
+        assert!(voltage_error < 1e-2, "{}", voltage_error); 
+        assert_eq!(points.last().unwrap().0, 1e-3); 
+
+        // Steps grow as the ringing decays, but never over the maximal one 
+        let steps: Vec<f64> = points.windows(2).map(|x| x[1].0 - x[0].0).collect(); 
+        assert!(steps.iter().all(|x| *x > 0.0 && *x <= 1e-4 * (1.0 + 1e-9))); 
+        assert!(steps[steps.len() - 2] > 10.0 * steps[1]); 
+    }
+
+    #[test]
+    fn adaptive_step_rejects_the_edge() {
+        // The step at 1 ms is passed by shrinking the step after rejections 
+        let code = RC.replace("time > 0", "time >= 0.001"); 
+        let points = simulate(&code, 1e-4, 3e-3, Method::Trapezoidal, true); 
+        let exact = |time: f64| match time >= 1e-3 {
+            true => rc_voltage(time - 1e-3), 
+            false => 0.0, 
+        }; 
+
+        let error = get_max_error(&points, |x| x.get_voltage("out"), exact); 
+        assert!(error < 1e-2, "{}", error); 
+        let edge = points.iter().position(|x| x.0 >= 1e-3).unwrap(); 
+        assert!(points[edge].0 - points[edge - 1].0 < 1e-6); 
+    }
+
+    #[test]
+    fn divided_difference_of_polynomial() {
+        // The third divided difference of t^3 is 1 at any points 
+        let times = [0.0, 0.5, 2.0, 3.0]; 
+        let values: Vec<f64> = times.iter().map(|x| x * x * x).collect(); 
+        assert!((divided_difference(&times, &values) - 1.0).abs() < 1e-12); 
+    }
+}
@@ -3,12 +3,18 @@ use crate::entities::Token;
 use crate::entities::Statement; 
 use crate::entities::TokenType; 
 use crate::entities::Position; 
+use crate::entities::Span; 
 use crate::entities::ErrorEntry; 
 use crate::entities::errors; 
+use crate::keywords::is_keyword; 
 
+/// Splits tokens into statements at ';', '{' and '}'. Blocks are 
+/// substatements of the statement before '{'. Unbalanced braces are 
+/// reported and the division goes on, so errors of the following 
+/// statements are found in the same run. 
 pub fn get_statements(tokens: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> Vec<Statement> {
     let (groups, separators) = divide_tokens(tokens, errs_acc); 
-    let braces_map = get_braces_map(&separators, errs_acc);
+    let braces_map = get_braces_map(&groups, &separators, errs_acc);
 
     get_statements_rec(&groups, &separators, &braces_map, 0, groups.len())
 }
@@ -44,6 +50,8 @@ fn get_statements_rec(groups: &Vec<Vec<Token>>,
             .map(|x| x.clone())
             .collect(); 
 
+        // A block ends with the separator of its last group 
+        let last_group = *map.get(&i).unwrap_or(&i); 
         let mut substatements: Vec<Statement> = Vec::new(); 
         if map.contains_key(&i) {
             let new_start = i + 1;
@@ -57,10 +65,12 @@ fn get_statements_rec(groups: &Vec<Vec<Token>>,
         }
 
         if tokens.len() > 0 || substatements.len() > 0 {
+            let span = get_span(&tokens, &substatements, &groups[last_group], &separators[last_group]); 
             let statement = Statement{
-                tokens: tokens,
-                comments: comments,
-                substatements: substatements,
+                tokens,
+                comments,
+                substatements,
+                span, 
             };
 
             statements.push(statement); 
@@ -70,12 +80,35 @@ fn get_statements_rec(groups: &Vec<Vec<Token>>,
     return statements;
 }
 
+/// Span from the first token of the statement up to its separator, 
+/// or up to the last token if the separator is missing 
+fn get_span(tokens: &[Token], substatements: &[Statement], last_group: &[Token], separator: &Token) -> Span {
+    let first = match (tokens.first(), substatements.first()) {
+        (Option::Some(token), _) => token.span.clone(), 
+        (Option::None, Option::Some(substatement)) => substatement.span.clone(), 
+        (Option::None, Option::None) => Span::default(), 
+    }; 
+
+    let last = match separator.token_type {
+        TokenType::Empty => last_group.last().map(|x| x.span.clone()).unwrap_or(first.clone()), 
+        _ => separator.span.clone(), 
+    }; 
+
+    first.to(&last)
+}
+
 fn divide_tokens(tokens: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> (Vec<Vec<Token>>, Vec<Token>) {
     let mut groups: Vec<Vec<Token>> = Vec::new(); 
     let mut separators: Vec<Token> = Vec::new(); 
     let mut curr_group: Vec<Token> = Vec::new(); 
 
     for token in tokens {
+        // Keywords start declarations, so the statement before 
+        // the keyword misses ';' 
+        if is_keyword_token(token) && curr_group.iter().any(|x| !matches!(x.token_type, TokenType::Comment)) {
+            push_unfinished(&mut groups, &mut separators, &mut curr_group, errs_acc); 
+        }
+
         if is_separator(token) {
             groups.push(curr_group.to_vec()); 
             separators.push(token.clone()); 
@@ -87,26 +120,41 @@ fn divide_tokens(tokens: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> (Vec<Ve
     }
 
     if curr_group.len() > 0 {
-        // If statement doesn't finish with separator, 
-        // throw an error
-        let last = curr_group.last().unwrap(); 
-        errs_acc.push(ErrorEntry::new(
-            errors::UNFINISHED_COMMAND, 
-            &last.position, 
-            &vec![]));
-
-        groups.push(curr_group); 
-        
-        separators.push(Token{
-            token_type: TokenType::Empty,
-            value: "".to_string(), // TODO: String.Empty
-            position: Position{line: 0, line_position: 0}
-        });
+        push_unfinished(&mut groups, &mut separators, &mut curr_group, errs_acc); 
     }
 
     (groups, separators)
 }
 
+/// Ends the group which doesn't finish with separator 
+/// and throws an error 
+fn push_unfinished(groups: &mut Vec<Vec<Token>>, 
+                   separators: &mut Vec<Token>, 
+                   curr_group: &mut Vec<Token>, 
+                   errs_acc: &mut Vec<ErrorEntry>) {
+
+    let last = curr_group.last().unwrap(); 
+    errs_acc.push(ErrorEntry::new(
+        errors::UNFINISHED_COMMAND, 
+        &last.position, 
+        &vec![]));
+
+    groups.push(curr_group.to_vec()); 
+    curr_group.clear(); 
+    
+    separators.push(Token{
+        token_type: TokenType::Empty,
+        unit: Option::None, 
+        value: "".to_string(), // TODO: String.Empty
+        position: Position{line: 0, line_position: 0}, 
+        span: Span::default(), 
+    });
+}
+
+fn is_keyword_token(token: &Token) -> bool {
+    matches!(token.token_type, TokenType::Word) && is_keyword(&token.value)
+}
+
 fn is_semicolon(token: &Token) -> bool {
     match token.token_type{
         TokenType::Symbol => token.value == ";",
@@ -132,12 +180,19 @@ fn is_separator(token: &Token) -> bool {
     is_semicolon(token) || is_opened_brace(token) || is_closed_brace(token)
 }
 
-fn get_braces_map(separators: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> HashMap<usize, usize> {
+/// Maps indexes of '{' separators to indexes of matching '}'. A block 
+/// which isn't closed before the next declaration (e.g. 'circuit') 
+/// or the end of the code ends right before it. 
+fn get_braces_map(groups: &[Vec<Token>], separators: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> HashMap<usize, usize> {
     let mut map: HashMap<usize, usize> = HashMap::new(); 
     let mut stack: Vec<usize> = Vec::new(); 
     let mut i = 0;
 
     for separator in separators {
+        if is_declaration(&groups[i]) {
+            close_blocks(&mut stack, separators, i, &mut map, errs_acc); 
+        }
+
         let ch = separator.clone().value;
         if ch == "{" {
             stack.push(i); 
@@ -159,7 +214,29 @@ fn get_braces_map(separators: &Vec<Token>, errs_acc: &mut Vec<ErrorEntry>) -> Ha
         i += 1; 
     }
 
+    close_blocks(&mut stack, separators, i, &mut map, errs_acc); 
     map
 }
 
+/// Reports opened blocks and ends them before the separator `end` 
+fn close_blocks(stack: &mut Vec<usize>, 
+                separators: &[Token], 
+                end: usize, 
+                map: &mut HashMap<usize, usize>, 
+                errs_acc: &mut Vec<ErrorEntry>) {
+
+    while let Option::Some(start) = stack.pop() {
+        errs_acc.push(ErrorEntry::new(errors::OPEN_CLOSED_BRACKETS, &separators[start].position, &vec![])
+            .with_help("close the block with '}'")); 
 
+        map.insert(start, end - 1); 
+    }
+}
+
+/// Declarations are top-level statements, e.g. 'circuit Main() {', 
+/// they are never inside a block 
+fn is_declaration(group: &[Token]) -> bool {
+    group.iter()
+        .find(|x| !matches!(x.token_type, TokenType::Comment))
+        .is_some_and(is_keyword_token)
+}
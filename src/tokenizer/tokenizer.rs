@@ -4,6 +4,7 @@ use crate::entities::CodeChar;
 use crate::entities::Token; 
 use crate::entities::TokenType; 
 use crate::entities::Position; 
+use crate::entities::Span; 
 use crate::interfaces::TokenRecognizer; 
 
 fn collect_code_chars(code: String) -> Vec<CodeChar> {
@@ -11,12 +12,12 @@ fn collect_code_chars(code: String) -> Vec<CodeChar> {
     let lines = lines_iter.collect::<Vec<&str>>(); 
 
     let mut code_chars: Vec<CodeChar> = Vec::new(); 
-    let mut i: usize = 0;
-    for line in lines 
+    let mut line_offset: usize = 0; 
+    for (i, line) in lines.into_iter().enumerate()
     {
+        // The space stands for '\n' at the end of the line 
         let text = format!("{} ", line);
-        let mut j: usize = 0; 
-        for ch in text.chars()
+        for (j, (offset, ch)) in text.char_indices().enumerate()
         {
             code_chars.push(CodeChar{
                 position: Position{
@@ -25,17 +26,38 @@ fn collect_code_chars(code: String) -> Vec<CodeChar> {
                 },
 
                 symbol: ch,
+                offset: line_offset + offset, 
             });
-
-            j += 1; 
         }
 
-        i += 1;
+        line_offset += text.len(); 
     }
 
     code_chars
 }
 
+/// Span of chars from `start` up to `next`. Spaces at the end, like the 
+/// one added after every line, are not a part of it. 
+fn get_span(chars: &[CodeChar], start: usize, next: usize) -> Span {
+    let mut last = next.min(chars.len()).max(start + 1) - 1; 
+    while last > start && chars[last].symbol.is_whitespace() {
+        last -= 1; 
+    }
+
+    let (first, last) = (&chars[start], &chars[last]); 
+    Span {
+        file_id: 0, 
+        start: first.offset, 
+        end: last.offset + last.symbol.len_utf8(), 
+        position: first.position.clone(), 
+        end_position: Position {
+            line: last.position.line, 
+            line_position: last.position.line_position + 1, 
+        }, 
+    }
+}
+
+/// Splits the code into tokens, every token gets its span in the file 0 
 pub fn tokenize(code: String, recognizers: &Vec<&dyn TokenRecognizer>, errs_acc: &mut Vec<ErrorEntry>) -> Vec<Token> {
     let code_chars = collect_code_chars(code);
     let code_chars = &code_chars; 
@@ -57,38 +79,48 @@ pub fn tokenize(code: String, recognizers: &Vec<&dyn TokenRecognizer>, errs_acc:
             continue 'outer; 
         }
 
-        let mut status = false; 
-        'inner: for recognizer in recognizers 
+        // The first recognized token wins 
+        for recognizer in recognizers 
         {
             let (token, next) = recognizer.recognize_token(code_chars, pos); 
             match token {
-                None => continue 'inner,
-                Some(x) => {
-                    status = true; 
+                None => continue,
+                Some(mut x) => {
+                    x.span = get_span(code_chars, pos, next); 
                     pos = next; 
+                    // Only malformed number literals are recognized as undefined tokens 
+                    if let TokenType::Undefined = x.token_type {
+                        errs_acc.push(ErrorEntry::new(
+                            errors::INVALID_NUMBER, 
+                            &x.position, 
+                            &vec![x.value.clone()])
+                            .with_help("write digits, an optional scale suffix (f, p, n, u, m, k, meg, g, t) and a unit (Ohm, F, H, V, A, W, s, Hz)")); 
+                    }
+
                     tokens.push(x);
+                    continue 'outer; 
                 }
             };
         }
 
-        if !status {
-            // If recognizers failed, throw the undefined 
-            // symbol error
-            if curr_char_data.symbol != '\r' { // TODO: Don't know is it good approach
-                errs_acc.push(ErrorEntry::new(
-                    errors::UNDEFINED_SYMBOL, 
-                    &curr_char_data.position, 
-                    &vec![curr_char_data.symbol.to_string()]));           
-                
-                tokens.push(Token{
-                    token_type: TokenType::Undefined, 
-                    position: curr_char_data.position.clone(),
-                    value: curr_char_data.symbol.to_string(), 
-                });
-            }
-
-            pos += 1;
+        // If recognizers failed, throw the undefined 
+        // symbol error
+        if curr_char_data.symbol != '\r' { // TODO: Don't know is it good approach
+            errs_acc.push(ErrorEntry::new(
+                errors::UNDEFINED_SYMBOL, 
+                &curr_char_data.position, 
+                &vec![curr_char_data.symbol.to_string()]));           
+            
+            tokens.push(Token{
+                token_type: TokenType::Undefined, 
+                unit: Option::None, 
+                position: curr_char_data.position.clone(),
+                value: curr_char_data.symbol.to_string(), 
+                span: get_span(code_chars, pos, pos + 1), 
+            });
         }
+
+        pos += 1;
     }
 
     tokens
@@ -1,5 +1,6 @@
 use crate::interfaces::TokenRecognizer; 
 use crate::entities::Token; 
+use crate::entities::Span; 
 use crate::entities::CodeChar; 
 use crate::entities::TokenType;
 
@@ -20,24 +21,37 @@ impl TokenRecognizer for BlockCommentTokenRecognizer
         let next_char = next_char_data.symbol; 
 
         if curr_char == '/' && next_char == '*' {
-            let string: String = chars.iter()
+            let symbols: Vec<char> = chars.iter()
                 .skip(position)
                 .map(|x| x.symbol)
                 .collect(); 
 
-            let addr = string.find("*/"); 
+            // Unclosed comment lasts until the end of the code 
+            let addr = symbols.windows(2).skip(2).position(|x| x == ['*', '/']); 
             let len = match addr {
-                None => string.len(), 
-                Some(ad) => ad + 2,
+                None => symbols.len(), 
+                Some(ad) => ad + 4,
             };
 
-            let comment_text: String = string.chars().take(len).collect();
-            let next_position = position + len + 1;
+            // The space which stands for the end of the line is '\n' again, 
+            // so lines of the comment can be told apart 
+            let comment_text: String = chars.iter()
+                .skip(position)
+                .take(len)
+                .zip(chars.iter().skip(position + 1).map(Option::Some).chain([Option::None]))
+                .map(|(x, next)| match next {
+                    Option::Some(next) if next.position.line != x.position.line => '\n', 
+                    _ => x.symbol, 
+                })
+                .collect();
+            let next_position = position + len;
 
             let token = Option::Some(Token{
                 position: curr_char_data.position,
                 value: comment_text,
                 token_type: TokenType::Comment,
+                unit: Option::None, 
+                span: Span::default(), 
             });
 
             (token, next_position)
@@ -1,5 +1,6 @@
 use crate::interfaces::TokenRecognizer; 
 use crate::entities::Token; 
+use crate::entities::Span; 
 use crate::entities::CodeChar; 
 use crate::entities::TokenType;
 
@@ -26,11 +27,14 @@ impl TokenRecognizer for LineCommentTokenRecognizer
                 .map(|x| x.symbol)
                 .collect(); 
             
-            let next_position = position + comment_text.len() + 1;
+            // The text ends with the space added after every line 
+            let next_position = position + comment_text.chars().count();
             let token = Option::Some(Token{
                 position: curr_char_data.position,
                 value: comment_text,
                 token_type: TokenType::Comment,
+                unit: Option::None, 
+                span: Span::default(), 
             });
 
             (token, next_position)
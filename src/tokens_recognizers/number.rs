@@ -1,7 +1,10 @@
 use crate::interfaces::TokenRecognizer; 
 use crate::entities::Token; 
+use crate::entities::Span; 
 use crate::entities::CodeChar; 
 use crate::entities::TokenType;
+use crate::keywords::SCALE_SUFFIXES; 
+use crate::keywords::is_unit; 
 
 pub struct NumberTokenRecognizer {}
 impl NumberTokenRecognizer {
@@ -12,6 +15,64 @@ impl NumberTokenRecognizer {
     fn is_initial_char(&self, ch: char) -> bool {
         ch.is_numeric()
     }
+
+    /// Splits the literal into the mantissa (digits with an optional 
+    /// exponent) and the suffix 
+    fn split_suffix<'a>(&self, text: &'a str) -> (&'a str, &'a str) {
+        let bytes = text.as_bytes(); 
+        let mut end = text.find(|x: char| !x.is_ascii_digit() && x != '.').unwrap_or(text.len()); 
+        if end < text.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+            let mut exponent_end = end + 1; 
+            if exponent_end < text.len() && (bytes[exponent_end] == b'-' || bytes[exponent_end] == b'+') {
+                exponent_end += 1; 
+            }
+
+            let digits = text[exponent_end..].find(|x: char| !x.is_ascii_digit()).unwrap_or(text.len() - exponent_end); 
+            if digits > 0 {
+                end = exponent_end + digits; 
+            }
+        }
+
+        text.split_at(end)
+    }
+
+    /// Normalised value and unit of the literal, e.g. '4.7kOhm' is 
+    /// ("4700", Some("Ohm")). `None` if the literal is malformed. 
+    /// Unlike SPICE the case matters: the suffix is first taken as 
+    /// a unit as written, only then as a scale in any case. 
+    fn parse_literal(&self, text: &str) -> Option<(String, Option<String>)> {
+        let (mantissa, suffix) = self.split_suffix(text); 
+        mantissa.parse::<f64>().ok()?; 
+        if suffix.is_empty() {
+            return Option::Some((mantissa.to_string(), Option::None)); 
+        }
+
+        // The whole suffix is the unit: '1F' is one farad, '1f' is 1e-15 
+        if is_unit(suffix) {
+            return Option::Some((mantissa.to_string(), Option::Some(suffix.to_string()))); 
+        }
+
+        let lower = suffix.to_ascii_lowercase(); 
+        let (scale, exponent) = SCALE_SUFFIXES.iter().find(|x| lower.starts_with(x.0))?; 
+        let unit = &suffix[scale.len()..]; 
+        if !unit.is_empty() && !is_unit(unit) {
+            return Option::None; 
+        }
+
+        // The exponent is added to the text, so '10u' is exactly 1e-5 
+        let (base, base_exponent) = match mantissa.split_once(['e', 'E']) {
+            Option::Some((base, x)) => (base, x.parse::<i32>().ok()?), 
+            Option::None => (mantissa, 0), 
+        }; 
+
+        let value = format!("{}e{}", base, base_exponent + exponent).parse::<f64>().ok()?; 
+        let unit = match unit.is_empty() {
+            true => Option::None, 
+            false => Option::Some(unit.to_string()), 
+        }; 
+
+        Option::Some((value.to_string(), unit))
+    }
 }
 
 impl TokenRecognizer for NumberTokenRecognizer
@@ -32,7 +93,7 @@ impl TokenRecognizer for NumberTokenRecognizer
             .map(|x| x.symbol)
             .collect(); 
 
-        let len = result.len(); 
+        let len = result.chars().count(); 
         let next_position = position + len; 
         let extend: String = match chars.get(next_position) {
             Option::None => format!(""),
@@ -58,15 +119,158 @@ impl TokenRecognizer for NumberTokenRecognizer
         };
 
         result.push_str(&extend); 
-        let len = result.len(); 
+        let len = result.chars().count(); 
         let next_position = position + len; 
         
-        let token = Option::Some(Token{
-            value: result, 
-            position: curr_char_data.position,
-            token_type: TokenType::Number,
-        });
-        
-        (token, next_position)
+        // Malformed literals are undefined tokens, the tokenizer reports them 
+        let token = match self.parse_literal(&result) {
+            Option::Some((value, unit)) => Token {
+                value, 
+                position: curr_char_data.position, 
+                token_type: TokenType::Number, 
+                unit, 
+                span: Span::default(), 
+            }, 
+            Option::None => Token {
+                value: result, 
+                position: curr_char_data.position, 
+                token_type: TokenType::Undefined, 
+                unit: Option::None, 
+                span: Span::default(), 
+            }, 
+        }; 
+
+        (Option::Some(token), next_position)
     } 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::ErrorEntry; 
+    use crate::entities::Token; 
+    use crate::entities::TokenType; 
+    use crate::packages::get_token_recognizers; 
+    use crate::tokenizer::tokenize; 
+    use super::NumberTokenRecognizer; 
+
+    // Value and unit of the literal, `None` if it's malformed 
+    fn parse(text: &str) -> Option<(f64, Option<String>)> {
+        let (value, unit) = NumberTokenRecognizer{}.parse_literal(text)?; 
+        Option::Some((value.parse::<f64>().unwrap(), unit))
+    }
+
+    fn value(text: &str) -> f64 {
+        parse(text).unwrap().0
+    }
+
+    fn unit(text: &str) -> Option<String> {
+        parse(text).unwrap().1
+    }
+
+    fn get_tokens(code: &str) -> (Vec<Token>, Vec<ErrorEntry>) {
+        let mut errs: Vec<ErrorEntry> = vec![]; 
+        let tokens = tokenize(code.to_string(), &get_token_recognizers(), &mut errs); 
+        (tokens, errs)
+    }
+
+    #[test]
+    fn plain_numbers_are_kept() {
+        assert_eq!(parse("42"), Option::Some((42.0, Option::None))); 
+        assert_eq!(value("0.5"), 0.5); 
+        assert_eq!(value("1e3"), 1000.0); 
+        assert_eq!(value("2.5E-2"), 0.025); 
+    }
+
+    #[test]
+    fn scale_suffixes_are_applied() {
+        assert_eq!(value("1t"), 1e12); 
+        assert_eq!(value("1g"), 1e9); 
+        assert_eq!(value("1meg"), 1e6); 
+        assert_eq!(value("4.7k"), 4700.0); 
+        assert_eq!(value("1m"), 1e-3); 
+        assert_eq!(value("10u"), 1e-5); 
+        assert_eq!(value("3n"), 3e-9); 
+        assert_eq!(value("22p"), 22e-12); 
+        assert_eq!(value("1f"), 1e-15); 
+
+        // The exponent is added to the one of the mantissa 
+        assert_eq!(value("1e3k"), 1e6); 
+        assert_eq!(value("0.1e-3u"), 1e-10); 
+    }
+
+    #[test]
+    fn scale_suffixes_ignore_case() {
+        assert_eq!(value("4.7K"), 4700.0); 
+        assert_eq!(value("1MEG"), 1e6); 
+        assert_eq!(value("1Meg"), 1e6); 
+        assert_eq!(value("10U"), 1e-5); 
+
+        // 'M' is milli as in SPICE, mega is 'meg' 
+        assert_eq!(value("1M"), 1e-3); 
+        assert_eq!(parse("1MHz"), Option::Some((1e-3, Option::Some("Hz".to_string())))); 
+    }
+
+    #[test]
+    fn units_follow_scale_suffixes() {
+        assert_eq!(parse("4.7kOhm"), Option::Some((4700.0, Option::Some("Ohm".to_string())))); 
+        assert_eq!(parse("100nF"), Option::Some((100e-9, Option::Some("F".to_string())))); 
+        assert_eq!(parse("2.2e3Ohm"), Option::Some((2200.0, Option::Some("Ohm".to_string())))); 
+        assert_eq!(unit("10mH"), Option::Some("H".to_string())); 
+        assert_eq!(unit("5V"), Option::Some("V".to_string())); 
+        assert_eq!(unit("1megHz"), Option::Some("Hz".to_string())); 
+        assert_eq!(unit("1ms"), Option::Some("s".to_string())); 
+        assert_eq!(unit("2W"), Option::Some("W".to_string())); 
+    }
+
+    #[test]
+    fn units_are_case_sensitive() {
+        // The unit is matched as written before the scale 
+        assert_eq!(parse("1F"), Option::Some((1.0, Option::Some("F".to_string())))); 
+        assert_eq!(parse("1f"), Option::Some((1e-15, Option::None))); 
+        assert_eq!(parse("1fF"), Option::Some((1e-15, Option::Some("F".to_string())))); 
+        assert_eq!(parse("1A"), Option::Some((1.0, Option::Some("A".to_string())))); 
+        assert_eq!(parse("1mA"), Option::Some((1e-3, Option::Some("A".to_string())))); 
+
+        assert_eq!(parse("1ohm"), Option::None); 
+        assert_eq!(parse("1kOHM"), Option::None); 
+        assert_eq!(parse("1v"), Option::None); 
+        assert_eq!(parse("1hz"), Option::None); 
+    }
+
+    #[test]
+    fn malformed_literals_are_rejected() {
+        assert_eq!(parse("1x"), Option::None); 
+        assert_eq!(parse("1kx"), Option::None); 
+        assert_eq!(parse("1.2.3"), Option::None); 
+        assert_eq!(parse("1e"), Option::None); 
+        assert_eq!(parse("1_000"), Option::None); 
+    }
+
+    #[test]
+    fn tokens_carry_value_and_unit() {
+        let (tokens, errs) = get_tokens("R1 = 4.7kOhm * 1e-3s + 2E+2;"); 
+        assert!(errs.is_empty()); 
+
+        let numbers: Vec<(&str, Option<&str>)> = tokens.iter()
+            .filter(|x| matches!(x.token_type, TokenType::Number))
+            .map(|x| (x.value.as_str(), x.unit.as_deref()))
+            .collect(); 
+        assert_eq!(numbers, vec![("4700", Option::Some("Ohm")), ("1e-3", Option::Some("s")), ("2E+2", Option::None)]); 
+
+        // The span covers the literal as written 
+        assert_eq!(tokens[2].span.end - tokens[2].span.start, 7); 
+    }
+
+    #[test]
+    fn malformed_literal_is_stx019() {
+        let (tokens, errs) = get_tokens("x = 1x + 2.5.1 + 3kohm;"); 
+        let codes: Vec<(&str, usize)> = errs.iter().map(|x| (x.error.code, x.position.as_ref().unwrap().line_position)).collect(); 
+        assert_eq!(codes, vec![("STX019", 4), ("STX019", 9), ("STX019", 17)]); 
+        assert_eq!(errs[0].get_message(), "Invalid number '1x'."); 
+        assert!(errs[0].help.as_ref().unwrap().contains("scale suffix")); 
+
+        // The literal stays in the token stream as an undefined token 
+        assert!(matches!(tokens[2].token_type, TokenType::Undefined)); 
+        assert_eq!(tokens[2].value, "1x"); 
+    }
+}
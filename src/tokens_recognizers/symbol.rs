@@ -1,22 +1,25 @@
 use crate::interfaces::TokenRecognizer; 
 use crate::entities::Token; 
+use crate::entities::Span; 
 use crate::entities::CodeChar; 
 use crate::entities::TokenType;
 
 pub struct SymbolTokenRecognizer {}
-const COMBINED_OPERATORS: [&'static str; 5] = [
+const COMBINED_OPERATORS: [&str; 7] = [
     ">=",
     "<=",
     "==",
     "!=",
     "=>", 
+    "&&", 
+    "||", 
 ];
 
 impl TokenRecognizer for SymbolTokenRecognizer
 {
     fn recognize_token(&self, chars: &Vec<CodeChar>, position: usize) -> (Option<Token>, usize)
     {
-        let symbols = "{}[]();=,+-=*/<>!".to_string(); 
+        let symbols = "{}[]();=,+-=*/<>!^%?:&|".to_string(); 
         let char_data = chars[position].clone();
         let curr_char = char_data.symbol;
         let pos = char_data.position; 
@@ -28,6 +31,8 @@ impl TokenRecognizer for SymbolTokenRecognizer
                 position: pos.clone(),
                 value: curr_char.to_string(),
                 token_type: TokenType::Symbol,
+                unit: Option::None, 
+                span: Span::default(), 
             });
 
             match chars.get(next_pos) {
@@ -41,6 +46,8 @@ impl TokenRecognizer for SymbolTokenRecognizer
                                 position: pos.clone(),
                                 value: two_chars_str.clone(),
                                 token_type: TokenType::Symbol,
+                                unit: Option::None, 
+                                span: Span::default(), 
                             });
 
                             next_pos = position + 2; 
@@ -1,5 +1,6 @@
 use crate::interfaces::TokenRecognizer; 
 use crate::entities::Token; 
+use crate::entities::Span; 
 use crate::entities::CodeChar; 
 use crate::entities::TokenType;
 
@@ -32,7 +33,7 @@ impl TokenRecognizer for WordTokenRecognizer
             .map(|x| x.symbol)
             .collect(); 
 
-        let len = result.len(); 
+        let len = result.chars().count(); 
         let mut next_position = position + len; 
         let last = result.chars().last().unwrap(); 
         if last == '.' {
@@ -44,6 +45,8 @@ impl TokenRecognizer for WordTokenRecognizer
             value: result, 
             position: curr_char_data.position,
             token_type: TokenType::Word,
+            unit: Option::None, 
+            span: Span::default(), 
         });
         
         (token, next_position)
@@ -0,0 +1,31 @@
+use std::fs; 
+use edesigner::Command; 
+use edesigner::CompileOptions; 
+use edesigner::check; 
+
+#[test]
+fn spans_point_to_their_files() {
+    let file_name = "examples/divider.txt"; 
+    let options = CompileOptions { file_name: file_name.to_string(), ..CompileOptions::default() }; 
+    let program = check(&fs::read_to_string(file_name).unwrap(), &options).ok().unwrap(); 
+
+    // The main file and the standard package
+    assert!(program.files.len() > 1); 
+    assert_eq!(program.files[0].file_id, 0); 
+    for file in &program.files {
+        for command in &file.commands {
+            let span = match command {
+                Command::Circle(c) => &c.span, 
+                Command::Element(c) => &c.span, 
+                Command::Primitive(c) => &c.span, 
+                Command::Package(c) => &c.span, 
+                _ => continue, 
+            }; 
+
+            assert_eq!(span.file_id, file.file_id, "{}", file.file_name); 
+        }
+    }
+
+    let circuit = &program.table.circuits["Main"]; 
+    assert_eq!(circuit.docs, ["Voltage divider loaded by a sub-circuit"]); 
+}
@@ -0,0 +1,33 @@
+//! Helpers of the integration tests which run the binary. Every test 
+//! crate includes the module and uses only a part of it. 
+#![allow(dead_code)]
+
+use std::process::Command; 
+use std::process::Output; 
+
+/// Runs edesigner with the arguments 
+pub fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_edesigner"))
+        .args(args)
+        .output()
+        .expect("failed to run edesigner")
+}
+
+/// Returns stdout and stderr of the program 
+pub fn run_to_strings(args: &[&str]) -> (String, String) {
+    let output = run(args); 
+    (String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+}
+
+/// Codes and 1-based positions of diagnostics of the file printed as JSON lines
+pub fn get_diagnostics(file_name: &str) -> Vec<(String, usize, usize)> {
+    let (_, stderr) = run_to_strings(&[file_name, "--message-format", "json"]); 
+    let get_field = |line: &str, name: &str| -> String {
+        let value = line.split(&format!("\"{}\":", name)).nth(1).unwrap(); 
+        value.split(',').next().unwrap().trim_matches('"').to_string()
+    }; 
+
+    stderr.lines()
+        .map(|x| (get_field(x, "code"), get_field(x, "line").parse().unwrap(), get_field(x, "column").parse().unwrap()))
+        .collect()
+}
@@ -0,0 +1,90 @@
+mod common; 
+
+use common::run; 
+
+#[test]
+fn errors_are_printed_as_json_lines() {
+    let output = run(&["tests/diagnostics/duplicate.txt", "--message-format", "json"]); 
+    let stderr = String::from_utf8(output.stderr).unwrap(); 
+    let expected = concat!(
+        "{\"code\":\"SEM004\",\"severity\":\"error\",\"message\":\"Name 'Main' is defined multiple times.\",", 
+        "\"args\":[\"Main\"],\"file\":\"tests/diagnostics/duplicate.txt\",\"line\":7,\"column\":9,\"end_line\":null,\"end_column\":null,", 
+        "\"labels\":[{\"line\":1,\"column\":9,\"message\":\"first defined here\"}],\"help\":null}\n"); 
+
+    assert_eq!(stderr, expected); 
+    assert!(output.stdout.is_empty()); 
+    assert_eq!(output.status.code(), Option::Some(1)); 
+}
+
+#[test]
+fn warnings_are_printed_as_sarif_and_dont_fail() {
+    let output = run(&["tests/diagnostics/mismatch.txt", "--netlist", "--message-format", "sarif"]); 
+    let stdout = String::from_utf8(output.stdout).unwrap(); 
+    let sarif = String::from_utf8(output.stderr).unwrap(); 
+
+    // The netlist and the log are printed to different streams 
+    assert!(stdout.starts_with("Circuit Main"), "{}", stdout); 
+    assert!(!stdout.contains("\"version\""), "{}", stdout); 
+    assert_eq!(sarif.lines().count(), 1, "{}", sarif); 
+    assert!(sarif.starts_with("{\"version\":\"2.1.0\""), "{}", sarif); 
+    assert!(sarif.contains("\"rules\":[{\"id\":\"DIM003\"}]"), "{}", sarif); 
+    assert!(sarif.contains("\"level\":\"warning\""), "{}", sarif); 
+    assert!(sarif.contains("\"region\":{\"startLine\":4,\"startColumn\":25,\"endLine\":4,\"endColumn\":28}"), "{}", sarif); 
+    assert_eq!(output.status.code(), Option::Some(0)); 
+}
+
+#[test]
+fn source_snippet_is_rendered() {
+    let output = run(&["tests/diagnostics/duplicate.txt"]); 
+    let stderr = String::from_utf8(output.stderr).unwrap(); 
+    let expected = concat!(
+        "error[SEM004]: Name 'Main' is defined multiple times.\n", 
+        " --> tests/diagnostics/duplicate.txt:7:9\n", 
+        "  |\n", 
+        "1 | circuit Main()\n", 
+        "  |         ---- first defined here\n", 
+        ". |\n", 
+        "7 | circuit Main()\n", 
+        "  |         ^^^^\n", 
+        "\n"); 
+
+    assert_eq!(stderr, expected); 
+    assert_eq!(output.status.code(), Option::Some(1)); 
+}
+
+#[test]
+fn whole_instance_is_underlined() {
+    let output = run(&["tests/diagnostics/arity.txt"]); 
+    let stderr = String::from_utf8(output.stderr).unwrap(); 
+    let expected = concat!(
+        "error[SEM006]: Element 'Resistor' has 2 node(s), but 1 given.\n", 
+        " --> tests/diagnostics/arity.txt:4:5\n", 
+        "  |\n", 
+        "4 |     R1[1] = Resistor(10Ohm);\n", 
+        "  |     ^^^^^^^^^^^^^^^^^^^^^^^^\n", 
+        "\n"); 
+
+    assert_eq!(stderr, expected); 
+    assert_eq!(output.status.code(), Option::Some(1)); 
+}
+
+#[test]
+fn diagnostic_without_location_has_no_snippet() {
+    let output = run(&["tests/diagnostics/no_ground.txt", "--op"]); 
+    let stderr = String::from_utf8(output.stderr).unwrap(); 
+    let expected = concat!(
+        "error[SIM001]: Circuit 'Main' has no ground node '0'.\n", 
+        "  --> tests/diagnostics/no_ground.txt\n", 
+        "\n"); 
+
+    assert_eq!(stderr, expected); 
+    assert_eq!(output.status.code(), Option::Some(1)); 
+
+    let output = run(&["tests/diagnostics/no_ground.txt", "--op", "--message-format", "json"]); 
+    let stderr = String::from_utf8(output.stderr).unwrap(); 
+    assert!(stderr.contains("\"line\":null,\"column\":null,\"end_line\":null"), "{}", stderr); 
+
+    let output = run(&["tests/diagnostics/no_ground.txt", "--op", "--message-format", "sarif"]); 
+    let stderr = String::from_utf8(output.stderr).unwrap(); 
+    assert!(stderr.contains("{\"physicalLocation\":{\"artifactLocation\":{\"uri\":\"tests/diagnostics/no_ground.txt\"}}}"), "{}", stderr); 
+}
@@ -0,0 +1,68 @@
+use std::fs; 
+use edesigner::Command; 
+use edesigner::CompileOptions; 
+use edesigner::compile; 
+use edesigner::get_commands; 
+use edesigner::get_statements; 
+use edesigner::get_command_recognizers; 
+use edesigner::get_token_recognizers; 
+use edesigner::tokenize; 
+
+#[test]
+fn pipeline_splits_source_into_commands() {
+    let code = "function half(x) => x / 2; \ncircuit Main() { R1[1, 0] = Resistor(half(10)); }"; 
+    let mut errors = vec![]; 
+    let tokens = tokenize(code.to_string(), &get_token_recognizers(), &mut errors); 
+    let statements = get_statements(&tokens, &mut errors); 
+    let commands = get_commands(&statements, &get_command_recognizers(), &mut errors); 
+
+    assert!(errors.is_empty()); 
+    assert!(matches!(&commands[0], Command::Function(c) if c.name == "half")); 
+    assert!(matches!(&commands[1], Command::Circle(c) if c.name == "Main" && c.instances.len() == 1)); 
+}
+
+#[test]
+fn compile_elaborates_top_circuit() {
+    let file_name = "examples/divider.txt"; 
+    let options = CompileOptions { file_name: file_name.to_string(), ..CompileOptions::default() }; 
+    let design = compile(&fs::read_to_string(file_name).unwrap(), &options).ok().unwrap(); 
+    let names: Vec<&str> = design.netlist.devices.iter().map(|x| x.name.as_str()).collect(); 
+    assert_eq!(design.netlist.circuit_name, "Main"); 
+    assert_eq!(names, ["V1", "R1", "K1.R1", "K1.R2"]); 
+}
+
+#[test]
+fn compile_returns_diagnostics_of_errors() {
+    let options = CompileOptions { file_name: "test.txt".to_string(), ..CompileOptions::default() }; 
+    let diagnostics = match compile("circuit Main() { R1[1, 0] = Resistor(1x); }", &options) {
+        Result::Ok(_) => panic!("the source should not compile"), 
+        Result::Err(diagnostics) => diagnostics, 
+    }; 
+
+    assert_eq!(diagnostics.len(), 1); 
+    assert_eq!(diagnostics[0].error.code, "STX019"); 
+    assert!(diagnostics[0].is_error()); 
+}
+
+#[test]
+fn compile_finds_packages_in_include_dirs() {
+    let dir = std::env::temp_dir().join(format!("edesigner-library-{}-include", std::process::id())); 
+    fs::create_dir_all(dir.join("parts")).unwrap(); 
+    fs::write(dir.join("parts/load.ed"), "package parts.load; \nelement [P, N] Load(R) { R1[P, N] = Resistor(R); }").unwrap(); 
+
+    let code = "import parts.load; \ncircuit Main() { V1[1, 0] = VoltageSource(1); K1[1, 0] = parts.load.Load(10); }"; 
+    let options = CompileOptions { file_name: "main.txt".to_string(), ..CompileOptions::default() }; 
+    let diagnostics = compile(code, &options).err().unwrap(); 
+    assert_eq!(diagnostics[0].error.code, "PKG001"); 
+
+    let options = CompileOptions {
+        file_name: "main.txt".to_string(), 
+        include_dirs: vec![dir.display().to_string()], 
+        ..CompileOptions::default()
+    }; 
+
+    let design = compile(code, &options).ok().unwrap(); 
+    let names: Vec<&str> = design.netlist.devices.iter().map(|x| x.name.as_str()).collect(); 
+    assert_eq!(names, ["V1", "K1.R1"]); 
+    fs::remove_dir_all(&dir).unwrap(); 
+}
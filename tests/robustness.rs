@@ -0,0 +1,104 @@
+use edesigner::compile; 
+use edesigner::get_command_recognizers; 
+use edesigner::get_commands; 
+use edesigner::get_statements; 
+use edesigner::get_token_recognizers; 
+use edesigner::tokenize; 
+use edesigner::CompileOptions; 
+use edesigner::Diagnostic; 
+use std::fs; 
+use std::panic; 
+
+mod common; 
+
+use common::get_diagnostics; 
+
+/// Pieces of the syntax the random programs are built of
+const VOCABULARY: [&str; 39] = [
+    "circuit", "element", "function", "primitive", "package", "import", 
+    "Main", "R1", "Resistor", "x", "U", "1", "2.5", "10k", "1x", 
+    "[", "]", "(", ")", "{", "}", ";", ",", "=", "=>", 
+    "+", "-", "*", "/", "^", "?", ":", ".", "\n", "// comment\n", "/*", 
+    "@", "\t", "Ω", 
+]; 
+
+const PROGRAMS_COUNT: usize = 1000; 
+
+/// Linear congruential generator, so failures are reproducible
+struct Random {
+    state: u64, 
+}
+
+impl Random {
+    fn next(&mut self, bound: usize) -> usize {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); 
+        ((self.state >> 33) as usize) % bound
+    }
+}
+
+fn get_program(random: &mut Random) -> String {
+    let length = random.next(40); 
+    (0..length)
+        .map(|_| VOCABULARY[random.next(VOCABULARY.len())])
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Removes one random line of the valid program
+fn get_mutation(code: &str, random: &mut Random) -> String {
+    let lines: Vec<&str> = code.lines().collect(); 
+    let skipped = random.next(lines.len()); 
+    lines.iter()
+        .enumerate()
+        .filter(|x| x.0 != skipped)
+        .map(|x| *x.1)
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// The front end reports errors of arbitrary programs instead of 
+/// panicking: every stage is called directly, then the whole `compile` 
+fn check_no_panic(code: &str) {
+    let result = panic::catch_unwind(|| {
+        let mut errs: Vec<Diagnostic> = vec![]; 
+        let tokens = tokenize(code.to_string(), &get_token_recognizers(), &mut errs); 
+        let statements = get_statements(&tokens, &mut errs); 
+        get_commands(&statements, &get_command_recognizers(), &mut errs); 
+        let _ = compile(code, &CompileOptions::default()); 
+    }); 
+
+    assert!(result.is_ok(), "program panicked:\n{}", code); 
+}
+
+#[test]
+fn random_token_sequences_dont_panic() {
+    let mut random = Random { state: 2021 }; 
+    for _ in 0..PROGRAMS_COUNT {
+        check_no_panic(&get_program(&mut random)); 
+    }
+}
+
+#[test]
+fn programs_without_a_line_dont_panic() {
+    let mut random = Random { state: 2022 }; 
+    for example in ["examples/divider.txt", "examples/rc.txt", "examples/rectifier.txt", "examples/code.txt"] {
+        let code = fs::read_to_string(example).unwrap(); 
+        for _ in 0..PROGRAMS_COUNT / 10 {
+            check_no_panic(&get_mutation(&code, &mut random)); 
+        }
+    }
+}
+
+#[test]
+fn independent_errors_are_reported_in_one_run() {
+    let locations: Vec<String> = get_diagnostics("tests/diagnostics/recovery.txt").iter()
+        .map(|(code, line, _)| format!("{}:{}", code, line))
+        .collect(); 
+
+    let expected = [
+        "STX019:3", "STX001:10", "STX002:6", "STX002:8", 
+        "STX003:2", "STX010:4", "STX008:6", "STX011:8", 
+    ]; 
+
+    assert_eq!(locations, expected); 
+}
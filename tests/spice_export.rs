@@ -0,0 +1,40 @@
+use std::fs; 
+
+mod common; 
+
+use common::run; 
+
+/// Runs `edesigner --spice` and compares the deck with the golden file
+fn check_spice(input: &str, args: &[&str], golden: &str) {
+    let output = run(&[&[input, "--spice"], args].concat()); 
+
+    let actual = String::from_utf8(output.stdout).unwrap(); 
+    let expected = fs::read_to_string(golden).unwrap(); 
+    assert_eq!(actual, expected, "SPICE deck of '{}' differs from '{}'", input, golden); 
+}
+
+#[test]
+fn exports_subcircuits_with_evaluated_instance_params() {
+    check_spice("examples/divider.txt", &[], "tests/spice/divider.cir"); 
+}
+
+#[test]
+fn exports_time_dependent_sources_as_behavioral_sources() {
+    check_spice("examples/rc.txt", &[], "tests/spice/rc.cir"); 
+}
+
+#[test]
+fn exports_diodes_with_models() {
+    check_spice("examples/rectifier.txt", &[], "tests/spice/rectifier.cir"); 
+}
+
+#[test]
+fn exports_functions_comments_and_local_ground() {
+    check_spice("tests/spice/stage.txt", &["-p", "A=2"], "tests/spice/stage.cir"); 
+}
+
+#[test]
+fn export_is_deterministic() {
+    let export = || run(&["tests/spice/stage.txt", "--spice", "-p", "A=2"]).stdout; 
+    assert_eq!(export(), export()); 
+}
@@ -0,0 +1,32 @@
+use std::fs; 
+mod common; 
+
+use common::run_to_strings as run; 
+
+/// Runs `edesigner --from-spice` and compares the source and the 
+/// diagnostics with the golden files
+fn check_import(deck: &str, golden: &str, golden_diagnostics: &str) {
+    let (source, diagnostics) = run(&[deck, "--from-spice"]); 
+    let expected = fs::read_to_string(golden).unwrap(); 
+    assert_eq!(source, expected, "source imported from '{}' differs from '{}'", deck, golden); 
+
+    let expected = fs::read_to_string(golden_diagnostics).unwrap(); 
+    assert_eq!(diagnostics, expected, "diagnostics of '{}' differ from '{}'", deck, golden_diagnostics); 
+}
+
+#[test]
+fn imports_subcircuits_models_and_params() {
+    check_import("tests/spice/amplifier.sp", "tests/spice/amplifier.txt", "tests/spice/amplifier.err"); 
+}
+
+#[test]
+fn reports_unsupported_cards_with_positions() {
+    check_import("tests/spice/unsupported.sp", "tests/spice/unsupported.txt", "tests/spice/unsupported.err"); 
+}
+
+#[test]
+fn imported_source_is_simulated() {
+    let (output, _) = run(&["tests/spice/amplifier.txt", "--op"]); 
+    assert!(output.starts_with("Operating point of Main"), "{}", output); 
+    assert!(output.contains("V(e)"), "{}", output); 
+}
@@ -0,0 +1,55 @@
+mod common; 
+
+use common::get_diagnostics; 
+
+fn check(file_name: &str, expected: &[(&str, usize, usize)]) {
+    let expected: Vec<(String, usize, usize)> = expected.iter()
+        .map(|x| (x.0.to_string(), x.1, x.2))
+        .collect(); 
+
+    assert_eq!(get_diagnostics(file_name), expected, "diagnostics of '{}'", file_name); 
+}
+
+#[test]
+fn missing_comma_between_pins() {
+    check("tests/syntax/missing_comma.txt", &[("STX021", 1, 12)]); 
+}
+
+#[test]
+fn number_as_pin_of_element() {
+    check("tests/syntax/number_pin.txt", &[("STX020", 1, 13)]); 
+}
+
+#[test]
+fn trailing_comma_in_parameters() {
+    check("tests/syntax/trailing_comma.txt", &[]); 
+}
+
+#[test]
+fn example_has_no_syntax_errors() {
+    let syntax_errors: Vec<(String, usize, usize)> = get_diagnostics("examples/code.txt").into_iter()
+        .filter(|x| x.0.starts_with("STX"))
+        .collect(); 
+
+    assert_eq!(syntax_errors, vec![]); 
+}
+
+#[test]
+fn empty_argument_in_parameters() {
+    check("tests/syntax/empty_argument.txt", &[("STX020", 1, 12), ("STX020", 2, 15)]); 
+}
+
+#[test]
+fn missing_assignment_in_entry() {
+    check("tests/syntax/missing_assignment.txt", &[("STX024", 3, 14)]); 
+}
+
+#[test]
+fn empty_parameter_expressions() {
+    check("tests/syntax/empty_parameter.txt", &[("STX025", 3, 29), ("STX025", 4, 22)]); 
+}
+
+#[test]
+fn unbalanced_brackets_in_parameters() {
+    check("tests/syntax/unbalanced_brackets.txt", &[("STX023", 3, 25), ("STX022", 4, 30)]); 
+}